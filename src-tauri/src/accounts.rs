@@ -1,7 +1,9 @@
-use rusqlite::{params, Connection};
-use tauri::AppHandle;
+use std::collections::HashMap;
+use chrono::Local;
+use rusqlite::{params, Connection, OptionalExtension};
+use tauri::{AppHandle, State};
 use crate::models::Account;
-use crate::utils::get_db_path;
+use crate::utils::DbPoolHandle;
 use crate::trigger_data_change_sync;
 
 #[derive(serde::Serialize)]
@@ -12,144 +14,208 @@ pub struct AccountImportSettings {
     pub created_at: String,
 }
 
-#[tauri::command]
-pub fn get_accounts(app: AppHandle) -> Result<Vec<Account>, String> {
-    let path = get_db_path(&app);
-    let conn = Connection::open(path).map_err(|e| e.to_string())?;
-    
-    let mut stmt = conn.prepare("SELECT id, name, type, description, created_at FROM accounts ORDER BY name").map_err(|e| e.to_string())?;
-    let rows = stmt.query_map([], |row| {
-        let id: i64 = row.get(0)?;
-        let name: String = row.get(1)?;
-        let account_type: String = row.get(2)?;
-        let description: Option<String> = row.get(3)?;
-        let created_at: String = row.get(4)?;
-        
-        // 실시간 잔액 계산 (계좌 타입과 거래 타입에 따라)
-        // 
-        // BALANCE CALCULATION LOGIC:
-        // =========================
-        // 
-        // CREDIT CARDS:
-        // - Expense transactions are stored as NEGATIVE amounts (e.g., -$1000)
-        // - Credit card debt should be NEGATIVE (you owe money)
-        // - We use the amount AS-IS (no ABS conversion) to preserve the sign
-        // - Example: $1000 expense = -$1000 stored = -$1000 balance (debt)
-        // 
-        // OTHER ACCOUNTS (Checking, Savings, Investment, Other):
-        // - Expense transactions are stored as NEGATIVE amounts (e.g., -$1000)
-        // - We convert to positive for display using -ABS(amount)
-        // - Example: $1000 expense = -$1000 stored = -$1000 balance (money spent)
-        // 
-        // TRANSFER transactions:
-        // - Use amount AS-IS for all account types
-        // - Backend handles sign conversion based on departure/arrival accounts
-        // 
-        // ADJUST transactions:
-        // - For Credit: use amount AS-IS
-        // - For Others: use ABS(amount) with proper sign based on category
-        // 
-        let balance: f64 = conn.query_row(
-            "SELECT IFNULL(SUM(CASE 
-                WHEN a.type = 'Credit' THEN
-                    CASE
-                        WHEN t.type = 'Expense' THEN amount
-                        WHEN t.type = 'Income' THEN amount
-                        WHEN t.type = 'Adjust' AND c.name = 'Add' THEN ABS(amount)
-                        WHEN t.type = 'Adjust' AND c.name = 'Subtract' THEN -ABS(amount)
-                        WHEN t.type = 'Transfer' THEN amount
-                        ELSE 0
-                    END
-                ELSE
-                    -- Checking, Savings, Investment, Other 계좌는 모두 동일하게 처리
-                    CASE
-                        WHEN t.type = 'Expense' THEN -ABS(amount)
-                        WHEN t.type = 'Income' THEN ABS(amount)
-                        WHEN t.type = 'Adjust' AND c.name = 'Add' THEN ABS(amount)
-                        WHEN t.type = 'Adjust' AND c.name = 'Subtract' THEN -ABS(amount)
-                        WHEN t.type = 'Transfer' THEN amount
-                        ELSE 0
-                    END
-                END), 0) 
-            FROM transactions t
-            LEFT JOIN categories c ON t.category_id = c.id
-            LEFT JOIN accounts a ON t.account_id = a.id
-            WHERE t.account_id = ?1",
-            params![id],
-            |r| r.get(0),
-        ).unwrap_or(0.0);
-        
-        Ok(Account { id, name, account_type, balance, description, created_at })
-    }).map_err(|e| e.to_string())?;
-    
+/// The app's base currency. Every account balance is reducible to this unit via
+/// `exchange_rates`; accounts already denominated in it always convert at 1.0.
+pub const BASE_CURRENCY: &str = "USD";
+
+// 실시간 잔액 계산 (계좌 타입과 거래 타입에 따라)
+//
+// BALANCE CALCULATION LOGIC:
+// =========================
+//
+// CREDIT CARDS:
+// - Expense transactions are stored as NEGATIVE amounts (e.g., -$1000)
+// - Credit card debt should be NEGATIVE (you owe money)
+// - We use the amount AS-IS (no ABS conversion) to preserve the sign
+// - Example: $1000 expense = -$1000 stored = -$1000 balance (debt)
+//
+// OTHER ACCOUNTS (Checking, Savings, Investment, Other):
+// - Expense transactions are stored as NEGATIVE amounts (e.g., -$1000)
+// - Expense uses -ABS(amount) and Income uses ABS(amount), whatever sign was stored
+// - Example: $1000 expense = -$1000 stored = -$1000 balance (money spent)
+//
+// TRANSFER transactions:
+// - Use amount AS-IS for all account types
+// - Backend handles sign conversion based on departure/arrival accounts
+//
+// ADJUST transactions (all account types):
+// - ABS(amount) for the 'Add' category, -ABS(amount) for 'Subtract'
+//
+// FEES: ABS(fee) is subtracted from every transaction's value.
+// The per-row value is `net_value` in the `v_transactions_net` view.
+const BALANCE_SUM_SQL: &str = "SELECT v.account_id,
+        IFNULL(SUM(v.net_value), 0) AS native_balance,
+        IFNULL(SUM(v.net_value *
+            -- Rate effective on the transaction's date: the most recent exchange_rates
+            -- row on or before it, falling back to 1.0 when the account is already in
+            -- the base currency or no earlier rate has been recorded.
+            CASE
+                WHEN a.currency = ?1 THEN 1.0
+                ELSE IFNULL((
+                    SELECT er.rate FROM exchange_rates er
+                    WHERE er.currency = a.currency AND er.date <= v.date
+                    ORDER BY er.date DESC LIMIT 1
+                ), 1.0)
+            END
+        ), 0) AS base_balance
+    FROM v_transactions_net v
+    LEFT JOIN accounts a ON v.account_id = a.id
+    GROUP BY v.account_id";
+
+/// Recomputes a single account's native-currency balance as of a given date, reading the
+/// same per-row signed amounts as [`BALANCE_SUM_SQL`] from [`crate::migrations`]'s
+/// `v_transactions_net` view. Backs [`crate::reconciliation::verify_balance_assertions`].
+pub const BALANCE_ASSERTION_SQL: &str = "SELECT IFNULL(SUM(net_value), 0)
+    FROM v_transactions_net
+    WHERE account_id = ?1 AND date <= ?2";
+
+/// Fetches all accounts along with their balances using a single grouped query for the
+/// balances instead of one `SUM` per account (the old N+1 pattern).
+fn fetch_accounts(conn: &Connection) -> Result<Vec<Account>, String> {
+    let mut balances: HashMap<i64, (f64, f64)> = HashMap::new();
+    {
+        let mut stmt = conn.prepare(BALANCE_SUM_SQL).map_err(|e| e.to_string())?;
+        let rows = stmt
+            .query_map(params![BASE_CURRENCY], |row| {
+                Ok((row.get::<_, i64>(0)?, row.get::<_, f64>(1)?, row.get::<_, f64>(2)?))
+            })
+            .map_err(|e| e.to_string())?;
+        for row in rows {
+            let (account_id, native_balance, base_balance) = row.map_err(|e| e.to_string())?;
+            balances.insert(account_id, (native_balance, base_balance));
+        }
+    }
+
+    let mut stmt = conn
+        .prepare("SELECT id, name, type, description, created_at, currency, apr, min_payment_floor, min_payment_pct FROM accounts ORDER BY name")
+        .map_err(|e| e.to_string())?;
+    let rows = stmt
+        .query_map([], |row| {
+            let id: i64 = row.get(0)?;
+            let name: String = row.get(1)?;
+            let account_type: String = row.get(2)?;
+            let description: Option<String> = row.get(3)?;
+            let created_at: String = row.get(4)?;
+            let currency: String = row.get(5)?;
+            let apr: Option<f64> = row.get(6)?;
+            let min_payment_floor: Option<f64> = row.get(7)?;
+            let min_payment_pct: Option<f64> = row.get(8)?;
+            Ok((id, name, account_type, description, created_at, currency, apr, min_payment_floor, min_payment_pct))
+        })
+        .map_err(|e| e.to_string())?;
+
     let mut accounts = Vec::new();
-    for account in rows {
-        accounts.push(account.map_err(|e| e.to_string())?);
+    for row in rows {
+        let (id, name, account_type, description, created_at, currency, apr, min_payment_floor, min_payment_pct) = row.map_err(|e| e.to_string())?;
+        let (balance, base_balance) = balances.get(&id).copied().unwrap_or((0.0, 0.0));
+        accounts.push(Account {
+            id, name, account_type, balance, description, created_at, currency, base_balance,
+            apr, min_payment_floor, min_payment_pct,
+        });
     }
     Ok(accounts)
 }
 
 #[tauri::command]
-pub async fn create_account(app: AppHandle, name: String, account_type: String, balance: Option<f64>, description: Option<String>) -> Result<Vec<Account>, String> {
-    let path = get_db_path(&app);
-    let conn = Connection::open(path).map_err(|e| e.to_string())?;
-    
-    let initial_balance = balance.unwrap_or(0.0);
+pub fn get_accounts(pool: State<'_, DbPoolHandle>) -> Result<Vec<Account>, String> {
+    let conn = pool.current().get().map_err(|e| e.to_string())?;
+    fetch_accounts(&conn)
+}
+
+/// Makes the persisted `accounts.balance` column agree with `v_transactions_net` by
+/// recomputing it as a pure `SUM` over that account's transaction rows, instead of the old
+/// pattern of mutating it with incremental `balance = balance + net` deltas on every
+/// create/update/delete. Call this at the end of any command that changes `transactions` so a
+/// crash between the transaction write and this recompute can never leave the two tables
+/// disagreeing — re-running it is always safe since it never reads the column it's about to
+/// overwrite.
+pub fn recompute_balances(conn: &Connection) -> Result<(), String> {
     conn.execute(
-        "INSERT INTO accounts (name, type, balance, description) VALUES (?1, ?2, ?3, ?4)",
-        params![name, account_type, initial_balance, description],
-    )
-    .map_err(|e| e.to_string())?;
-    
+        "UPDATE accounts SET balance = (
+            SELECT COALESCE(SUM(net_value), 0) FROM v_transactions_net WHERE account_id = accounts.id
+        )",
+        [],
+    ).map_err(|e| e.to_string())?;
+    Ok(())
+}
+
+#[tauri::command]
+pub fn recompute_account_balances(pool: State<'_, DbPoolHandle>) -> Result<Vec<Account>, String> {
+    let conn = pool.current().get().map_err(|e| e.to_string())?;
+    recompute_balances(&conn)?;
+    fetch_accounts(&conn)
+}
+
+#[tauri::command]
+pub async fn create_account(app: AppHandle, pool: State<'_, DbPoolHandle>, name: String, account_type: String, balance: Option<f64>, description: Option<String>, currency: Option<String>) -> Result<Vec<Account>, String> {
+    {
+        let conn = pool.current().get().map_err(|e| e.to_string())?;
+        let initial_balance = balance.unwrap_or(0.0);
+        let currency = currency.unwrap_or_else(|| BASE_CURRENCY.to_string());
+        conn.execute(
+            "INSERT INTO accounts (name, type, balance, description, currency) VALUES (?1, ?2, ?3, ?4, ?5)",
+            params![name, account_type, initial_balance, description, currency],
+        )
+        .map_err(|e| e.to_string())?;
+    }
+
     // Trigger sync after data change
     trigger_data_change_sync(&app).await;
-    
-    get_accounts(app)
+
+    let conn = pool.current().get().map_err(|e| e.to_string())?;
+    fetch_accounts(&conn)
 }
 
 #[tauri::command]
-pub async fn update_account(app: AppHandle, account: Account) -> Result<Vec<Account>, String> {
-    let path = get_db_path(&app);
-    let conn = Connection::open(path).map_err(|e| e.to_string())?;
-    
-    conn.execute(
-        "UPDATE accounts SET name = ?1, type = ?2, description = ?3 WHERE id = ?4",
-        params![account.name, account.account_type, account.description, account.id],
-    )
-    .map_err(|e| e.to_string())?;
-    
+pub async fn update_account(app: AppHandle, pool: State<'_, DbPoolHandle>, account: Account) -> Result<Vec<Account>, String> {
+    {
+        let conn = pool.current().get().map_err(|e| e.to_string())?;
+        conn.execute(
+            "UPDATE accounts SET name = ?1, type = ?2, description = ?3, currency = ?4, apr = ?5, min_payment_floor = ?6, min_payment_pct = ?7 WHERE id = ?8",
+            params![
+                account.name, account.account_type, account.description, account.currency,
+                account.apr, account.min_payment_floor, account.min_payment_pct, account.id,
+            ],
+        )
+        .map_err(|e| e.to_string())?;
+    }
+
     // Trigger sync after data change
     trigger_data_change_sync(&app).await;
-    
-    get_accounts(app)
+
+    let conn = pool.current().get().map_err(|e| e.to_string())?;
+    fetch_accounts(&conn)
 }
 
 #[tauri::command]
-pub async fn delete_account(app: AppHandle, id: i64) -> Result<Vec<Account>, String> {
-    let path = get_db_path(&app);
-    let conn = Connection::open(path).map_err(|e| e.to_string())?;
-    
-    conn.execute(
-        "DELETE FROM accounts WHERE id = ?1",
-        params![id],
-    )
-    .map_err(|e| e.to_string())?;
-    
+pub async fn delete_account(app: AppHandle, pool: State<'_, DbPoolHandle>, id: i64) -> Result<Vec<Account>, String> {
+    {
+        let conn = pool.current().get().map_err(|e| e.to_string())?;
+        conn.execute(
+            "DELETE FROM accounts WHERE id = ?1",
+            params![id],
+        )
+        .map_err(|e| e.to_string())?;
+        // Deleting an account leaves its transactions' attachments orphaned (no ON DELETE
+        // CASCADE enforcement, since foreign_keys is never turned on for this connection).
+        crate::attachments::delete_orphaned_attachments(&conn)?;
+    }
+
     // Trigger sync after data change
     trigger_data_change_sync(&app).await;
-    
-    get_accounts(app)
+
+    let conn = pool.current().get().map_err(|e| e.to_string())?;
+    fetch_accounts(&conn)
 }
 
 #[tauri::command]
-pub fn get_account_import_settings(app: AppHandle, account_id: i64) -> Result<AccountImportSettings, String> {
-    let path = get_db_path(&app);
-    let conn = Connection::open(path).map_err(|e| e.to_string())?;
-    
+pub fn get_account_import_settings(pool: State<'_, DbPoolHandle>, account_id: i64) -> Result<AccountImportSettings, String> {
+    let conn = pool.current().get().map_err(|e| e.to_string())?;
+
     let mut stmt = conn.prepare(
         "SELECT id, account_id, csv_sign_logic, created_at FROM account_import_settings WHERE account_id = ?1"
     ).map_err(|e| e.to_string())?;
-    
+
     let settings = stmt.query_row(params![account_id], |row| {
         Ok(AccountImportSettings {
             id: row.get(0)?,
@@ -158,34 +224,180 @@ pub fn get_account_import_settings(app: AppHandle, account_id: i64) -> Result<Ac
             created_at: row.get(3)?,
         })
     }).map_err(|e| e.to_string())?;
-    
+
     Ok(settings)
 }
 
 #[tauri::command]
-pub fn update_account_import_settings(app: AppHandle, account_id: i64, csv_sign_logic: String) -> Result<AccountImportSettings, String> {
-    let path = get_db_path(&app);
-    let conn = Connection::open(path).map_err(|e| e.to_string())?;
-    
+pub fn update_account_import_settings(pool: State<'_, DbPoolHandle>, account_id: i64, csv_sign_logic: String) -> Result<AccountImportSettings, String> {
+    let conn = pool.current().get().map_err(|e| e.to_string())?;
+
     conn.execute(
         "INSERT INTO account_import_settings (account_id, csv_sign_logic) VALUES (?1, ?2)
          ON CONFLICT(account_id) DO UPDATE SET csv_sign_logic = ?2",
         params![account_id, csv_sign_logic],
     ).map_err(|e| e.to_string())?;
-    
-    get_account_import_settings(app, account_id)
+
+    get_account_import_settings(pool, account_id)
 }
 
 #[tauri::command]
-pub fn get_csv_sign_logic_for_account(app: AppHandle, account_id: i64) -> Result<String, String> {
-    let path = get_db_path(&app);
-    let conn = Connection::open(path).map_err(|e| e.to_string())?;
-    
+pub fn get_csv_sign_logic_for_account(pool: State<'_, DbPoolHandle>, account_id: i64) -> Result<String, String> {
+    let conn = pool.current().get().map_err(|e| e.to_string())?;
+
     let csv_sign_logic: String = conn.query_row(
         "SELECT csv_sign_logic FROM account_import_settings WHERE account_id = ?1",
         params![account_id],
         |row| row.get(0),
     ).unwrap_or_else(|_| "standard".to_string());
-    
+
     Ok(csv_sign_logic)
-} 
\ No newline at end of file
+}
+
+#[derive(serde::Serialize)]
+pub struct ExchangeRate {
+    pub id: i64,
+    pub currency: String,
+    pub date: String,
+    pub rate: f64,
+}
+
+/// Records the rate to convert `currency` into [`BASE_CURRENCY`] effective on `date`.
+/// Re-running for the same `(currency, date)` overwrites the previously stored rate.
+#[tauri::command]
+pub fn set_exchange_rate(pool: State<'_, DbPoolHandle>, currency: String, date: String, rate: f64) -> Result<Vec<ExchangeRate>, String> {
+    let conn = pool.current().get().map_err(|e| e.to_string())?;
+    conn.execute(
+        "INSERT INTO exchange_rates (currency, date, rate) VALUES (?1, ?2, ?3)
+         ON CONFLICT(currency, date) DO UPDATE SET rate = ?3",
+        params![currency, date, rate],
+    ).map_err(|e| e.to_string())?;
+    get_exchange_rates(pool, currency)
+}
+
+#[tauri::command]
+pub fn get_exchange_rates(pool: State<'_, DbPoolHandle>, currency: String) -> Result<Vec<ExchangeRate>, String> {
+    let conn = pool.current().get().map_err(|e| e.to_string())?;
+    let mut stmt = conn.prepare(
+        "SELECT id, currency, date, rate FROM exchange_rates WHERE currency = ?1 ORDER BY date"
+    ).map_err(|e| e.to_string())?;
+    let rows = stmt.query_map(params![currency], |row| {
+        Ok(ExchangeRate {
+            id: row.get(0)?,
+            currency: row.get(1)?,
+            date: row.get(2)?,
+            rate: row.get(3)?,
+        })
+    }).map_err(|e| e.to_string())?;
+    let mut rates = Vec::new();
+    for r in rows {
+        rates.push(r.map_err(|e| e.to_string())?);
+    }
+    Ok(rates)
+}
+
+/// Upserts a batch of freshly-fetched quotes (currency -> rate against `base_currency`),
+/// stamped with today's date, into the `exchange_rates` cache. The actual FX lookup is the
+/// caller's responsibility (this crate has no HTTP client wired in) — this command is just
+/// the cache-write half of the refresh, playing the role zcash-sync's `prices::Quote` cache
+/// plays for its own rate table. All upserts run inside a single `conn.transaction()` so a
+/// failure partway through a large rate batch doesn't leave the cache half-refreshed.
+#[tauri::command]
+pub fn refresh_fx_rates(pool: State<'_, DbPoolHandle>, base_currency: String, rates: HashMap<String, f64>) -> Result<Vec<ExchangeRate>, String> {
+    let mut conn = pool.current().get().map_err(|e| e.to_string())?;
+    let today = Local::now().format("%Y-%m-%d").to_string();
+
+    let tx = conn.transaction().map_err(|e| e.to_string())?;
+    for (currency, rate) in &rates {
+        if currency == &base_currency {
+            continue;
+        }
+        tx.execute(
+            "INSERT INTO exchange_rates (currency, date, rate) VALUES (?1, ?2, ?3)
+             ON CONFLICT(currency, date) DO UPDATE SET rate = ?3",
+            params![currency, today, rate],
+        ).map_err(|e| e.to_string())?;
+    }
+    tx.commit().map_err(|e| e.to_string())?;
+
+    let mut stmt = conn
+        .prepare("SELECT id, currency, date, rate FROM exchange_rates WHERE date = ?1 ORDER BY currency")
+        .map_err(|e| e.to_string())?;
+    let rows = stmt.query_map(params![today], |row| {
+        Ok(ExchangeRate {
+            id: row.get(0)?,
+            currency: row.get(1)?,
+            date: row.get(2)?,
+            rate: row.get(3)?,
+        })
+    }).map_err(|e| e.to_string())?;
+    let mut refreshed = Vec::new();
+    for r in rows {
+        refreshed.push(r.map_err(|e| e.to_string())?);
+    }
+    Ok(refreshed)
+}
+
+/// A single account's balance, converted into [`get_net_worth`]'s requested base currency.
+/// `converted` is `false` when no quote was available for the account's currency, in which
+/// case `converted_balance` falls back to the native balance rather than silently mixing
+/// currencies into the total.
+#[derive(serde::Serialize)]
+pub struct ConvertedBalance {
+    pub account_id: i64,
+    pub name: String,
+    pub currency: String,
+    pub native_balance: f64,
+    pub converted_balance: f64,
+    pub rate: f64,
+    pub converted: bool,
+}
+
+#[derive(serde::Serialize)]
+pub struct NetWorth {
+    pub base_currency: String,
+    pub accounts: Vec<ConvertedBalance>,
+    pub total: f64,
+}
+
+/// Converts every account's computed balance into `base_currency` using the most recent
+/// quote on or before today, and sums the result. Accounts already in `base_currency` convert
+/// at 1.0; accounts with no quote available fall back to their native balance flagged
+/// `converted: false` so the UI can warn instead of silently summing mismatched currencies.
+#[tauri::command]
+pub fn get_net_worth(pool: State<'_, DbPoolHandle>, base_currency: String) -> Result<NetWorth, String> {
+    let conn = pool.current().get().map_err(|e| e.to_string())?;
+    let accounts = fetch_accounts(&conn)?;
+    let today = Local::now().format("%Y-%m-%d").to_string();
+
+    let mut converted_accounts = Vec::new();
+    let mut total = 0.0;
+    for account in accounts {
+        let (rate, converted) = if account.currency == base_currency {
+            (1.0, true)
+        } else {
+            let quote: Option<f64> = conn.query_row(
+                "SELECT rate FROM exchange_rates WHERE currency = ?1 AND date <= ?2 ORDER BY date DESC LIMIT 1",
+                params![account.currency, today],
+                |row| row.get(0),
+            ).optional().map_err(|e| e.to_string())?;
+            match quote {
+                Some(rate) => (rate, true),
+                None => (1.0, false),
+            }
+        };
+        let converted_balance = if converted { account.balance * rate } else { account.balance };
+        total += converted_balance;
+        converted_accounts.push(ConvertedBalance {
+            account_id: account.id,
+            name: account.name,
+            currency: account.currency,
+            native_balance: account.balance,
+            converted_balance,
+            rate,
+            converted,
+        });
+    }
+
+    Ok(NetWorth { base_currency, accounts: converted_accounts, total })
+}
@@ -0,0 +1,193 @@
+use rusqlite::{params, Connection};
+use sha2::{Digest, Sha256};
+use tauri::{AppHandle, State};
+
+use crate::utils::{get_attachments_dir, DbPoolHandle};
+
+/// A receipt or document attached to a transaction, following qualinvest's `doc_path` idea —
+/// unlike [`crate::transactions::save_transaction_attachment`]'s single `attachment_path`
+/// column, a transaction can have any number of these.
+#[derive(Debug, serde::Serialize, serde::Deserialize, Clone)]
+pub struct Attachment {
+    pub id: i64,
+    pub transaction_id: i64,
+    pub original_filename: String,
+    pub stored_path: String,
+    pub mime_type: Option<String>,
+    /// SHA-256 of the file contents (`migrations::m0037_attachment_hash`), `None` for rows
+    /// attached before this column existed. Used by [`add_attachment`] to reuse an existing
+    /// copy on disk instead of storing the same file twice.
+    pub sha256: Option<String>,
+    pub created_at: String,
+}
+
+fn fetch_attachments(conn: &Connection, transaction_id: i64) -> Result<Vec<Attachment>, String> {
+    let mut stmt = conn.prepare(
+        "SELECT id, transaction_id, original_filename, stored_path, mime_type, sha256, created_at
+         FROM attachments WHERE transaction_id = ?1 ORDER BY created_at"
+    ).map_err(|e| e.to_string())?;
+    let rows = stmt.query_map(params![transaction_id], |row| {
+        Ok(Attachment {
+            id: row.get(0)?,
+            transaction_id: row.get(1)?,
+            original_filename: row.get(2)?,
+            stored_path: row.get(3)?,
+            mime_type: row.get(4)?,
+            sha256: row.get(5)?,
+            created_at: row.get(6)?,
+        })
+    }).map_err(|e| e.to_string())?;
+    let mut attachments = Vec::new();
+    for r in rows {
+        attachments.push(r.map_err(|e| e.to_string())?);
+    }
+    Ok(attachments)
+}
+
+fn sha256_hex(data: &[u8]) -> String {
+    let mut hasher = Sha256::new();
+    hasher.update(data);
+    format!("{:x}", hasher.finalize())
+}
+
+#[tauri::command]
+pub fn list_attachments(pool: State<'_, DbPoolHandle>, transaction_id: i64) -> Result<Vec<Attachment>, String> {
+    let conn = pool.current().get().map_err(|e| e.to_string())?;
+    fetch_attachments(&conn, transaction_id)
+}
+
+/// Copies `source_path` into the app's local `attachments/` directory (renamed to avoid
+/// collisions) and records its metadata against `transaction_id`. If a file with the same
+/// SHA-256 is already stored (e.g. the same receipt attached to a second transaction), the
+/// existing copy on disk is reused instead of writing a duplicate.
+#[tauri::command]
+pub fn add_attachment(
+    app: AppHandle,
+    pool: State<'_, DbPoolHandle>,
+    transaction_id: i64,
+    source_path: String,
+) -> Result<Vec<Attachment>, String> {
+    let source = std::path::Path::new(&source_path);
+    let original_filename = source
+        .file_name()
+        .and_then(|n| n.to_str())
+        .ok_or_else(|| "Invalid source path".to_string())?
+        .to_string();
+    let extension = source.extension().and_then(|e| e.to_str()).unwrap_or("");
+    let mime_type = mime_type_for_extension(extension);
+
+    let contents = std::fs::read(source).map_err(|e| format!("Failed to read attachment: {}", e))?;
+    let hash = sha256_hex(&contents);
+
+    let conn = pool.current().get().map_err(|e| e.to_string())?;
+    let existing_path: Option<String> = conn
+        .query_row(
+            "SELECT stored_path FROM attachments WHERE sha256 = ?1 LIMIT 1",
+            params![hash],
+            |row| row.get(0),
+        )
+        .ok();
+
+    let stored_path_str = match existing_path {
+        Some(path) => path,
+        None => {
+            let attachments_dir = get_attachments_dir(&app);
+            let stored_file_name = format!(
+                "{}_{}",
+                chrono::Utc::now().format("%Y%m%d%H%M%S%3f"),
+                original_filename
+            );
+            let stored_path = attachments_dir.join(&stored_file_name);
+            std::fs::write(&stored_path, &contents).map_err(|e| format!("Failed to copy attachment: {}", e))?;
+            stored_path.to_string_lossy().to_string()
+        }
+    };
+
+    conn.execute(
+        "INSERT INTO attachments (transaction_id, original_filename, stored_path, mime_type, sha256) VALUES (?1, ?2, ?3, ?4, ?5)",
+        params![transaction_id, original_filename, stored_path_str, mime_type, hash],
+    ).map_err(|e| e.to_string())?;
+
+    fetch_attachments(&conn, transaction_id)
+}
+
+#[tauri::command]
+pub fn open_attachment(pool: State<'_, DbPoolHandle>, id: i64) -> Result<(), String> {
+    let conn = pool.current().get().map_err(|e| e.to_string())?;
+    let stored_path: String = conn
+        .query_row("SELECT stored_path FROM attachments WHERE id = ?1", params![id], |row| row.get(0))
+        .map_err(|e| e.to_string())?;
+    open::that(&stored_path).map_err(|e| format!("Failed to open attachment: {}", e))
+}
+
+/// Removes the database row, and the stored file along with it — unless another attachment
+/// row still points at the same `stored_path` (the content-hash dedup case in [`add_attachment`]),
+/// in which case the shared file is left in place for that other row.
+#[tauri::command]
+pub fn delete_attachment(pool: State<'_, DbPoolHandle>, id: i64) -> Result<Vec<Attachment>, String> {
+    let conn = pool.current().get().map_err(|e| e.to_string())?;
+    let (transaction_id, stored_path): (i64, String) = conn
+        .query_row(
+            "SELECT transaction_id, stored_path FROM attachments WHERE id = ?1",
+            params![id],
+            |row| Ok((row.get(0)?, row.get(1)?)),
+        )
+        .map_err(|e| e.to_string())?;
+
+    conn.execute("DELETE FROM attachments WHERE id = ?1", params![id]).map_err(|e| e.to_string())?;
+
+    let still_referenced: bool = conn
+        .query_row(
+            "SELECT EXISTS(SELECT 1 FROM attachments WHERE stored_path = ?1)",
+            params![stored_path],
+            |row| row.get(0),
+        )
+        .map_err(|e| e.to_string())?;
+    if !still_referenced {
+        let _ = std::fs::remove_file(&stored_path);
+    }
+
+    fetch_attachments(&conn, transaction_id)
+}
+
+/// Removes every attachment (row and file) for transactions that no longer exist — used
+/// alongside account/transaction deletion so orphaned files never accumulate on disk.
+pub fn delete_orphaned_attachments(conn: &Connection) -> Result<(), String> {
+    let mut stmt = conn.prepare(
+        "SELECT a.id, a.stored_path FROM attachments a
+         LEFT JOIN transactions t ON a.transaction_id = t.id
+         WHERE t.id IS NULL"
+    ).map_err(|e| e.to_string())?;
+    let orphans: Vec<(i64, String)> = stmt
+        .query_map([], |row| Ok((row.get(0)?, row.get(1)?)))
+        .map_err(|e| e.to_string())?
+        .collect::<Result<_, _>>()
+        .map_err(|e| e.to_string())?;
+
+    for (id, stored_path) in orphans {
+        conn.execute("DELETE FROM attachments WHERE id = ?1", params![id]).map_err(|e| e.to_string())?;
+        let still_referenced: bool = conn
+            .query_row(
+                "SELECT EXISTS(SELECT 1 FROM attachments WHERE stored_path = ?1)",
+                params![stored_path],
+                |row| row.get(0),
+            )
+            .map_err(|e| e.to_string())?;
+        if !still_referenced {
+            let _ = std::fs::remove_file(&stored_path);
+        }
+    }
+    Ok(())
+}
+
+fn mime_type_for_extension(extension: &str) -> Option<String> {
+    let mime = match extension.to_lowercase().as_str() {
+        "pdf" => "application/pdf",
+        "png" => "image/png",
+        "jpg" | "jpeg" => "image/jpeg",
+        "gif" => "image/gif",
+        "heic" => "image/heic",
+        _ => return None,
+    };
+    Some(mime.to_string())
+}
@@ -1,28 +1,275 @@
-use rusqlite::Connection;
+use rusqlite::{params, Connection, OptionalExtension, ToSql, Transaction};
+use std::collections::HashMap;
 use std::fs;
-use std::path::Path;
-use tauri::AppHandle;
+use std::io::{Read, Write};
+use std::path::{Component, Path, PathBuf};
+use tauri::{AppHandle, Emitter};
 use chrono::Local;
+use serde_json::Value;
+use argon2::Argon2;
+use chacha20poly1305::{aead::{Aead, KeyInit}, ChaCha20Poly1305, Key, Nonce, XChaCha20Poly1305, XNonce};
+use flate2::{read::GzDecoder, write::GzEncoder, Compression};
+use rand::RngCore;
+use sha2::{Digest, Sha256};
+use base64::{engine::general_purpose::STANDARD as BASE64, Engine};
 
 use crate::utils::{get_db_path, get_onedrive_path, get_onedrive_backups_dir};
 
+/// Default read/write chunk size for [`read_with_progress`]/[`write_with_progress`] when a
+/// command's caller doesn't override it — large enough to keep syscall overhead low, small
+/// enough that a slow cloud-synced folder still emits several progress events per second.
+const DEFAULT_PROGRESS_CHUNK_SIZE: usize = 1024 * 1024;
+
+/// Emitted via `AppHandle::emit("backup_progress", ..)` while [`backup_database`],
+/// [`restore_database`], [`export_database`], and [`import_database`] read/write large files in
+/// chunks, so the frontend can drive a determinate progress bar instead of freezing on a single
+/// blocking call. `phase` names the step underway (e.g. `"reading"`, `"compressing"`,
+/// `"writing"`); `bytes_total` is `0` when the step has no meaningful byte count (e.g. compression).
+#[derive(Clone, serde::Serialize)]
+pub struct BackupProgress {
+    pub phase: String,
+    pub bytes_done: u64,
+    pub bytes_total: u64,
+}
+
+fn emit_progress(app: &AppHandle, phase: &str, bytes_done: u64, bytes_total: u64) {
+    let _ = app.emit("backup_progress", BackupProgress {
+        phase: phase.to_string(),
+        bytes_done,
+        bytes_total,
+    });
+}
+
+/// Reads `path` in `chunk_size`-sized chunks, emitting a `"backup_progress"` event after each
+/// one so the frontend can track bytes-read against the file's total size.
+fn read_with_progress(app: &AppHandle, path: &Path, phase: &str, chunk_size: usize) -> Result<Vec<u8>, String> {
+    let total = fs::metadata(path).map_err(|e| e.to_string())?.len();
+    let mut file = fs::File::open(path).map_err(|e| e.to_string())?;
+    let mut data = Vec::with_capacity(total as usize);
+    let mut buf = vec![0u8; chunk_size.max(1)];
+    let mut done: u64 = 0;
+
+    loop {
+        let read = file.read(&mut buf).map_err(|e| e.to_string())?;
+        if read == 0 {
+            break;
+        }
+        data.extend_from_slice(&buf[..read]);
+        done += read as u64;
+        emit_progress(app, phase, done, total);
+    }
+
+    Ok(data)
+}
+
+/// Writes `data` to `path` in `chunk_size`-sized chunks, emitting a `"backup_progress"` event
+/// after each one. Not itself durable (no temp-file/rename dance) — [`write_backup_file`] still
+/// owns that for callers that need crash-safety; this is purely for progress feedback on a
+/// single large write.
+fn write_with_progress(app: &AppHandle, path: &Path, data: &[u8], phase: &str, chunk_size: usize) -> Result<(), String> {
+    let total = data.len() as u64;
+    let mut file = fs::File::create(path).map_err(|e| e.to_string())?;
+    let mut done: u64 = 0;
+
+    for chunk in data.chunks(chunk_size.max(1)) {
+        file.write_all(chunk).map_err(|e| e.to_string())?;
+        done += chunk.len() as u64;
+        emit_progress(app, phase, done, total);
+    }
+
+    Ok(())
+}
+
 #[derive(serde::Serialize)]
 pub struct BackupInfo {
     pub timestamp: String,
     pub file_size: u64,
     pub version: String,
     pub is_compressed: bool,
+    pub is_encrypted: bool,
+}
+
+/// Magic bytes identifying a `backup_database`-produced encrypted container, so
+/// `restore_database` can tell it apart from a raw SQLite file (which starts with SQLite's own
+/// `"SQLite format 3\0"` header) without needing a passphrase hint from the caller. `_V1` is the
+/// original single-shot ChaCha20-Poly1305 format ([`decrypt_db_bytes_v1`]) — still readable so
+/// backups written before the streaming format existed keep restoring, but [`encrypt_db_bytes`]
+/// only ever writes the current one.
+const ENCRYPTED_DB_BACKUP_MAGIC: &[u8; 8] = b"WNUTBKP2";
+const ENCRYPTED_DB_BACKUP_MAGIC_V1: &[u8; 8] = b"WNUTBKP1";
+const ENCRYPTED_DB_BACKUP_SALT_LEN: usize = 16;
+const ENCRYPTED_DB_BACKUP_NONCE_LEN: usize = 12;
+/// Leading, random part of each chunk's 24-byte XChaCha20-Poly1305 nonce; the remaining 4 bytes
+/// are that chunk's big-endian index (see [`encrypt_db_bytes`]).
+const STREAM_NONCE_PREFIX_LEN: usize = 20;
+/// Plaintext bytes sealed per AEAD chunk — large enough to keep the per-chunk tag/length
+/// overhead negligible, small enough that decrypting never has to hold much more than one
+/// chunk's worth of ciphertext in memory at a time.
+const STREAM_CHUNK_LEN: usize = 1024 * 1024;
+
+/// Seals the raw bytes of the `.db` file with a passphrase-derived key, chunked into
+/// [`STREAM_CHUNK_LEN`]-sized pieces each under its own XChaCha20-Poly1305 nonce (`prefix(20) |
+/// chunk_index_be(4)`), so a chunk can never be decrypted as if it were a different position in
+/// the stream — reordering or duplicating ciphertext chunks, or cutting one short, fails
+/// decryption instead of silently splicing in the wrong bytes. The stream has no end marker, so
+/// dropping whole chunks from its end is not detected by [`decrypt_db_bytes`]. Layout: `[magic(8) | salt(16) |
+/// nonce_prefix(20) | (len(4) | ciphertext)*]`.
+fn encrypt_db_bytes(passphrase: &str, plaintext: &[u8]) -> Result<Vec<u8>, String> {
+    let mut salt = [0u8; ENCRYPTED_DB_BACKUP_SALT_LEN];
+    rand::thread_rng().fill_bytes(&mut salt);
+    let key_bytes = derive_backup_key(passphrase, &salt)?;
+    let cipher = XChaCha20Poly1305::new(Key::from_slice(&key_bytes));
+
+    let mut nonce_prefix = [0u8; STREAM_NONCE_PREFIX_LEN];
+    rand::thread_rng().fill_bytes(&mut nonce_prefix);
+
+    let mut out = Vec::with_capacity(8 + salt.len() + nonce_prefix.len() + plaintext.len() + plaintext.len() / STREAM_CHUNK_LEN.max(1) * 20 + 20);
+    out.extend_from_slice(ENCRYPTED_DB_BACKUP_MAGIC);
+    out.extend_from_slice(&salt);
+    out.extend_from_slice(&nonce_prefix);
+
+    for (index, chunk) in plaintext.chunks(STREAM_CHUNK_LEN).enumerate() {
+        let nonce_bytes = stream_chunk_nonce(&nonce_prefix, index as u32);
+        let ciphertext = cipher
+            .encrypt(XNonce::from_slice(&nonce_bytes), chunk)
+            .map_err(|e| e.to_string())?;
+        out.extend_from_slice(&(ciphertext.len() as u32).to_be_bytes());
+        out.extend_from_slice(&ciphertext);
+    }
+    Ok(out)
+}
+
+/// Builds chunk `index`'s 24-byte XChaCha20-Poly1305 nonce from the stream's random prefix.
+fn stream_chunk_nonce(prefix: &[u8; STREAM_NONCE_PREFIX_LEN], index: u32) -> [u8; 24] {
+    let mut nonce = [0u8; 24];
+    nonce[..STREAM_NONCE_PREFIX_LEN].copy_from_slice(prefix);
+    nonce[STREAM_NONCE_PREFIX_LEN..].copy_from_slice(&index.to_be_bytes());
+    nonce
+}
+
+/// Reads just the first 8 bytes of `path`, for magic-header sniffing without loading a
+/// potentially large backup file into memory.
+fn read_magic_prefix(path: &Path) -> Result<[u8; 8], String> {
+    let mut file = fs::File::open(path).map_err(|e| e.to_string())?;
+    let mut buf = [0u8; 8];
+    file.read_exact(&mut buf).map_err(|e| e.to_string())?;
+    Ok(buf)
 }
 
+fn is_encrypted_backup_magic(bytes: &[u8; 8]) -> bool {
+    bytes == ENCRYPTED_DB_BACKUP_MAGIC || bytes == ENCRYPTED_DB_BACKUP_MAGIC_V1
+}
 
+/// Reverses [`encrypt_db_bytes`], or falls back to [`decrypt_db_bytes_v1`] for a backup written
+/// before the streaming format existed. Either path fails cleanly on an incorrect passphrase (an
+/// AEAD auth tag mismatch) without writing anything — callers must not touch the DB path until
+/// this returns `Ok`.
+fn decrypt_db_bytes(passphrase: &str, data: &[u8]) -> Result<Vec<u8>, String> {
+    if data.len() >= 8 && &data[0..8] == ENCRYPTED_DB_BACKUP_MAGIC_V1 {
+        return decrypt_db_bytes_v1(passphrase, data);
+    }
+
+    let header_len = 8 + ENCRYPTED_DB_BACKUP_SALT_LEN + STREAM_NONCE_PREFIX_LEN;
+    if data.len() < header_len || &data[0..8] != ENCRYPTED_DB_BACKUP_MAGIC {
+        return Err("Not an encrypted walnutbook backup".to_string());
+    }
+    let salt = &data[8..8 + ENCRYPTED_DB_BACKUP_SALT_LEN];
+    let mut nonce_prefix = [0u8; STREAM_NONCE_PREFIX_LEN];
+    nonce_prefix.copy_from_slice(&data[8 + ENCRYPTED_DB_BACKUP_SALT_LEN..header_len]);
+
+    let key_bytes = derive_backup_key(passphrase, salt)?;
+    let cipher = XChaCha20Poly1305::new(Key::from_slice(&key_bytes));
+
+    let mut plaintext = Vec::with_capacity(data.len() - header_len);
+    let mut cursor = header_len;
+    let mut index: u32 = 0;
+    while cursor < data.len() {
+        if cursor + 4 > data.len() {
+            return Err("Failed to decrypt backup: wrong passphrase or corrupted file".to_string());
+        }
+        let chunk_len = u32::from_be_bytes(data[cursor..cursor + 4].try_into().unwrap()) as usize;
+        cursor += 4;
+        if cursor + chunk_len > data.len() {
+            return Err("Failed to decrypt backup: wrong passphrase or corrupted file".to_string());
+        }
+        let nonce_bytes = stream_chunk_nonce(&nonce_prefix, index);
+        let chunk_plain = cipher
+            .decrypt(XNonce::from_slice(&nonce_bytes), &data[cursor..cursor + chunk_len])
+            .map_err(|_| "Failed to decrypt backup: wrong passphrase or corrupted file".to_string())?;
+        plaintext.extend_from_slice(&chunk_plain);
+        cursor += chunk_len;
+        index += 1;
+    }
+    Ok(plaintext)
+}
+
+/// The original whole-buffer ChaCha20-Poly1305 format: `[magic(8) | salt(16) | nonce(12) |
+/// ciphertext]`, kept only so a backup written before the streaming format shipped still
+/// restores.
+fn decrypt_db_bytes_v1(passphrase: &str, data: &[u8]) -> Result<Vec<u8>, String> {
+    let header_len = 8 + ENCRYPTED_DB_BACKUP_SALT_LEN + ENCRYPTED_DB_BACKUP_NONCE_LEN;
+    if data.len() < header_len {
+        return Err("Not an encrypted walnutbook backup".to_string());
+    }
+    let salt = &data[8..8 + ENCRYPTED_DB_BACKUP_SALT_LEN];
+    let nonce = &data[8 + ENCRYPTED_DB_BACKUP_SALT_LEN..header_len];
+    let ciphertext = &data[header_len..];
+
+    let key_bytes = derive_backup_key(passphrase, salt)?;
+    let cipher = ChaCha20Poly1305::new(Key::from_slice(&key_bytes));
+    cipher
+        .decrypt(Nonce::from_slice(nonce), ciphertext)
+        .map_err(|_| "Failed to decrypt backup: wrong passphrase or corrupted file".to_string())
+}
+
+fn sha256_hex(data: &[u8]) -> String {
+    let mut hasher = Sha256::new();
+    hasher.update(data);
+    hasher.finalize().iter().map(|byte| format!("{:02x}", byte)).collect()
+}
+
+fn gzip_compress(data: &[u8]) -> Result<Vec<u8>, String> {
+    let mut encoder = GzEncoder::new(Vec::new(), Compression::default());
+    encoder.write_all(data).map_err(|e| e.to_string())?;
+    encoder.finish().map_err(|e| e.to_string())
+}
+
+fn gzip_decompress(data: &[u8]) -> Result<Vec<u8>, String> {
+    let mut decoder = GzDecoder::new(data);
+    let mut out = Vec::new();
+    decoder.read_to_end(&mut out).map_err(|e| e.to_string())?;
+    Ok(out)
+}
+
+/// Sidecar next to a `.db.gz` backup recording the SHA-256 of the *uncompressed* database, so
+/// `read_restorable_db_bytes` can detect silent corruption or tampering in the compressed file
+/// before it's ever written over the live database.
+#[derive(serde::Serialize, serde::Deserialize)]
+struct BackupManifest {
+    sha256: String,
+}
+
+fn manifest_path_for(backup_path: &Path) -> PathBuf {
+    let mut os_string = backup_path.as_os_str().to_os_string();
+    os_string.push(".manifest.json");
+    PathBuf::from(os_string)
+}
+
+fn write_backup_manifest(backup_path: &Path, sha256: &str) -> Result<(), String> {
+    let manifest = BackupManifest { sha256: sha256.to_string() };
+    let json = serde_json::to_vec(&manifest).map_err(|e| e.to_string())?;
+    fs::write(manifest_path_for(backup_path), json).map_err(|e| e.to_string())
+}
 
 #[tauri::command]
-pub fn backup_database(app: AppHandle, save_path: String) -> Result<BackupInfo, String> {
+pub fn backup_database(app: AppHandle, save_path: String, passphrase: Option<String>, chunk_size: Option<usize>) -> Result<BackupInfo, String> {
+    let chunk_size = chunk_size.unwrap_or(DEFAULT_PROGRESS_CHUNK_SIZE);
     let db_path = get_db_path(&app);
-    
+
+    emit_progress(&app, "verifying", 0, 0);
     // Verify database integrity before backup
     let conn = Connection::open(&db_path).map_err(|e| e.to_string())?;
-    
+
     // Check if all required tables exist
     let tables = ["accounts", "transactions", "categories", "budgets"];
     for table in tables.iter() {
@@ -30,7 +277,8 @@ pub fn backup_database(app: AppHandle, save_path: String) -> Result<BackupInfo,
             return Err(format!("Database is missing {} table", table));
         }
     }
-    
+    let schema_version = crate::migrations::schema_version(&conn)?;
+
     // Create backup with timestamp only if not already present
     let (backup_path, timestamp) = if save_path.contains("_202") || save_path.contains("_203") || save_path.contains("_204") || save_path.contains("_205") {
         // Path already contains a timestamp, extract it from the path
@@ -46,19 +294,39 @@ pub fn backup_database(app: AppHandle, save_path: String) -> Result<BackupInfo,
         };
         (path, timestamp)
     };
-    
-    fs::copy(&db_path, &backup_path).map_err(|e| e.to_string())?;
-    
+
+    // Compress first (checksum is taken over the uncompressed bytes so it stays meaningful
+    // independent of the gzip container), then encrypt the compressed bytes if a passphrase
+    // was given — `read_restorable_db_bytes` reverses this in the same order on restore.
+    let plaintext = read_with_progress(&app, &db_path, "reading", chunk_size)?;
+    let checksum = sha256_hex(&plaintext);
+    emit_progress(&app, "compressing", 0, 0);
+    let compressed = gzip_compress(&plaintext)?;
+
+    let is_encrypted = passphrase.as_deref().is_some_and(|p| !p.is_empty());
+    let compressed_path = format!("{}.gz", backup_path);
+    if is_encrypted {
+        emit_progress(&app, "encrypting", 0, 0);
+        let sealed = encrypt_db_bytes(passphrase.as_deref().unwrap(), &compressed)?;
+        write_with_progress(&app, Path::new(&compressed_path), &sealed, "writing", chunk_size)?;
+    } else {
+        write_with_progress(&app, Path::new(&compressed_path), &compressed, "writing", chunk_size)?;
+    }
+    write_backup_manifest(Path::new(&compressed_path), &checksum)?;
+
     // Get file size
-    let metadata = fs::metadata(&backup_path).map_err(|e| e.to_string())?;
+    let metadata = fs::metadata(&compressed_path).map_err(|e| e.to_string())?;
     let file_size = metadata.len();
-    
-    Ok(BackupInfo {
+
+    let info = BackupInfo {
         timestamp: timestamp.to_string(),
         file_size,
-        version: "1.0".to_string(),
-        is_compressed: false,
-    })
+        version: schema_version.to_string(),
+        is_compressed: true,
+        is_encrypted,
+    };
+    emit_progress(&app, "done", file_size, file_size);
+    Ok(info)
 }
 
 
@@ -70,9 +338,9 @@ pub fn manual_backup_to_onedrive(app: AppHandle) -> Result<BackupInfo, String> {
     let timestamp = Local::now().format("%Y%m%d_%H%M%S");
     let backup_filename = format!("walnutbook_backup_{}.db", timestamp);
     let backup_path = backup_folder.join(&backup_filename);
-    // Clean old backups (keep only last 10)
-    cleanup_old_backups(&backup_folder, 10)?;
-    backup_database(app, backup_path.to_string_lossy().to_string())
+    // Prune old backups per the grandfather-father-son retention policy, rather than a flat count.
+    apply_retention_policy(&backup_folder, &BackupRetentionPolicy::default())?;
+    backup_database(app, backup_path.to_string_lossy().to_string(), None, None)
 }
 
 #[tauri::command]
@@ -84,94 +352,207 @@ pub fn get_backup_history() -> Result<Vec<BackupInfo>, String> {
     let mut backups = Vec::new();
     if let Ok(entries) = fs::read_dir(&backup_folder) {
         for entry in entries {
-            if let Ok(entry) = entry {
-                let path = entry.path();
-                if let Some(extension) = path.extension() {
-                    if extension == "db" {
-                        if let Ok(metadata) = fs::metadata(&path) {
-                            if let Some(filename) = path.file_name() {
-                                if let Some(filename_str) = filename.to_str() {
-                                    if filename_str.starts_with("walnutbook_backup_") {
-                                        let timestamp = filename_str
-                                            .replace("walnutbook_backup_", "")
-                                            .replace(".db", "");
-                                        let timestamp = if timestamp.matches('_').count() >= 2 {
-                                            let parts: Vec<&str> = timestamp.split('_').collect();
-                                            if parts.len() >= 6 {
-                                                format!("{}_{}_{}_{}_{}_{}", parts[0], parts[1], parts[2], parts[3], parts[4], parts[5])
-                                            } else {
-                                                timestamp
-                                            }
-                                        } else {
-                                            timestamp
-                                        };
-                                        backups.push(BackupInfo {
-                                            timestamp,
-                                            file_size: metadata.len(),
-                                            version: "1.0".to_string(),
-                                            is_compressed: false,
-                                        });
-                                    }
-                                }
-                            }
-                        }
-                    }
-                }
-            }
+            let Ok(entry) = entry else { continue };
+            let path = entry.path();
+            let Some(filename_str) = path.file_name().and_then(|f| f.to_str()) else { continue };
+            let Some(timestamp) = backup_timestamp_stem(filename_str) else { continue };
+            let Ok(metadata) = fs::metadata(&path) else { continue };
+
+            let is_compressed = filename_str.ends_with(".db.gz");
+            let is_encrypted = read_magic_prefix(&path).map(|bytes| is_encrypted_backup_magic(&bytes)).unwrap_or(false);
+            backups.push(BackupInfo {
+                timestamp: timestamp.to_string(),
+                file_size: metadata.len(),
+                version: "1.0".to_string(),
+                is_compressed,
+                is_encrypted,
+            });
         }
     }
     backups.sort_by(|a, b| b.timestamp.cmp(&a.timestamp));
-    backups.truncate(10);
     Ok(backups)
 }
 
-fn cleanup_old_backups(backup_folder: &std::path::Path, keep_count: usize) -> Result<(), String> {
+/// How many generations of `walnutbook_backup_*.db` files [`apply_retention_policy`] keeps, per
+/// bucket — modeled on Proxmox's `prune` rules. `keep_last` keeps the N most recent backups
+/// unconditionally; each other field buckets all backups by its own time key (day/week
+/// `%Y-%W`/month/year) and keeps the newest backup seen in each distinct bucket, up to that
+/// rule's count. A backup survives if any single rule keeps it.
+#[derive(Debug, Clone, Copy, serde::Deserialize)]
+pub struct BackupRetentionPolicy {
+    pub keep_last: u32,
+    pub keep_daily: u32,
+    pub keep_weekly: u32,
+    pub keep_monthly: u32,
+    pub keep_yearly: u32,
+}
+
+impl Default for BackupRetentionPolicy {
+    fn default() -> Self {
+        Self { keep_last: 4, keep_daily: 7, keep_weekly: 4, keep_monthly: 6, keep_yearly: 0 }
+    }
+}
+
+#[derive(Debug, serde::Serialize)]
+pub struct RetentionResult {
+    pub kept: Vec<String>,
+    pub pruned: Vec<String>,
+}
+
+/// Lists every `walnutbook_backup_*.db`/`walnutbook_backup_*.db.gz` file under `backup_folder`
+/// with its timestamp parsed out of the filename, newest first. The `.manifest.json` sidecars
+/// written next to `.db.gz` backups are not themselves backups and are skipped.
+fn list_dated_backups(backup_folder: &Path) -> Result<Vec<(PathBuf, chrono::NaiveDateTime)>, String> {
     let mut backups = Vec::new();
     if let Ok(entries) = fs::read_dir(backup_folder) {
         for entry in entries {
-            if let Ok(entry) = entry {
-                let path = entry.path();
-                if let Some(extension) = path.extension() {
-                    if extension == "db" {
-                        if let Some(filename) = path.file_name() {
-                            if let Some(filename_str) = filename.to_str() {
-                                if filename_str.starts_with("walnutbook_backup_") {
-                                    if let Ok(metadata) = fs::metadata(&path) {
-                                        if let Ok(modified) = metadata.modified() {
-                                            backups.push((path, modified));
-                                        }
-                                    }
-                                }
-                            }
-                        }
-                    }
-                }
+            let entry = entry.map_err(|e| e.to_string())?;
+            let path = entry.path();
+            let Some(filename) = path.file_name().and_then(|f| f.to_str()) else { continue };
+            let Some(stamp) = backup_timestamp_stem(filename) else { continue };
+            if let Ok(dt) = chrono::NaiveDateTime::parse_from_str(stamp, "%Y%m%d_%H%M%S") {
+                backups.push((path, dt));
+            }
+        }
+    }
+    backups.sort_by(|a, b| b.1.cmp(&a.1));
+    Ok(backups)
+}
+
+/// Strips the `walnutbook_backup_` prefix and `.db`/`.db.gz` suffix from `filename`, returning
+/// the embedded timestamp stem, or `None` if `filename` isn't a backup file at all (including
+/// the `.manifest.json` sidecars [`write_backup_manifest`] writes next to `.db.gz` backups).
+fn backup_timestamp_stem(filename: &str) -> Option<&str> {
+    let stem = filename.strip_prefix("walnutbook_backup_")?;
+    stem.strip_suffix(".db.gz").or_else(|| stem.strip_suffix(".db"))
+}
+
+/// Applies `policy` to every dated backup in `backup_folder`, deleting whichever ones no rule
+/// keeps. Returns the surviving and removed filenames so the caller (or UI) can show what
+/// happened.
+fn apply_retention_policy(backup_folder: &Path, policy: &BackupRetentionPolicy) -> Result<RetentionResult, String> {
+    let backups = list_dated_backups(backup_folder)?;
+    let mut keep = vec![false; backups.len()];
+
+    for slot in keep.iter_mut().take(policy.keep_last as usize) {
+        *slot = true;
+    }
+
+    let rules: [(u32, &str); 4] = [
+        (policy.keep_daily, "%Y%m%d"),
+        (policy.keep_weekly, "%Y-%W"),
+        (policy.keep_monthly, "%Y%m"),
+        (policy.keep_yearly, "%Y"),
+    ];
+    for (count, bucket_format) in rules {
+        let mut seen_buckets = std::collections::HashSet::new();
+        let mut kept_for_rule = 0u32;
+        for (i, (_, timestamp)) in backups.iter().enumerate() {
+            if kept_for_rule >= count {
+                break;
+            }
+            if seen_buckets.insert(timestamp.format(bucket_format).to_string()) {
+                keep[i] = true;
+                kept_for_rule += 1;
             }
         }
     }
-    backups.sort_by(|a, b| a.1.cmp(&b.1));
-    if backups.len() > keep_count {
-        for (path, _) in backups.iter().take(backups.len() - keep_count) {
-            let _ = fs::remove_file(path);
+
+    let mut kept = Vec::new();
+    let mut pruned = Vec::new();
+    for (i, (path, _)) in backups.iter().enumerate() {
+        let filename = path.file_name().unwrap().to_string_lossy().to_string();
+        if keep[i] {
+            kept.push(filename);
+        } else {
+            fs::remove_file(path).map_err(|e| e.to_string())?;
+            pruned.push(filename);
         }
     }
-    Ok(())
+
+    Ok(RetentionResult { kept, pruned })
 }
 
+/// Prunes `walnutbook_backup_*.db` files in the OneDrive backups folder per `policy`, so the
+/// UI can offer a manual "clean up old backups" action with the same rules `manual_backup_to_onedrive`
+/// applies automatically before each new backup.
 #[tauri::command]
-pub fn restore_database(app: AppHandle, file_path: String) -> Result<(), String> {
+pub fn prune_backups(policy: BackupRetentionPolicy) -> Result<RetentionResult, String> {
+    let backup_folder = get_onedrive_backups_dir()?;
+    apply_retention_policy(&backup_folder, &policy)
+}
+
+/// Reads `file_path`, transparently decrypting it with `passphrase` if it carries the
+/// [`ENCRYPTED_DB_BACKUP_MAGIC`] header produced by `backup_database`'s encrypted mode.
+/// A plain SQLite file is returned as-is, so the existing unencrypted restore flow keeps
+/// working with no passphrase supplied.
+fn read_restorable_db_bytes(app: &AppHandle, file_path: &str, passphrase: &Option<String>, chunk_size: usize) -> Result<Vec<u8>, String> {
+    let path = Path::new(file_path);
+    emit_progress(app, "reading", 0, 0);
+    let raw = if read_magic_prefix(path).map(|b| is_encrypted_backup_magic(&b)).unwrap_or(false) {
+        let passphrase = passphrase
+            .as_ref()
+            .filter(|p| !p.is_empty())
+            .ok_or_else(|| "This backup is encrypted; a passphrase is required to restore it".to_string())?;
+        let data = read_with_progress(app, path, "reading", chunk_size)?;
+        emit_progress(app, "decrypting", 0, 0);
+        decrypt_db_bytes(passphrase, &data)?
+    } else {
+        read_with_progress(app, path, "reading", chunk_size)?
+    };
+
+    let decompressed = if file_path.ends_with(".gz") {
+        emit_progress(app, "decompressing", 0, 0);
+        gzip_decompress(&raw)?
+    } else {
+        raw
+    };
+
+    // A manifest is only written by `backup_database` for compressed backups; older/manually
+    // placed files have nothing to check against and are trusted as-is.
+    emit_progress(app, "verifying", 0, 0);
+    let manifest_path = manifest_path_for(path);
+    if let Ok(manifest_bytes) = fs::read(&manifest_path) {
+        let manifest: BackupManifest = serde_json::from_slice(&manifest_bytes).map_err(|e| e.to_string())?;
+        let actual = sha256_hex(&decompressed);
+        if actual != manifest.sha256 {
+            return Err("Backup checksum mismatch: file is corrupted or has been tampered with".to_string());
+        }
+    }
+
+    Ok(decompressed)
+}
+
+#[tauri::command]
+pub fn restore_database(app: AppHandle, file_path: String, passphrase: Option<String>, chunk_size: Option<usize>) -> Result<(), String> {
+    let chunk_size = chunk_size.unwrap_or(DEFAULT_PROGRESS_CHUNK_SIZE);
     let db_path = get_db_path(&app);
-    
+
     // Create backup of current database
     let backup_path = format!("{}.backup_{}", db_path.to_string_lossy(), Local::now().format("%Y%m%d_%H%M%S"));
     fs::copy(&db_path, &backup_path).map_err(|e| e.to_string())?;
-    
+
+    // Decrypt (if needed) before touching the live DB path, so a wrong passphrase leaves it untouched
+    let restored_bytes = match read_restorable_db_bytes(&app, &file_path, &passphrase, chunk_size) {
+        Ok(bytes) => bytes,
+        Err(e) => {
+            let _ = fs::remove_file(&backup_path);
+            return Err(e);
+        }
+    };
+
     // Try to restore
-    match fs::copy(&file_path, &db_path) {
+    match write_with_progress(&app, &db_path, &restored_bytes, "writing", chunk_size) {
         Ok(_) => {
-            // Verify restored database
-            let conn = Connection::open(&db_path).map_err(|e| e.to_string())?;
-            
+            // Restored files are often older backups, possibly on a schema from before columns
+            // like `import_id`/`cleared_status` existed — bring them up to the current schema
+            // before anything reads from them, the same way a fresh `init_db` would.
+            let mut conn = Connection::open(&db_path).map_err(|e| e.to_string())?;
+            if let Err(e) = crate::migrations::run_migrations(&mut conn) {
+                let _ = fs::copy(&backup_path, &db_path);
+                return Err(format!("Failed to migrate restored database: {}", e));
+            }
+
             // Check if required tables exist
             let tables = ["accounts", "transactions", "categories", "budgets"];
             for table in tables.iter() {
@@ -181,48 +562,58 @@ pub fn restore_database(app: AppHandle, file_path: String) -> Result<(), String>
                     return Err(format!("Restored database is missing {} table", table));
                 }
             }
-            
+
             // Delete backup if verification succeeds
             fs::remove_file(backup_path).map_err(|e| e.to_string())?;
+            emit_progress(&app, "done", 0, 0);
             Ok(())
         }
         Err(e) => {
             // Restore from backup if copy fails
             let _ = fs::copy(&backup_path, &db_path);
-            Err(e.to_string())
+            Err(e)
         }
     }
 }
 
 #[tauri::command]
-pub fn export_database(app: AppHandle) -> Result<Vec<u8>, String> {
+pub fn export_database(app: AppHandle, chunk_size: Option<usize>) -> Result<Vec<u8>, String> {
+    let chunk_size = chunk_size.unwrap_or(DEFAULT_PROGRESS_CHUNK_SIZE);
     let path = get_db_path(&app);
-    fs::read(&path).map_err(|e| e.to_string())
+    let data = read_with_progress(&app, &path, "exporting", chunk_size)?;
+    emit_progress(&app, "done", data.len() as u64, data.len() as u64);
+    Ok(data)
 }
 
 #[tauri::command]
-pub fn import_database(app: AppHandle, data: Vec<u8>) -> Result<(), String> {
+pub fn import_database(app: AppHandle, data: Vec<u8>, chunk_size: Option<usize>) -> Result<(), String> {
+    let chunk_size = chunk_size.unwrap_or(DEFAULT_PROGRESS_CHUNK_SIZE);
     let db_path = get_db_path(&app);
-    
+
     // Create backup
     let backup_path = format!("{}.backup_{}", db_path.to_string_lossy(), Local::now().format("%Y%m%d_%H%M%S"));
     fs::copy(&db_path, &backup_path).map_err(|e| e.to_string())?;
-    
+
     // Write new database
-    if let Err(e) = fs::write(&db_path, &data) {
+    if let Err(e) = write_with_progress(&app, &db_path, &data, "importing", chunk_size) {
         let _ = fs::copy(&backup_path, &db_path);
-        return Err(e.to_string());
+        return Err(e);
     }
-    
-    // Verify new database
-    let conn = match Connection::open(&db_path) {
+
+    // Verify new database, migrating it to the current schema first (imported files commonly
+    // predate columns/tables the running app now assumes exist).
+    let mut conn = match Connection::open(&db_path) {
         Ok(conn) => conn,
         Err(e) => {
             let _ = fs::copy(&backup_path, &db_path);
             return Err(e.to_string());
         }
     };
-    
+    if let Err(e) = crate::migrations::run_migrations(&mut conn) {
+        let _ = fs::copy(&backup_path, &db_path);
+        return Err(format!("Failed to migrate imported database: {}", e));
+    }
+
     // Check required tables
     let tables = ["accounts", "transactions", "categories", "budgets", "account_import_settings"];
     for table in tables.iter() {
@@ -237,43 +628,65 @@ pub fn import_database(app: AppHandle, data: Vec<u8>) -> Result<(), String> {
     Ok(())
 }
 
+/// Resolves `timestamp` back to the on-disk backup file, trying the current `.db.gz` naming
+/// before falling back to the plain `.db` files older versions of this app produced.
+fn locate_backup_by_timestamp(backup_folder: &Path, timestamp: &str) -> Option<PathBuf> {
+    let gz_path = backup_folder.join(format!("walnutbook_backup_{}.db.gz", timestamp));
+    if gz_path.exists() {
+        return Some(gz_path);
+    }
+    let db_path = backup_folder.join(format!("walnutbook_backup_{}.db", timestamp));
+    db_path.exists().then_some(db_path)
+}
+
 #[tauri::command]
 pub fn delete_backup_from_history(timestamp: String) -> Result<(), String> {
     let backup_folder = get_onedrive_backups_dir()?;
-    let backup_filename = format!("walnutbook_backup_{}.db", timestamp);
-    let backup_path = backup_folder.join(&backup_filename);
-    if backup_path.exists() {
+    if let Some(backup_path) = locate_backup_by_timestamp(&backup_folder, &timestamp) {
+        let _ = fs::remove_file(manifest_path_for(&backup_path));
         fs::remove_file(&backup_path).map_err(|e| e.to_string())?;
     }
     Ok(())
 }
 
 #[tauri::command]
-pub fn restore_backup_from_history(app: AppHandle, timestamp: String) -> Result<(), String> {
+pub fn restore_backup_from_history(app: AppHandle, timestamp: String, passphrase: Option<String>) -> Result<(), String> {
     let backup_folder = get_onedrive_backups_dir()?;
-    let backup_filename = format!("walnutbook_backup_{}.db", timestamp);
-    let backup_path = backup_folder.join(&backup_filename);
-    if !backup_path.exists() {
-        return Err("Backup file not found".to_string());
-    }
-    restore_database_from_path(app, backup_path.to_string_lossy().to_string())
+    let backup_path = locate_backup_by_timestamp(&backup_folder, &timestamp)
+        .ok_or_else(|| "Backup file not found".to_string())?;
+    restore_database_from_path(app, backup_path.to_string_lossy().to_string(), passphrase)
 }
 
-fn restore_database_from_path(app: AppHandle, file_path: String) -> Result<(), String> {
+fn restore_database_from_path(app: AppHandle, file_path: String, passphrase: Option<String>) -> Result<(), String> {
     // This is a helper function for restoring from a specific path
     // We'll use the existing restore logic but without the file dialog
     let db_path = get_db_path(&app);
-    
+
     // Create backup of current database
     let backup_path = format!("{}.backup_{}", db_path.to_string_lossy(), Local::now().format("%Y%m%d_%H%M%S"));
     fs::copy(&db_path, &backup_path).map_err(|e| e.to_string())?;
-    
+
+    // Decrypt (if needed) before touching the live DB path, so a wrong passphrase leaves it untouched
+    let restored_bytes = match read_restorable_db_bytes(&app, &file_path, &passphrase, DEFAULT_PROGRESS_CHUNK_SIZE) {
+        Ok(bytes) => bytes,
+        Err(e) => {
+            let _ = fs::remove_file(&backup_path);
+            return Err(e);
+        }
+    };
+
     // Try to restore
-    match fs::copy(&file_path, &db_path) {
+    match fs::write(&db_path, &restored_bytes) {
         Ok(_) => {
-            // Verify restored database
-            let conn = Connection::open(&db_path).map_err(|e| e.to_string())?;
-            
+            // Restored files are often older backups, possibly on a schema from before columns
+            // like `import_id`/`cleared_status` existed — bring them up to the current schema
+            // before anything reads from them, the same way a fresh `init_db` would.
+            let mut conn = Connection::open(&db_path).map_err(|e| e.to_string())?;
+            if let Err(e) = crate::migrations::run_migrations(&mut conn) {
+                let _ = fs::copy(&backup_path, &db_path);
+                return Err(format!("Failed to migrate restored database: {}", e));
+            }
+
             // Check if required tables exist
             let tables = ["accounts", "transactions", "categories", "budgets"];
             for table in tables.iter() {
@@ -283,7 +696,7 @@ fn restore_database_from_path(app: AppHandle, file_path: String) -> Result<(), S
                     return Err(format!("Restored database is missing {} table", table));
                 }
             }
-            
+
             // Delete backup if verification succeeds
             fs::remove_file(backup_path).map_err(|e| e.to_string())?;
             Ok(())
@@ -296,10 +709,533 @@ fn restore_database_from_path(app: AppHandle, file_path: String) -> Result<(), S
     }
 }
 
+/// Rejects `folder_path` if any raw component is a literal `..`, or if the path (once resolved
+/// against its nearest existing ancestor, since the backup folder itself may not exist yet)
+/// falls outside the detected cloud-sync root — OneDrive, falling back to the user's home
+/// directory. The error message is distinct from every other backup error so the frontend can
+/// match on it and warn the user instead of silently writing backups to an arbitrary location.
+fn validate_backup_folder_path(folder_path: &str) -> Result<PathBuf, String> {
+    let traversal_err = || format!("Path escapes the allowed backup root: {}", folder_path);
+
+    let requested = Path::new(folder_path);
+    if requested.components().any(|c| matches!(c, Component::ParentDir)) {
+        return Err(traversal_err());
+    }
+
+    let allowed_root = get_onedrive_path().or_else(|_| crate::utils::home_dir())?;
+    let allowed_root = fs::canonicalize(&allowed_root).unwrap_or_else(|_| PathBuf::from(&allowed_root));
+
+    let mut remaining = requested.to_path_buf();
+    let mut trailing = PathBuf::new();
+    let resolved_base = loop {
+        if let Ok(base) = fs::canonicalize(&remaining) {
+            break base;
+        }
+        let Some(name) = remaining.file_name() else {
+            return Err(traversal_err());
+        };
+        trailing = Path::new(name).join(&trailing);
+        let Some(parent) = remaining.parent() else {
+            return Err(traversal_err());
+        };
+        remaining = parent.to_path_buf();
+    };
+    let resolved = resolved_base.join(trailing);
+
+    if !resolved.starts_with(&allowed_root) {
+        return Err(traversal_err());
+    }
+
+    Ok(resolved)
+}
+
 #[tauri::command]
 pub fn create_backup_folder(folder_path: String) -> Result<(), String> {
+    let resolved = validate_backup_folder_path(&folder_path)?;
+    fs::create_dir_all(&resolved).map_err(|e| e.to_string())?;
+    Ok(())
+}
+
+/// Writes `data` to `<folder_path>/<filename>` durably: the bytes land in a temporary sibling
+/// file first, which is flushed and `fsync`'d before an atomic `rename` replaces the final
+/// path, so a reader never observes a half-written file. The directory entry for that rename
+/// is itself not durable until the directory's own metadata is synced, so the parent directory
+/// is opened and `fsync`'d too — skipped on Windows, where directories can't be opened as files
+/// and `rename` over an existing path is already atomic at the filesystem level. Without this,
+/// a crash mid-save (e.g. power loss while OneDrive is mid-sync) can leave a truncated or
+/// zero-byte backup on disk.
+#[tauri::command]
+pub fn write_backup_file(folder_path: String, filename: String, data: Vec<u8>) -> Result<(), String> {
     fs::create_dir_all(&folder_path).map_err(|e| e.to_string())?;
+    let dir = Path::new(&folder_path);
+    let final_path = dir.join(&filename);
+    let tmp_path = dir.join(format!("{}.tmp", filename));
+
+    {
+        let mut tmp_file = fs::File::create(&tmp_path).map_err(|e| e.to_string())?;
+        tmp_file.write_all(&data).map_err(|e| e.to_string())?;
+        tmp_file.sync_all().map_err(|e| e.to_string())?;
+    }
+
+    fs::rename(&tmp_path, &final_path).map_err(|e| e.to_string())?;
+
+    #[cfg(not(target_os = "windows"))]
+    {
+        let dir_file = fs::File::open(dir).map_err(|e| e.to_string())?;
+        dir_file.sync_all().map_err(|e| e.to_string())?;
+    }
+
     Ok(())
 }
 
- 
\ No newline at end of file
+/// How [`save_backup`] should treat whatever already occupies `filename` before writing the
+/// new one — modeled on the classic `cp --backup` modes.
+#[derive(Debug, Clone, Copy, serde::Deserialize)]
+pub enum BackupRotationMode {
+    /// Overwrite the existing file, keeping no history.
+    None,
+    /// Keep exactly one previous copy, renamed to `<filename>~`.
+    Simple,
+    /// Keep up to `keep_count` previous copies as `<filename>.~1~` (newest) through
+    /// `<filename>.~N~` (oldest), evicting whatever falls off the end.
+    Numbered,
+}
+
+/// Rotates whatever already exists at `<folder>/<filename>` per `mode`, then writes `data` to
+/// that path via [`write_backup_file`]'s durable atomic-rename path. The rename chain (oldest
+/// evicted, everything else shifted up a slot) runs before the new file is written, so a crash
+/// mid-rotation leaves old generations intact rather than losing them to a half-applied shift.
+#[tauri::command]
+pub fn save_backup(folder: String, filename: String, data: Vec<u8>, mode: BackupRotationMode, keep_count: Option<u32>) -> Result<(), String> {
+    fs::create_dir_all(&folder).map_err(|e| e.to_string())?;
+    let dir = Path::new(&folder);
+    let final_path = dir.join(&filename);
+
+    match mode {
+        BackupRotationMode::None => {}
+        BackupRotationMode::Simple => {
+            if final_path.exists() {
+                let previous_path = dir.join(format!("{}~", filename));
+                fs::rename(&final_path, &previous_path).map_err(|e| e.to_string())?;
+            }
+        }
+        BackupRotationMode::Numbered => {
+            let keep = keep_count.unwrap_or(5).max(1);
+
+            let oldest_path = dir.join(format!("{}.~{}~", filename, keep));
+            let _ = fs::remove_file(&oldest_path);
+
+            for n in (1..keep).rev() {
+                let from = dir.join(format!("{}.~{}~", filename, n));
+                if from.exists() {
+                    let to = dir.join(format!("{}.~{}~", filename, n + 1));
+                    fs::rename(&from, &to).map_err(|e| e.to_string())?;
+                }
+            }
+
+            if final_path.exists() {
+                let newest_path = dir.join(format!("{}.~1~", filename));
+                fs::rename(&final_path, &newest_path).map_err(|e| e.to_string())?;
+            }
+        }
+    }
+
+    write_backup_file(folder, filename, data)
+}
+
+/// Current on-disk format of [`export_backup`]/[`import_backup`] archives. Bump this (and add
+/// a migration branch in `import_backup`) if the archive's envelope shape itself ever changes
+/// — independent of `schema_version`, which tracks the *database* schema the payload was
+/// dumped from.
+const BACKUP_FORMAT_VERSION: u32 = 1;
+
+/// Tables captured by a full encrypted backup, in dependency order so `import_backup` can
+/// delete-then-reinsert without tripping foreign key checks (categories before transactions
+/// that reference them, etc.). `reminders` and `reminder_payment_history` are the exception to
+/// the delete-then-reinsert rule — see [`merge_reminders_rows`].
+const BACKUP_TABLES: &[&str] = &[
+    "accounts", "categories", "budgets", "transactions",
+    "reminders", "reminder_payment_history",
+];
+
+/// A versioned, self-describing, passphrase-encrypted snapshot of every table in
+/// [`BACKUP_TABLES`] — modeled on zcash-sync's `FullEncryptedBackup`. `salt` and `nonce` are
+/// fresh per export; `ciphertext` is the ChaCha20-Poly1305 sealing of the JSON table dump
+/// under a key derived from the passphrase via Argon2id.
+#[derive(serde::Serialize, serde::Deserialize)]
+struct EncryptedBackupArchive {
+    format_version: u32,
+    schema_version: i64,
+    salt: String,
+    nonce: String,
+    ciphertext: String,
+}
+
+pub(crate) fn dump_table_rows(conn: &Connection, table: &str) -> Result<Vec<Value>, String> {
+    dump_table_rows_where(conn, table, "")
+}
+
+/// Same row-to-JSON dump as [`dump_table_rows`], with a caller-supplied ` WHERE ...` clause
+/// (pass `""` for none) so a caller like [`crate::history::get_entity_history`] can filter at
+/// the SQL layer instead of dumping a whole table and discarding most of it in Rust.
+pub(crate) fn dump_table_rows_where(conn: &Connection, table: &str, where_clause: &str) -> Result<Vec<Value>, String> {
+    let mut stmt = conn.prepare(&format!("SELECT * FROM {}{}", table, where_clause)).map_err(|e| e.to_string())?;
+    let column_names: Vec<String> = stmt.column_names().into_iter().map(|s| s.to_string()).collect();
+    let mut rows = stmt.query([]).map_err(|e| e.to_string())?;
+
+    let mut out = Vec::new();
+    while let Some(row) = rows.next().map_err(|e| e.to_string())? {
+        let mut obj = serde_json::Map::new();
+        for (i, name) in column_names.iter().enumerate() {
+            let value = match row.get_ref(i).map_err(|e| e.to_string())? {
+                rusqlite::types::ValueRef::Null => Value::Null,
+                rusqlite::types::ValueRef::Integer(n) => Value::from(n),
+                rusqlite::types::ValueRef::Real(f) => {
+                    serde_json::Number::from_f64(f).map(Value::Number).unwrap_or(Value::Null)
+                }
+                rusqlite::types::ValueRef::Text(t) => Value::String(String::from_utf8_lossy(t).to_string()),
+                rusqlite::types::ValueRef::Blob(b) => Value::String(BASE64.encode(b)),
+            };
+            obj.insert(name.clone(), value);
+        }
+        out.push(Value::Object(obj));
+    }
+    Ok(out)
+}
+
+pub(crate) fn json_value_to_sql(value: &Value) -> Box<dyn ToSql> {
+    match value {
+        Value::Null => Box::new(Option::<i64>::None),
+        Value::Bool(b) => Box::new(*b),
+        Value::Number(n) => match n.as_i64() {
+            Some(i) => Box::new(i),
+            None => Box::new(n.as_f64().unwrap_or(0.0)),
+        },
+        Value::String(s) => Box::new(s.clone()),
+        other => Box::new(other.to_string()),
+    }
+}
+
+/// Derives a 256-bit ChaCha20-Poly1305 key from `passphrase` and `salt` via Argon2id.
+fn derive_backup_key(passphrase: &str, salt: &[u8]) -> Result<[u8; 32], String> {
+    let mut key_bytes = [0u8; 32];
+    Argon2::default()
+        .hash_password_into(passphrase.as_bytes(), salt, &mut key_bytes)
+        .map_err(|e| e.to_string())?;
+    Ok(key_bytes)
+}
+
+/// Serializes every table in [`BACKUP_TABLES`] into a single JSON document and seals it with
+/// a passphrase-derived ChaCha20-Poly1305 key, producing a portable archive independent of the
+/// on-disk `.db` file (safe to drop in a cloud-synced folder, unlike a raw SQLite copy).
+#[tauri::command]
+pub fn export_backup(app: AppHandle, passphrase: String) -> Result<Vec<u8>, String> {
+    let db_path = get_db_path(&app);
+    let conn = Connection::open(&db_path).map_err(|e| e.to_string())?;
+
+    let schema_version: i64 = conn
+        .query_row("PRAGMA user_version", [], |row| row.get(0))
+        .map_err(|e| e.to_string())?;
+
+    let mut tables = serde_json::Map::new();
+    for table in BACKUP_TABLES {
+        tables.insert((*table).to_string(), Value::Array(dump_table_rows(&conn, table)?));
+    }
+    let payload = serde_json::to_vec(&Value::Object(tables)).map_err(|e| e.to_string())?;
+
+    let mut salt = [0u8; 16];
+    rand::thread_rng().fill_bytes(&mut salt);
+    let key_bytes = derive_backup_key(&passphrase, &salt)?;
+    let cipher = ChaCha20Poly1305::new(Key::from_slice(&key_bytes));
+
+    let mut nonce_bytes = [0u8; 12];
+    rand::thread_rng().fill_bytes(&mut nonce_bytes);
+    let ciphertext = cipher
+        .encrypt(Nonce::from_slice(&nonce_bytes), payload.as_ref())
+        .map_err(|e| e.to_string())?;
+
+    let archive = EncryptedBackupArchive {
+        format_version: BACKUP_FORMAT_VERSION,
+        schema_version,
+        salt: BASE64.encode(salt),
+        nonce: BASE64.encode(nonce_bytes),
+        ciphertext: BASE64.encode(ciphertext),
+    };
+    serde_json::to_vec(&archive).map_err(|e| e.to_string())
+}
+
+/// Verifies the archive's auth tag, checks its embedded schema version isn't newer than this
+/// app's current migration level, then replaces every row of every table in [`BACKUP_TABLES`]
+/// (except `reminders` and `reminder_payment_history`, which are merged — see
+/// [`merge_reminders_rows`]) inside a single transaction so a partially-restored database is
+/// never left on disk.
+#[tauri::command]
+pub fn import_backup(app: AppHandle, data: Vec<u8>, passphrase: String) -> Result<(), String> {
+    let archive: EncryptedBackupArchive =
+        serde_json::from_slice(&data).map_err(|e| format!("Invalid backup archive: {}", e))?;
+    if archive.format_version != BACKUP_FORMAT_VERSION {
+        return Err(format!("Unsupported backup format version {}", archive.format_version));
+    }
+
+    let salt = BASE64.decode(&archive.salt).map_err(|e| e.to_string())?;
+    let nonce_bytes = BASE64.decode(&archive.nonce).map_err(|e| e.to_string())?;
+    let ciphertext = BASE64.decode(&archive.ciphertext).map_err(|e| e.to_string())?;
+
+    let key_bytes = derive_backup_key(&passphrase, &salt)?;
+    let cipher = ChaCha20Poly1305::new(Key::from_slice(&key_bytes));
+    let payload = cipher
+        .decrypt(Nonce::from_slice(&nonce_bytes), ciphertext.as_ref())
+        .map_err(|_| "Failed to decrypt backup: wrong passphrase or corrupted archive".to_string())?;
+
+    let tables: Value = serde_json::from_slice(&payload).map_err(|e| e.to_string())?;
+
+    let db_path = get_db_path(&app);
+    let mut conn = Connection::open(&db_path).map_err(|e| e.to_string())?;
+
+    let current_schema_version: i64 = conn
+        .query_row("PRAGMA user_version", [], |row| row.get(0))
+        .map_err(|e| e.to_string())?;
+    if archive.schema_version > current_schema_version {
+        return Err(format!(
+            "This backup was made with a newer schema (v{}) than the app currently supports (v{}); update the app before restoring it",
+            archive.schema_version, current_schema_version
+        ));
+    }
+
+    let tx = conn.transaction().map_err(|e| e.to_string())?;
+    let mut reminder_id_map = HashMap::new();
+    for table in BACKUP_TABLES {
+        let rows = tables.get(*table).and_then(|v| v.as_array()).cloned().unwrap_or_default();
+
+        if *table == "reminders" {
+            reminder_id_map = merge_reminders_rows(&tx, &rows)?;
+            continue;
+        }
+        if *table == "reminder_payment_history" {
+            merge_reminder_payment_history_rows(&tx, &rows, &reminder_id_map)?;
+            continue;
+        }
+
+        tx.execute(&format!("DELETE FROM {}", table), []).map_err(|e| e.to_string())?;
+        for row in rows {
+            let obj = row.as_object().ok_or_else(|| format!("Malformed row in table {}", table))?;
+            let columns: Vec<&String> = obj.keys().collect();
+            let placeholders: Vec<String> = (1..=columns.len()).map(|i| format!("?{}", i)).collect();
+            let sql = format!(
+                "INSERT INTO {} ({}) VALUES ({})",
+                table,
+                columns.iter().map(|c| c.as_str()).collect::<Vec<_>>().join(", "),
+                placeholders.join(", ")
+            );
+            let boxed_values: Vec<Box<dyn ToSql>> = columns.iter().map(|c| json_value_to_sql(&obj[*c])).collect();
+            let values: Vec<&dyn ToSql> = boxed_values.iter().map(|b| b.as_ref()).collect();
+            tx.execute(&sql, values.as_slice()).map_err(|e| e.to_string())?;
+        }
+    }
+    tx.commit().map_err(|e| e.to_string())?;
+
+    Ok(())
+}
+
+/// Upserts each imported `reminders` row by its natural key (`account_id`, `payment_day`,
+/// `statement_date`) instead of the delete-then-reinsert every other [`BACKUP_TABLES`] entry
+/// gets, so restoring a backup never drops a reminder created locally after the backup was
+/// taken. Returns a map from the backup's row `id`s to whatever `id` that reminder ended up at
+/// in this database, so [`merge_reminder_payment_history_rows`] can follow the same rows.
+fn merge_reminders_rows(tx: &Transaction, rows: &[Value]) -> Result<HashMap<i64, i64>, String> {
+    let mut id_map = HashMap::new();
+    for row in rows {
+        let obj = row.as_object().ok_or_else(|| "Malformed row in table reminders".to_string())?;
+        let old_id = obj.get("id").and_then(Value::as_i64).unwrap_or(0);
+        let account_id = obj.get("account_id").and_then(Value::as_i64)
+            .ok_or_else(|| "reminders row missing account_id".to_string())?;
+        let payment_day = obj.get("payment_day").and_then(Value::as_i64)
+            .ok_or_else(|| "reminders row missing payment_day".to_string())?;
+        let statement_date = obj.get("statement_date").and_then(Value::as_str).unwrap_or("").to_string();
+
+        let existing_id: Option<i64> = tx.query_row(
+            "SELECT id FROM reminders WHERE account_id = ?1 AND payment_day = ?2 AND statement_date = ?3",
+            params![account_id, payment_day, statement_date],
+            |r| r.get(0),
+        ).optional().map_err(|e| e.to_string())?;
+
+        let columns: Vec<&String> = obj.keys().filter(|k| *k != "id").collect();
+        if let Some(existing_id) = existing_id {
+            let assignments: Vec<String> = columns.iter().enumerate().map(|(i, c)| format!("{} = ?{}", c, i + 1)).collect();
+            let sql = format!("UPDATE reminders SET {} WHERE id = ?{}", assignments.join(", "), columns.len() + 1);
+            let mut boxed_values: Vec<Box<dyn ToSql>> = columns.iter().map(|c| json_value_to_sql(&obj[*c])).collect();
+            boxed_values.push(Box::new(existing_id));
+            let values: Vec<&dyn ToSql> = boxed_values.iter().map(|b| b.as_ref()).collect();
+            tx.execute(&sql, values.as_slice()).map_err(|e| e.to_string())?;
+            id_map.insert(old_id, existing_id);
+        } else {
+            let placeholders: Vec<String> = (1..=columns.len()).map(|i| format!("?{}", i)).collect();
+            let sql = format!(
+                "INSERT INTO reminders ({}) VALUES ({})",
+                columns.iter().map(|c| c.as_str()).collect::<Vec<_>>().join(", "),
+                placeholders.join(", ")
+            );
+            let boxed_values: Vec<Box<dyn ToSql>> = columns.iter().map(|c| json_value_to_sql(&obj[*c])).collect();
+            let values: Vec<&dyn ToSql> = boxed_values.iter().map(|b| b.as_ref()).collect();
+            tx.execute(&sql, values.as_slice()).map_err(|e| e.to_string())?;
+            id_map.insert(old_id, tx.last_insert_rowid());
+        }
+    }
+    Ok(id_map)
+}
+
+/// Same natural-key upsert as [`merge_reminders_rows`], keyed on (`reminder_id`, `paid_date`,
+/// `statement_date`), with `reminder_id` first remapped through `reminder_id_map` so a payment
+/// history row always lands against the reminder its parent was merged into.
+fn merge_reminder_payment_history_rows(
+    tx: &Transaction,
+    rows: &[Value],
+    reminder_id_map: &HashMap<i64, i64>,
+) -> Result<(), String> {
+    for row in rows {
+        let obj = row.as_object().ok_or_else(|| "Malformed row in table reminder_payment_history".to_string())?;
+        let imported_reminder_id = obj.get("reminder_id").and_then(Value::as_i64)
+            .ok_or_else(|| "reminder_payment_history row missing reminder_id".to_string())?;
+        let reminder_id = *reminder_id_map.get(&imported_reminder_id).unwrap_or(&imported_reminder_id);
+        let paid_date = obj.get("paid_date").and_then(Value::as_str).unwrap_or("").to_string();
+        let statement_date = obj.get("statement_date").and_then(Value::as_str).map(|s| s.to_string());
+
+        let existing_id: Option<i64> = tx.query_row(
+            "SELECT id FROM reminder_payment_history WHERE reminder_id = ?1 AND paid_date = ?2 AND statement_date IS ?3",
+            params![reminder_id, paid_date, statement_date],
+            |r| r.get(0),
+        ).optional().map_err(|e| e.to_string())?;
+
+        let mut obj = obj.clone();
+        obj.insert("reminder_id".to_string(), Value::from(reminder_id));
+        let columns: Vec<String> = obj.keys().filter(|k| k.as_str() != "id").cloned().collect();
+
+        if let Some(existing_id) = existing_id {
+            let assignments: Vec<String> = columns.iter().enumerate().map(|(i, c)| format!("{} = ?{}", c, i + 1)).collect();
+            let sql = format!("UPDATE reminder_payment_history SET {} WHERE id = ?{}", assignments.join(", "), columns.len() + 1);
+            let mut boxed_values: Vec<Box<dyn ToSql>> = columns.iter().map(|c| json_value_to_sql(&obj[c])).collect();
+            boxed_values.push(Box::new(existing_id));
+            let values: Vec<&dyn ToSql> = boxed_values.iter().map(|b| b.as_ref()).collect();
+            tx.execute(&sql, values.as_slice()).map_err(|e| e.to_string())?;
+        } else {
+            let placeholders: Vec<String> = (1..=columns.len()).map(|i| format!("?{}", i)).collect();
+            let sql = format!(
+                "INSERT INTO reminder_payment_history ({}) VALUES ({})",
+                columns.join(", "),
+                placeholders.join(", ")
+            );
+            let boxed_values: Vec<Box<dyn ToSql>> = columns.iter().map(|c| json_value_to_sql(&obj[c])).collect();
+            let values: Vec<&dyn ToSql> = boxed_values.iter().map(|b| b.as_ref()).collect();
+            tx.execute(&sql, values.as_slice()).map_err(|e| e.to_string())?;
+        }
+    }
+    Ok(())
+}
+
+ 
+/// Filename prefix distinguishing the automatic pre-migration safety copies below from the
+/// user-initiated `walnutbook_backup_*` files [`backup_database`]/[`manual_backup_to_onedrive`]
+/// produce, so pruning one set never touches the other.
+const PRE_MIGRATION_BACKUP_PREFIX: &str = "walnutbook_premigration_v";
+
+/// How many automatic pre-migration backups [`maybe_backup_before_migration`] keeps before
+/// deleting the oldest.
+const DEFAULT_PRE_MIGRATION_BACKUP_COUNT: usize = 10;
+
+/// Where [`maybe_backup_before_migration`] writes its safety copies: the same OneDrive backups
+/// folder the rest of this module uses, or — if that can't be resolved (no home directory, no
+/// writable filesystem) — a plain `backups/` directory next to the live database file.
+fn pre_migration_backup_dir(db_path: &Path) -> PathBuf {
+    if let Ok(dir) = get_onedrive_backups_dir() {
+        return dir;
+    }
+    let dir = db_path.parent().unwrap_or_else(|| Path::new(".")).join("backups");
+    let _ = fs::create_dir_all(&dir);
+    dir
+}
+
+/// If `db_path` already exists and its schema version is behind `crate::migrations::MIGRATIONS`,
+/// takes a consistent snapshot of it via `VACUUM INTO` (safe even while the app holds the file
+/// open, unlike a plain file copy) before [`crate::migrations::run_migrations`] touches the
+/// schema, then prunes old pre-migration backups down to [`DEFAULT_PRE_MIGRATION_BACKUP_COUNT`].
+/// A fresh install (no existing file) or a database already at the latest version is a no-op.
+pub fn maybe_backup_before_migration(db_path: &Path) -> Result<(), String> {
+    if !db_path.exists() {
+        return Ok(());
+    }
+
+    let current_version = {
+        let conn = Connection::open(db_path).map_err(|e| e.to_string())?;
+        crate::migrations::schema_version(&conn)?
+    };
+    let target_version = crate::migrations::MIGRATIONS.len() as i64;
+    if current_version >= target_version {
+        return Ok(());
+    }
+
+    let backup_dir = pre_migration_backup_dir(db_path);
+    let timestamp = Local::now().format("%Y-%m-%dT%H-%M-%S").to_string();
+    let backup_path = backup_dir.join(format!("{}{}_{}.db", PRE_MIGRATION_BACKUP_PREFIX, target_version, timestamp));
+
+    let conn = Connection::open(db_path).map_err(|e| e.to_string())?;
+    conn.execute(
+        &format!("VACUUM INTO '{}'", backup_path.to_string_lossy().replace('\'', "''")),
+        [],
+    ).map_err(|e| e.to_string())?;
+
+    prune_pre_migration_backups(&backup_dir, DEFAULT_PRE_MIGRATION_BACKUP_COUNT)
+}
+
+/// Deletes the oldest `walnutbook_premigration_v*.db` files in `backup_dir` beyond `keep_count`,
+/// newest-first by filesystem modified time (these files, unlike the timestamped manual-backup
+/// naming, are sorted by mtime rather than a parsed-out timestamp since the bundled version
+/// number sorts before the timestamp in the filename).
+fn prune_pre_migration_backups(backup_dir: &Path, keep_count: usize) -> Result<(), String> {
+    let mut backups: Vec<(PathBuf, std::time::SystemTime)> = fs::read_dir(backup_dir)
+        .map_err(|e| e.to_string())?
+        .filter_map(|entry| entry.ok())
+        .map(|entry| entry.path())
+        .filter(|path| {
+            path.file_name()
+                .and_then(|f| f.to_str())
+                .is_some_and(|name| name.starts_with(PRE_MIGRATION_BACKUP_PREFIX))
+        })
+        .filter_map(|path| fs::metadata(&path).ok().and_then(|m| m.modified().ok()).map(|m| (path, m)))
+        .collect();
+
+    backups.sort_by(|a, b| b.1.cmp(&a.1));
+    for (path, _) in backups.into_iter().skip(keep_count) {
+        let _ = fs::remove_file(path);
+    }
+    Ok(())
+}
+
+/// Lists the automatic pre-migration safety copies [`maybe_backup_before_migration`] has taken,
+/// newest first, so the UI can offer "roll back a bad upgrade" alongside the manual backup
+/// history.
+#[tauri::command]
+pub fn list_pre_migration_backups(app: AppHandle) -> Result<Vec<String>, String> {
+    let backup_dir = pre_migration_backup_dir(&get_db_path(&app));
+    let mut backups: Vec<(PathBuf, std::time::SystemTime)> = fs::read_dir(&backup_dir)
+        .map_err(|e| e.to_string())?
+        .filter_map(|entry| entry.ok())
+        .map(|entry| entry.path())
+        .filter(|path| {
+            path.file_name()
+                .and_then(|f| f.to_str())
+                .is_some_and(|name| name.starts_with(PRE_MIGRATION_BACKUP_PREFIX))
+        })
+        .filter_map(|path| fs::metadata(&path).ok().and_then(|m| m.modified().ok()).map(|m| (path, m)))
+        .collect();
+    backups.sort_by(|a, b| b.1.cmp(&a.1));
+    Ok(backups.into_iter().map(|(path, _)| path.to_string_lossy().to_string()).collect())
+}
+
+/// Restores the live database from a pre-migration safety copy at `path` (as listed by
+/// [`list_pre_migration_backups`]). Pre-migration backups are always plain, unencrypted
+/// `VACUUM INTO` snapshots, so this skips the passphrase prompt `restore_backup_from_history`
+/// needs for user-initiated backups.
+#[tauri::command]
+pub fn restore_backup(app: AppHandle, path: String) -> Result<(), String> {
+    restore_database_from_path(app, path, None)
+}
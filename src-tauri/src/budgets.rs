@@ -1,15 +1,18 @@
-use rusqlite::{params, Connection};
-use tauri::AppHandle;
+use std::collections::HashMap;
 
+use chrono::{Datelike, NaiveDate};
+use rusqlite::{params, OptionalExtension};
+use serde::Serialize;
+use tauri::{AppHandle, State};
+
+use crate::encryption::{open_encrypted, DbKeyState};
 use crate::models::Budget;
-use crate::utils::get_db_path;
 
 #[tauri::command]
-pub fn get_budgets(app: AppHandle, month: String) -> Result<Vec<Budget>, String> {
-    let path = get_db_path(&app);
-    let conn = Connection::open(path).map_err(|e| e.to_string())?;
+pub fn get_budgets(app: AppHandle, key_state: State<'_, DbKeyState>, month: String) -> Result<Vec<Budget>, String> {
+    let conn = open_encrypted(&app, &key_state)?;
     let mut stmt = conn.prepare(
-        "SELECT id, category_id, amount, month, notes, created_at FROM budgets WHERE month = ?1"
+        "SELECT id, category_id, amount, month, notes, created_at, recurring_rule_id FROM budgets WHERE month = ?1"
     ).map_err(|e| e.to_string())?;
     let rows = stmt.query_map(params![month], |row| {
         Ok(Budget {
@@ -19,6 +22,7 @@ pub fn get_budgets(app: AppHandle, month: String) -> Result<Vec<Budget>, String>
             month: row.get(3)?,
             notes: row.get(4)?,
             created_at: row.get(5)?,
+            recurring_rule_id: row.get(6)?,
         })
     }).map_err(|e| e.to_string())?;
     let mut budgets = Vec::new();
@@ -29,13 +33,12 @@ pub fn get_budgets(app: AppHandle, month: String) -> Result<Vec<Budget>, String>
 }
 
 #[tauri::command]
-pub fn add_budget(app: AppHandle, category_id: i64, amount: f64, month: String, notes: Option<String>) -> Result<Vec<Budget>, String> {
-    let path = get_db_path(&app);
-    let conn = Connection::open(path).map_err(|e| e.to_string())?;
-    
+pub fn add_budget(app: AppHandle, key_state: State<'_, DbKeyState>, category_id: i64, amount: f64, month: String, notes: Option<String>, recurring_rule_id: Option<i64>) -> Result<Vec<Budget>, String> {
+    let conn = open_encrypted(&app, &key_state)?;
+
     match conn.execute(
-        "INSERT INTO budgets (category_id, amount, month, notes) VALUES (?1, ?2, ?3, ?4)",
-        params![category_id, amount, month, notes],
+        "INSERT INTO budgets (category_id, amount, month, notes, recurring_rule_id) VALUES (?1, ?2, ?3, ?4, ?5)",
+        params![category_id, amount, month, notes, recurring_rule_id],
     ) {
         Ok(_) => {},
         Err(e) => {
@@ -48,27 +51,46 @@ pub fn add_budget(app: AppHandle, category_id: i64, amount: f64, month: String,
             }
         }
     }
-    
-    get_budgets(app, month)
+
+    get_budgets(app, key_state, month)
 }
 
 #[tauri::command]
-pub fn update_budget(app: AppHandle, budget: Budget) -> Result<Vec<Budget>, String> {
-    let path = get_db_path(&app);
-    let conn = Connection::open(path).map_err(|e| e.to_string())?;
+pub fn update_budget(app: AppHandle, key_state: State<'_, DbKeyState>, budget: Budget) -> Result<Vec<Budget>, String> {
+    let conn = open_encrypted(&app, &key_state)?;
     conn.execute(
-        "UPDATE budgets SET category_id = ?1, amount = ?2, notes = ?3 WHERE id = ?4",
-        params![budget.category_id, budget.amount, budget.notes, budget.id],
+        "UPDATE budgets SET category_id = ?1, amount = ?2, notes = ?3, recurring_rule_id = ?4 WHERE id = ?5",
+        params![budget.category_id, budget.amount, budget.notes, budget.recurring_rule_id, budget.id],
     )
     .map_err(|e| e.to_string())?;
-    get_budgets(app, budget.month)
+    get_budgets(app, key_state, budget.month)
 }
 
+/// Copies every budget row linked to a recurring rule (`recurring_rule_id IS NOT NULL`) from
+/// `from_month` forward into `to_month`, carrying over its category, amount and rule link.
+/// Months that already have a budget for a given category are left alone — `add_budget`'s own
+/// unique-constraint skip would otherwise silently duplicate or clobber a manually-adjusted
+/// amount for that month.
 #[tauri::command]
-pub fn delete_budget(app: AppHandle, id: i64, current_month: String) -> Result<Vec<Budget>, String> {
-    let path = get_db_path(&app);
-    let conn = Connection::open(path).map_err(|e| e.to_string())?;
-    
+pub fn rollover_recurring_budgets(app: AppHandle, key_state: State<'_, DbKeyState>, from_month: String, to_month: String) -> Result<Vec<Budget>, String> {
+    let conn = open_encrypted(&app, &key_state)?;
+
+    conn.execute(
+        "INSERT INTO budgets (category_id, amount, month, notes, recurring_rule_id)
+         SELECT category_id, amount, ?2, notes, recurring_rule_id
+         FROM budgets
+         WHERE month = ?1 AND recurring_rule_id IS NOT NULL
+           AND category_id NOT IN (SELECT category_id FROM budgets WHERE month = ?2)",
+        params![from_month, to_month],
+    ).map_err(|e| e.to_string())?;
+
+    get_budgets(app, key_state, to_month)
+}
+
+#[tauri::command]
+pub fn delete_budget(app: AppHandle, key_state: State<'_, DbKeyState>, id: i64, current_month: String) -> Result<Vec<Budget>, String> {
+    let conn = open_encrypted(&app, &key_state)?;
+
     // Check if budget exists before deleting
     let exists: bool = conn.query_row(
         "SELECT COUNT(*) FROM budgets WHERE id = ?1",
@@ -102,6 +124,94 @@ pub fn delete_budget(app: AppHandle, id: i64, current_month: String) -> Result<V
     if let Ok((category_id, month)) = budget_info {
         println!("Successfully deleted budget: id={}, category_id={}, month={}", id, category_id, month);
     }
-    
-    get_budgets(app, current_month)
-} 
\ No newline at end of file
+
+    get_budgets(app, key_state, current_month)
+}
+
+#[derive(Debug, Serialize, Clone)]
+pub struct BudgetStatus {
+    pub category_id: i64,
+    pub category_name: String,
+    pub budgeted: f64,
+    pub activity: f64,
+    pub available: f64,
+}
+
+/// `"YYYY-MM"` one calendar month after `month`, via the same total-months trick
+/// `recurring_rules::advance` uses for its Monthly step.
+fn next_month(month: &str) -> Result<String, String> {
+    let first = NaiveDate::parse_from_str(&format!("{}-01", month), "%Y-%m-%d").map_err(|e| e.to_string())?;
+    let total_months = first.year() * 12 + (first.month() as i32 - 1) + 1;
+    Ok(format!("{:04}-{:02}", total_months.div_euclid(12), total_months.rem_euclid(12) + 1))
+}
+
+/// Computes each category's YNAB-style "available" for `month`: what's left of this month's
+/// budget once last month's leftover (or shortfall) carries in and this month's spending comes
+/// out. Walks forward one month at a time from the earliest month any budget was ever entered,
+/// since a given month's carryover depends on every month before it having already been
+/// resolved - `running` caches each category's available as of the month just finished so the
+/// walk never re-derives a month it already passed.
+#[tauri::command]
+pub fn get_budget_status(app: AppHandle, key_state: State<'_, DbKeyState>, month: String) -> Result<Vec<BudgetStatus>, String> {
+    let conn = open_encrypted(&app, &key_state)?;
+
+    let earliest: Option<String> = conn
+        .query_row("SELECT MIN(month) FROM budgets WHERE month <= ?1", params![month], |row| row.get(0))
+        .optional()
+        .map_err(|e| e.to_string())?
+        .flatten();
+    let Some(mut cursor) = earliest else { return Ok(Vec::new()) };
+
+    let mut categories_stmt = conn
+        .prepare("SELECT id, name, carry_overspending FROM categories ORDER BY name")
+        .map_err(|e| e.to_string())?;
+    let categories: Vec<(i64, String, bool)> = categories_stmt
+        .query_map([], |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?)))
+        .map_err(|e| e.to_string())?
+        .collect::<Result<_, _>>()
+        .map_err(|e| e.to_string())?;
+    drop(categories_stmt);
+
+    let mut running: HashMap<i64, f64> = HashMap::new();
+    let mut status = Vec::new();
+    loop {
+        for (category_id, category_name, carry_overspending) in &categories {
+            let budgeted: f64 = conn
+                .query_row(
+                    "SELECT IFNULL(SUM(amount), 0) FROM budgets WHERE category_id = ?1 AND month = ?2",
+                    params![category_id, cursor],
+                    |row| row.get(0),
+                )
+                .map_err(|e| e.to_string())?;
+            let activity: f64 = conn
+                .query_row(
+                    "SELECT IFNULL(SUM(ABS(amount) + IFNULL(ABS(fee), 0)), 0) FROM transactions
+                     WHERE category_id = ?1 AND type = 'Expense' AND strftime('%Y-%m', date) = ?2",
+                    params![category_id, cursor],
+                    |row| row.get(0),
+                )
+                .map_err(|e| e.to_string())?;
+
+            let carryover = running.get(category_id).copied().unwrap_or(0.0);
+            let available = budgeted + carryover - activity;
+            running.insert(*category_id, if available < 0.0 && !carry_overspending { 0.0 } else { available });
+
+            if cursor == month {
+                status.push(BudgetStatus {
+                    category_id: *category_id,
+                    category_name: category_name.clone(),
+                    budgeted,
+                    activity,
+                    available,
+                });
+            }
+        }
+
+        if cursor == month {
+            break;
+        }
+        cursor = next_month(&cursor)?;
+    }
+
+    Ok(status)
+}
\ No newline at end of file
@@ -1,14 +1,15 @@
-use rusqlite::{params, Connection};
+use std::collections::HashMap;
+
+use rusqlite::{params, Connection, OptionalExtension};
 use serde_json::Value;
-use tauri::AppHandle;
+use tauri::{AppHandle, State};
 
+use crate::encryption::{open_encrypted, DbKeyState};
 use crate::models::Category;
-use crate::utils::get_db_path;
 
 #[tauri::command]
-pub fn get_categories(app: AppHandle) -> Result<Vec<String>, String> {
-    let path = get_db_path(&app);
-    let conn = Connection::open(path).map_err(|e| e.to_string())?;
+pub fn get_categories(app: AppHandle, key_state: State<'_, DbKeyState>) -> Result<Vec<String>, String> {
+    let conn = open_encrypted(&app, &key_state)?;
     let mut stmt = conn.prepare("SELECT name FROM categories ORDER BY name").map_err(|e| e.to_string())?;
     let rows = stmt.query_map([], |row| row.get(0)).map_err(|e| e.to_string())?;
     let mut categories = Vec::new();
@@ -19,11 +20,10 @@ pub fn get_categories(app: AppHandle) -> Result<Vec<String>, String> {
 }
 
 #[tauri::command]
-pub fn get_categories_full(app: AppHandle) -> Result<Vec<Category>, String> {
-    let path = get_db_path(&app);
-    let conn = Connection::open(path).map_err(|e| e.to_string())?;
+pub fn get_categories_full(app: AppHandle, key_state: State<'_, DbKeyState>) -> Result<Vec<Category>, String> {
+    let conn = open_encrypted(&app, &key_state)?;
     let mut stmt = conn.prepare(
-        "SELECT id, name, type, is_reimbursement, reimbursement_target_category_id FROM categories ORDER BY name"
+        "SELECT id, name, type, is_reimbursement, reimbursement_target_category_id, carry_overspending FROM categories ORDER BY name"
     ).map_err(|e| e.to_string())?;
     let rows = stmt.query_map([], |row| {
         Ok(Category {
@@ -32,6 +32,7 @@ pub fn get_categories_full(app: AppHandle) -> Result<Vec<Category>, String> {
             category_type: row.get(2)?,
             is_reimbursement: row.get(3)?,
             reimbursement_target_category_id: row.get(4)?,
+            carry_overspending: row.get(5)?,
         })
     }).map_err(|e| e.to_string())?;
     let mut categories = Vec::new();
@@ -44,193 +45,287 @@ pub fn get_categories_full(app: AppHandle) -> Result<Vec<Category>, String> {
 #[tauri::command]
 pub fn add_category(
     app: AppHandle,
+    key_state: State<'_, DbKeyState>,
     name: String,
     category_type: String,
     is_reimbursement: bool,
-    reimbursement_target_category_id: Option<i64>
+    reimbursement_target_category_id: Option<i64>,
+    carry_overspending: bool
 ) -> Result<Vec<Category>, String> {
-    let path = get_db_path(&app);
-    let conn = Connection::open(path).map_err(|e| e.to_string())?;
+    let conn = open_encrypted(&app, &key_state)?;
     conn.execute(
-        "INSERT INTO categories (name, type, is_reimbursement, reimbursement_target_category_id) VALUES (?1, ?2, ?3, ?4)",
-        params![name, category_type, is_reimbursement, reimbursement_target_category_id],
+        "INSERT INTO categories (name, type, is_reimbursement, reimbursement_target_category_id, carry_overspending) VALUES (?1, ?2, ?3, ?4, ?5)",
+        params![name, category_type, is_reimbursement, reimbursement_target_category_id, carry_overspending],
     )
     .map_err(|e| e.to_string())?;
-    get_categories_full(app)
+    get_categories_full(app, key_state)
 }
 
 #[tauri::command]
 pub fn update_category(
     app: AppHandle,
+    key_state: State<'_, DbKeyState>,
     id: i64,
     name: String,
     category_type: String,
     is_reimbursement: bool,
-    reimbursement_target_category_id: Option<i64>
+    reimbursement_target_category_id: Option<i64>,
+    carry_overspending: bool
 ) -> Result<Vec<Category>, String> {
-    let path = get_db_path(&app);
-    let conn = Connection::open(path).map_err(|e| e.to_string())?;
+    let conn = open_encrypted(&app, &key_state)?;
     conn.execute(
-        "UPDATE categories SET name = ?1, type = ?2, is_reimbursement = ?3, reimbursement_target_category_id = ?4 WHERE id = ?5",
-        params![name, category_type, is_reimbursement, reimbursement_target_category_id, id],
+        "UPDATE categories SET name = ?1, type = ?2, is_reimbursement = ?3, reimbursement_target_category_id = ?4, carry_overspending = ?5 WHERE id = ?6",
+        params![name, category_type, is_reimbursement, reimbursement_target_category_id, carry_overspending, id],
     )
     .map_err(|e| e.to_string())?;
-    get_categories_full(app)
+    get_categories_full(app, key_state)
 }
 
 #[tauri::command]
-pub fn delete_category(app: AppHandle, id: i64) -> Result<Vec<Category>, String> {
-    let path = get_db_path(&app);
-    let conn = Connection::open(path).map_err(|e| e.to_string())?;
+pub fn delete_category(app: AppHandle, key_state: State<'_, DbKeyState>, id: i64) -> Result<Vec<Category>, String> {
+    let conn = open_encrypted(&app, &key_state)?;
     conn.execute(
         "DELETE FROM categories WHERE id = ?1",
         params![id],
     )
     .map_err(|e| e.to_string())?;
-    get_categories_full(app)
+    get_categories_full(app, key_state)
+}
+
+/// One category's aggregated totals for [`get_spending_by_category`], before reimbursement
+/// netting is applied.
+struct CategoryTotals {
+    category_id: Option<i64>,
+    name: String,
+    is_reimbursement: bool,
+    reimbursement_target_category_id: Option<i64>,
+    expense: f64,
+    income: f64,
 }
 
 #[tauri::command]
-pub fn get_spending_by_category(app: AppHandle, start_date: String, end_date: String) -> Result<Value, String> {
-    let path = get_db_path(&app);
-    let conn = Connection::open(path).map_err(|e| e.to_string())?;
-    
+pub fn get_spending_by_category(
+    app: AppHandle,
+    key_state: State<'_, DbKeyState>,
+    start_date: String,
+    end_date: String,
+    net_reimbursements: Option<bool>,
+) -> Result<Value, String> {
+    let conn = open_encrypted(&app, &key_state)?;
+
     let mut stmt = conn.prepare(
-        "SELECT c.name, SUM(CASE WHEN t.type = 'Expense' THEN t.amount ELSE 0 END) as expense,
-         SUM(CASE WHEN t.type = 'Income' THEN t.amount ELSE 0 END) as income
-         FROM transactions t
-         LEFT JOIN categories c ON t.category_id = c.id
-         WHERE t.date BETWEEN ?1 AND ?2
-         AND t.type != 'Transfer'
-         GROUP BY c.name
+        "SELECT category_id, category_name, is_reimbursement, reimbursement_target_category_id,
+         SUM(CASE WHEN type = 'Expense' THEN amount ELSE 0 END) as expense,
+         SUM(CASE WHEN type = 'Income' THEN amount ELSE 0 END) as income
+         FROM v_transactions_resolved
+         WHERE date BETWEEN ?1 AND ?2
+         AND type != 'Transfer'
+         GROUP BY category_id, category_name, is_reimbursement, reimbursement_target_category_id
          HAVING expense > 0 OR income > 0"
     ).map_err(|e| e.to_string())?;
-    
+
     let rows = stmt.query_map(params![start_date, end_date], |row| {
-        Ok((
-            row.get::<_, String>(0)?,
-            row.get::<_, f64>(1)?,
-            row.get::<_, f64>(2)?,
-        ))
+        Ok(CategoryTotals {
+            category_id: row.get(0)?,
+            name: row.get(1)?,
+            is_reimbursement: row.get(2)?,
+            reimbursement_target_category_id: row.get(3)?,
+            expense: row.get(4)?,
+            income: row.get(5)?,
+        })
     }).map_err(|e| e.to_string())?;
-    
+
+    let mut totals = Vec::new();
+    for row in rows {
+        totals.push(row.map_err(|e| e.to_string())?);
+    }
+
+    // Net mode folds each reimbursement category's income back into the `expense` total of its
+    // `reimbursement_target_category_id`, so a refunded work expense shows its true out-of-pocket
+    // cost instead of being counted as both spend and income.
+    let net = net_reimbursements.unwrap_or(false);
+    let mut reimbursed_by_target: HashMap<i64, f64> = HashMap::new();
+    if net {
+        for total in &totals {
+            if total.is_reimbursement {
+                if let Some(target_id) = total.reimbursement_target_category_id {
+                    *reimbursed_by_target.entry(target_id).or_insert(0.0) += total.income;
+                }
+            }
+        }
+    }
+
     let mut categories = Vec::new();
     let mut expenses = Vec::new();
     let mut incomes = Vec::new();
-    
-    for row in rows {
-        let (category, expense, income) = row.map_err(|e| e.to_string())?;
-        categories.push(category);
-        expenses.push(expense);
-        incomes.push(income);
+    let mut reimbursed = Vec::new();
+
+    for total in &totals {
+        if net && total.is_reimbursement && total.reimbursement_target_category_id.is_some() {
+            continue;
+        }
+        let reimbursed_amount = total
+            .category_id
+            .and_then(|id| reimbursed_by_target.get(&id))
+            .copied()
+            .unwrap_or(0.0);
+        categories.push(total.name.clone());
+        expenses.push(if net { (total.expense - reimbursed_amount).max(0.0) } else { total.expense });
+        incomes.push(total.income);
+        reimbursed.push(reimbursed_amount);
     }
-    
+
     Ok(serde_json::json!({
         "categories": categories,
         "expenses": expenses,
-        "incomes": incomes
+        "incomes": incomes,
+        "reimbursed": reimbursed
     }))
 }
 
 #[tauri::command]
-pub fn get_income_vs_expenses(app: AppHandle, start_date: String, end_date: String) -> Result<Value, String> {
-    let path = get_db_path(&app);
-    let conn = Connection::open(path).map_err(|e| e.to_string())?;
-    
+pub fn get_income_vs_expenses(
+    app: AppHandle,
+    key_state: State<'_, DbKeyState>,
+    start_date: String,
+    end_date: String,
+    net_reimbursements: Option<bool>,
+) -> Result<Value, String> {
+    let conn = open_encrypted(&app, &key_state)?;
+
+    // `reimbursed` is the portion of each month's income that came from a reimbursement
+    // category; in net mode it's backed out of both totals so a refunded expense stops showing
+    // up as both spend and income.
     let mut stmt = conn.prepare(
         "SELECT strftime('%Y-%m', date) as month,
          SUM(CASE WHEN type = 'Expense' THEN amount ELSE 0 END) as expenses,
-         SUM(CASE WHEN type = 'Income' THEN amount ELSE 0 END) as income
-         FROM transactions
+         SUM(CASE WHEN type = 'Income' THEN amount ELSE 0 END) as income,
+         SUM(CASE WHEN type = 'Income' AND is_reimbursement = 1 THEN amount ELSE 0 END) as reimbursed
+         FROM v_transactions_resolved
          WHERE date BETWEEN ?1 AND ?2
          AND type != 'Transfer'
          GROUP BY month
          ORDER BY month"
     ).map_err(|e| e.to_string())?;
-    
+
     let rows = stmt.query_map(params![start_date, end_date], |row| {
         Ok((
             row.get::<_, String>(0)?,
             row.get::<_, f64>(1)?,
             row.get::<_, f64>(2)?,
+            row.get::<_, f64>(3)?,
         ))
     }).map_err(|e| e.to_string())?;
-    
+
+    let net = net_reimbursements.unwrap_or(false);
     let mut months = Vec::new();
     let mut expenses = Vec::new();
     let mut incomes = Vec::new();
-    
+    let mut reimbursed = Vec::new();
+
     for row in rows {
-        let (month, expense, income) = row.map_err(|e| e.to_string())?;
+        let (month, expense, income, reimbursed_amount) = row.map_err(|e| e.to_string())?;
         months.push(month);
-        expenses.push(expense);
-        incomes.push(income);
+        if net {
+            expenses.push((expense - reimbursed_amount).max(0.0));
+            incomes.push(income - reimbursed_amount);
+        } else {
+            expenses.push(expense);
+            incomes.push(income);
+        }
+        reimbursed.push(reimbursed_amount);
     }
-    
+
     Ok(serde_json::json!({
         "months": months,
         "expenses": expenses,
-        "incomes": incomes
+        "incomes": incomes,
+        "reimbursed": reimbursed
     }))
 }
 
 #[tauri::command]
-pub fn get_net_worth_history(app: AppHandle, start_date: String, end_date: String) -> Result<Value, String> {
-    let path = get_db_path(&app);
-    let conn = Connection::open(path).map_err(|e| e.to_string())?;
-    
-    let mut stmt = conn.prepare(
+pub fn get_net_worth_history(
+    app: AppHandle,
+    key_state: State<'_, DbKeyState>,
+    start_date: String,
+    end_date: String,
+    granularity: Option<String>,
+    base_currency: Option<String>,
+) -> Result<Value, String> {
+    let conn = open_encrypted(&app, &key_state)?;
+    let base_currency = base_currency.unwrap_or_else(|| crate::accounts::BASE_CURRENCY.to_string());
+    net_worth_history(&conn, &start_date, &end_date, granularity.as_deref(), &base_currency)
+}
+
+/// Step between successive points in the series, e.g. `'+1 month'` for SQLite's `date()`
+/// modifier syntax. Falls back to monthly for `None` or any unrecognized value so older
+/// frontend builds that don't yet pass `granularity` keep their existing behavior.
+fn granularity_step(granularity: Option<&str>) -> &'static str {
+    match granularity {
+        Some("daily") => "+1 day",
+        Some("weekly") => "+7 days",
+        _ => "+1 month",
+    }
+}
+
+/// Each point's balance is the sum of every `v_transactions_net.net_value` up to and including
+/// that point's date — i.e. the account balance *as of that date* — not today's balance walked
+/// forward, so earlier points in the series correctly predate later deltas. Each account's
+/// native-currency balance is then converted into `base_currency` using the most recent
+/// `exchange_rates` quote on or before that same point's date (the same "latest rate
+/// on-or-before" join `BALANCE_SUM_SQL`/`get_net_worth` already use), so `net_worth`/`assets`/
+/// `liabilities` stay meaningful for accounts that aren't already denominated in it.
+fn net_worth_history(conn: &Connection, start_date: &str, end_date: &str, granularity: Option<&str>, base_currency: &str) -> Result<Value, String> {
+    let step = granularity_step(granularity);
+
+    let mut stmt = conn.prepare(&format!(
         "WITH RECURSIVE dates(date) AS (
             SELECT ?1
             UNION ALL
-            SELECT date(date, '+1 month')
+            SELECT date(date, '{step}')
             FROM dates
             WHERE date < ?2
         ),
-        monthly_balances AS (
-            SELECT 
+        point_balances AS (
+            SELECT
                 d.date,
                 a.id as account_id,
                 a.type as account_type,
-                COALESCE(SUM(CASE 
-                    WHEN a.type = 'Credit' THEN
-                        CASE
-                            WHEN t.type = 'Expense' THEN ABS(t.amount)
-                            WHEN t.type = 'Income' THEN -ABS(t.amount)
-                            WHEN t.type = 'Adjust' AND c.name = 'Add' THEN -ABS(t.amount)
-                            WHEN t.type = 'Adjust' AND c.name = 'Subtract' THEN ABS(t.amount)
-                            WHEN t.type = 'Transfer' THEN t.amount
-                            ELSE 0
-                        END
-                    ELSE
-                        CASE
-                            WHEN t.type = 'Expense' THEN -ABS(t.amount)
-                            WHEN t.type = 'Income' THEN ABS(t.amount)
-                            WHEN t.type = 'Adjust' AND c.name = 'Add' THEN ABS(t.amount)
-                            WHEN t.type = 'Adjust' AND c.name = 'Subtract' THEN -ABS(t.amount)
-                            WHEN t.type = 'Transfer' THEN t.amount
-                            ELSE 0
-                        END
-                    END
-                ), 0) as balance
+                a.currency as currency,
+                COALESCE(SUM(v.net_value), 0) as native_balance
             FROM dates d
             CROSS JOIN accounts a
-            LEFT JOIN transactions t ON t.account_id = a.id 
-                AND t.date <= d.date
-            LEFT JOIN categories c ON t.category_id = c.id
+            LEFT JOIN v_transactions_net v ON v.account_id = a.id
+                AND v.date <= d.date
             GROUP BY d.date, a.id
+        ),
+        converted_balances AS (
+            SELECT
+                pb.date,
+                pb.account_type,
+                pb.native_balance *
+                    CASE
+                        WHEN pb.currency = ?3 THEN 1.0
+                        ELSE IFNULL((
+                            SELECT er.rate FROM exchange_rates er
+                            WHERE er.currency = pb.currency AND er.date <= pb.date
+                            ORDER BY er.date DESC LIMIT 1
+                        ), 1.0)
+                    END as converted_balance
+            FROM point_balances pb
         )
-        SELECT 
+        SELECT
             date,
-            SUM(balance) as net_worth,
-            SUM(CASE WHEN account_type != 'Credit' THEN balance ELSE 0 END) as assets,
-            SUM(CASE WHEN account_type = 'Credit' THEN balance ELSE 0 END) as liabilities
-        FROM monthly_balances
+            SUM(converted_balance) as net_worth,
+            SUM(CASE WHEN account_type != 'Credit' THEN converted_balance ELSE 0 END) as assets,
+            SUM(CASE WHEN account_type = 'Credit' THEN converted_balance ELSE 0 END) as liabilities
+        FROM converted_balances
         GROUP BY date
         ORDER BY date"
-    ).map_err(|e| e.to_string())?;
-    
-    let rows = stmt.query_map(params![start_date, end_date], |row| {
+    )).map_err(|e| e.to_string())?;
+
+    let rows = stmt.query_map(params![start_date, end_date, base_currency], |row| {
         Ok((
             row.get::<_, String>(0)?,
             row.get::<_, f64>(1)?,
@@ -238,12 +333,12 @@ pub fn get_net_worth_history(app: AppHandle, start_date: String, end_date: Strin
             row.get::<_, f64>(3)?,
         ))
     }).map_err(|e| e.to_string())?;
-    
+
     let mut dates = Vec::new();
     let mut net_worth = Vec::new();
     let mut assets = Vec::new();
     let mut liabilities = Vec::new();
-    
+
     for row in rows {
         let (date, nw, a, l) = row.map_err(|e| e.to_string())?;
         dates.push(date);
@@ -251,11 +346,251 @@ pub fn get_net_worth_history(app: AppHandle, start_date: String, end_date: Strin
         assets.push(a);
         liabilities.push(l);
     }
-    
+
     Ok(serde_json::json!({
         "dates": dates,
         "net_worth": net_worth,
         "assets": assets,
-        "liabilities": liabilities
+        "liabilities": liabilities,
+        "base_currency": base_currency,
+        "per_currency": per_currency_subtotals(conn, end_date, base_currency)?
+    }))
+}
+
+/// Each currency actually used by an account, with its combined native balance as of `as_of_date`
+/// and that total converted into `base_currency` via the same latest-rate-on-or-before lookup
+/// `net_worth_history` uses per point — a snapshot breakdown alongside the converted time series,
+/// so the UI can show e.g. "USD 4,200 + EUR 1,100" next to the single converted total.
+fn per_currency_subtotals(conn: &Connection, as_of_date: &str, base_currency: &str) -> Result<Value, String> {
+    let mut stmt = conn.prepare(
+        "SELECT a.currency, IFNULL(SUM(v.net_value), 0) as native_balance
+         FROM accounts a
+         LEFT JOIN v_transactions_net v ON v.account_id = a.id AND v.date <= ?1
+         GROUP BY a.currency"
+    ).map_err(|e| e.to_string())?;
+
+    let rows = stmt.query_map(params![as_of_date], |row| {
+        Ok((row.get::<_, String>(0)?, row.get::<_, f64>(1)?))
+    }).map_err(|e| e.to_string())?;
+
+    let mut subtotals = Vec::new();
+    for row in rows {
+        let (currency, native_balance) = row.map_err(|e| e.to_string())?;
+        let rate: f64 = if currency == base_currency {
+            1.0
+        } else {
+            conn.query_row(
+                "SELECT rate FROM exchange_rates WHERE currency = ?1 AND date <= ?2 ORDER BY date DESC LIMIT 1",
+                params![currency, as_of_date],
+                |row| row.get(0),
+            ).optional().map_err(|e| e.to_string())?.unwrap_or(1.0)
+        };
+        subtotals.push(serde_json::json!({
+            "currency": currency,
+            "native_balance": native_balance,
+            "rate": rate,
+            "converted_balance": native_balance * rate
+        }));
+    }
+
+    Ok(Value::Array(subtotals))
+}
+
+/// Income/expense totals for `month` (format `YYYY-MM`) plus a per-category breakdown.
+/// Transfers are excluded since they move money between the user's own accounts rather than
+/// in or out, which would otherwise inflate both totals.
+#[tauri::command]
+pub fn get_monthly_summary(app: AppHandle, key_state: State<'_, DbKeyState>, month: String) -> Result<Value, String> {
+    let conn = open_encrypted(&app, &key_state)?;
+
+    let (total_income, total_expense): (f64, f64) = conn.query_row(
+        "SELECT
+            IFNULL(SUM(CASE WHEN t.type = 'Income' THEN ABS(t.amount) ELSE 0 END), 0),
+            IFNULL(SUM(CASE WHEN t.type = 'Expense' THEN ABS(t.amount) + IFNULL(ABS(t.fee), 0) ELSE 0 END), 0)
+         FROM transactions t
+         WHERE strftime('%Y-%m', t.date) = ?1 AND t.type != 'Transfer'",
+        params![month],
+        |row| Ok((row.get(0)?, row.get(1)?)),
+    ).map_err(|e| e.to_string())?;
+
+    let mut stmt = conn.prepare(
+        "SELECT c.name,
+            IFNULL(SUM(CASE WHEN t.type = 'Income' THEN ABS(t.amount) ELSE 0 END), 0) as income,
+            IFNULL(SUM(CASE WHEN t.type = 'Expense' THEN ABS(t.amount) + IFNULL(ABS(t.fee), 0) ELSE 0 END), 0) as expense
+         FROM transactions t
+         LEFT JOIN categories c ON t.category_id = c.id
+         WHERE strftime('%Y-%m', t.date) = ?1 AND t.type != 'Transfer'
+         GROUP BY c.name
+         HAVING income > 0 OR expense > 0
+         ORDER BY c.name"
+    ).map_err(|e| e.to_string())?;
+
+    let rows = stmt.query_map(params![month], |row| {
+        Ok(serde_json::json!({
+            "category": row.get::<_, Option<String>>(0)?,
+            "income": row.get::<_, f64>(1)?,
+            "expense": row.get::<_, f64>(2)?,
+        }))
+    }).map_err(|e| e.to_string())?;
+
+    let mut categories = Vec::new();
+    for row in rows {
+        categories.push(row.map_err(|e| e.to_string())?);
+    }
+
+    Ok(serde_json::json!({
+        "month": month,
+        "total_income": total_income,
+        "total_expense": total_expense,
+        "net": total_income - total_expense,
+        "categories": categories
+    }))
+}
+
+/// For every budget line set for `month`, compares it against actual expense transactions in
+/// that category and month, returning `remaining` (budget minus actual, negative if over) and
+/// `overspent` so the UI doesn't have to recompute the comparison itself.
+#[tauri::command]
+pub fn get_budget_vs_actual(app: AppHandle, key_state: State<'_, DbKeyState>, month: String) -> Result<Value, String> {
+    let conn = open_encrypted(&app, &key_state)?;
+
+    let mut stmt = conn.prepare(
+        "SELECT c.id, c.name, b.amount,
+            IFNULL((
+                SELECT SUM(ABS(t.amount) + IFNULL(ABS(t.fee), 0))
+                FROM transactions t
+                WHERE t.category_id = c.id AND t.type = 'Expense' AND strftime('%Y-%m', t.date) = ?1
+            ), 0) as actual
+         FROM budgets b
+         JOIN categories c ON b.category_id = c.id
+         WHERE b.month = ?1
+         ORDER BY c.name"
+    ).map_err(|e| e.to_string())?;
+
+    let rows = stmt.query_map(params![month], |row| {
+        let budgeted: f64 = row.get(2)?;
+        let actual: f64 = row.get(3)?;
+        Ok(serde_json::json!({
+            "category_id": row.get::<_, i64>(0)?,
+            "category": row.get::<_, String>(1)?,
+            "budgeted": budgeted,
+            "actual": actual,
+            "remaining": budgeted - actual,
+            "overspent": actual > budgeted,
+        }))
+    }).map_err(|e| e.to_string())?;
+
+    let mut items = Vec::new();
+    for row in rows {
+        items.push(row.map_err(|e| e.to_string())?);
+    }
+
+    Ok(serde_json::json!({ "month": month, "items": items }))
+}
+
+/// Walks `account_id`'s transactions between `from` and `to` in date order, producing a
+/// running-balance time series using the same per-account-type sign logic as
+/// [`crate::accounts::BALANCE_SUM_SQL`], for feeding a balance-over-time chart.
+#[tauri::command]
+pub fn get_balance_history(app: AppHandle, key_state: State<'_, DbKeyState>, account_id: i64, from: String, to: String) -> Result<Value, String> {
+    let conn = open_encrypted(&app, &key_state)?;
+
+    let mut stmt = conn.prepare(
+        "SELECT v.date, v.transaction_id,
+            SUM(v.net_value) OVER (ORDER BY v.date, v.transaction_id) as running_balance
+         FROM v_transactions_net v
+         WHERE v.account_id = ?1 AND v.date BETWEEN ?2 AND ?3
+         ORDER BY v.date, v.transaction_id"
+    ).map_err(|e| e.to_string())?;
+
+    let rows = stmt.query_map(params![account_id, from, to], |row| {
+        Ok((
+            row.get::<_, String>(0)?,
+            row.get::<_, i64>(1)?,
+            row.get::<_, f64>(2)?,
+        ))
+    }).map_err(|e| e.to_string())?;
+
+    let mut dates = Vec::new();
+    let mut transaction_ids = Vec::new();
+    let mut balances = Vec::new();
+    for row in rows {
+        let (date, transaction_id, balance) = row.map_err(|e| e.to_string())?;
+        dates.push(date);
+        transaction_ids.push(transaction_id);
+        balances.push(balance);
+    }
+
+    Ok(serde_json::json!({
+        "account_id": account_id,
+        "dates": dates,
+        "transaction_ids": transaction_ids,
+        "balances": balances
     }))
-} 
\ No newline at end of file
+}
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::migrations::run_migrations;
+
+    fn setup_db() -> Connection {
+        let mut conn = Connection::open_in_memory().unwrap();
+        run_migrations(&mut conn).unwrap();
+        conn.execute("INSERT INTO accounts (name, type, balance, currency) VALUES ('Checking', 'Checking', 0, 'USD')", []).unwrap();
+        conn.execute("INSERT INTO accounts (name, type, balance, currency) VALUES ('Savings', 'Savings', 0, 'USD')", []).unwrap();
+        conn
+    }
+
+    #[test]
+    fn granularity_step_defaults_to_monthly() {
+        assert_eq!(granularity_step(None), "+1 month");
+        assert_eq!(granularity_step(Some("bogus")), "+1 month");
+        assert_eq!(granularity_step(Some("daily")), "+1 day");
+        assert_eq!(granularity_step(Some("weekly")), "+7 days");
+    }
+
+    #[test]
+    fn net_worth_reflects_balance_as_of_each_point_not_todays_balance() {
+        let conn = setup_db();
+        conn.execute(
+            "INSERT INTO transactions (date, account_id, type, category_id, amount, payee) VALUES ('2026-01-10', 1, 'Income', NULL, 1000, 'Employer')",
+            [],
+        ).unwrap();
+        conn.execute(
+            "INSERT INTO transactions (date, account_id, type, category_id, amount, payee) VALUES ('2026-02-10', 1, 'Expense', NULL, 200, 'Groceries')",
+            [],
+        ).unwrap();
+
+        let result = net_worth_history(&conn, "2026-01-01", "2026-03-01", Some("daily"), "USD").unwrap();
+        let dates = result["dates"].as_array().unwrap();
+        let net_worth = result["net_worth"].as_array().unwrap();
+
+        let before_income = dates.iter().position(|d| d.as_str().unwrap() == "2026-01-09").unwrap();
+        assert_eq!(net_worth[before_income].as_f64().unwrap(), 0.0);
+
+        let after_income = dates.iter().position(|d| d.as_str().unwrap() == "2026-01-10").unwrap();
+        assert_eq!(net_worth[after_income].as_f64().unwrap(), 1000.0);
+
+        let after_expense = dates.iter().position(|d| d.as_str().unwrap() == "2026-02-10").unwrap();
+        assert_eq!(net_worth[after_expense].as_f64().unwrap(), 800.0);
+    }
+
+    #[test]
+    fn transfer_between_own_accounts_is_net_zero() {
+        let conn = setup_db();
+        conn.execute(
+            "INSERT INTO transactions (date, account_id, to_account_id, type, category_id, amount, payee, transfer_id) VALUES ('2026-01-15', 1, 2, 'Transfer', NULL, -500, 'Move to savings', 1)",
+            [],
+        ).unwrap();
+        conn.execute(
+            "INSERT INTO transactions (date, account_id, to_account_id, type, category_id, amount, payee, transfer_id) VALUES ('2026-01-15', 2, 1, 'Transfer', NULL, 500, 'Move to savings', 1)",
+            [],
+        ).unwrap();
+
+        let result = net_worth_history(&conn, "2026-01-01", "2026-02-01", Some("monthly"), "USD").unwrap();
+        let net_worth = result["net_worth"].as_array().unwrap();
+        for point in net_worth {
+            assert_eq!(point.as_f64().unwrap(), 0.0);
+        }
+    }
+}
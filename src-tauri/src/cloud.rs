@@ -0,0 +1,197 @@
+use serde::{Deserialize, Serialize};
+
+/// Direct-to-cloud counterpart to `sync.rs`'s locally-mounted OneDrive folder sync: talks to
+/// Microsoft Graph over HTTPS so backups still reach OneDrive for users without the OneDrive
+/// client installed (headless/server setups, or a machine that's never run the desktop sync
+/// agent). Requires the `reqwest` crate (json feature) as a new dependency.
+const GRAPH_BASE: &str = "https://graph.microsoft.com/v1.0";
+const DEVICE_CODE_URL: &str = "https://login.microsoftonline.com/common/oauth2/v2.0/devicecode";
+const TOKEN_URL: &str = "https://login.microsoftonline.com/common/oauth2/v2.0/token";
+/// `Files.ReadWrite` is enough to create folders and upload under the user's own drive;
+/// `offline_access` lets `cloud_refresh_token` renew the session without a fresh sign-in.
+const GRAPH_SCOPE: &str = "Files.ReadWrite offline_access";
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct GraphDeviceCode {
+    pub device_code: String,
+    pub user_code: String,
+    pub verification_uri: String,
+    pub expires_in: i64,
+    pub interval: i64,
+    pub message: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct DeviceCodeResponse {
+    device_code: String,
+    user_code: String,
+    verification_uri: String,
+    expires_in: i64,
+    interval: i64,
+    message: String,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct GraphToken {
+    pub access_token: String,
+    pub refresh_token: Option<String>,
+    pub expires_in: i64,
+}
+
+#[derive(Debug, Deserialize)]
+struct TokenResponse {
+    access_token: String,
+    refresh_token: Option<String>,
+    expires_in: i64,
+}
+
+#[derive(Debug, Deserialize)]
+struct TokenErrorResponse {
+    error: String,
+}
+
+/// Step 1 of the OAuth2 device-code flow: asks Graph for a `user_code`/`verification_uri` pair
+/// for the frontend to show the user, plus a `device_code` to poll with
+/// [`cloud_poll_device_code`] once they've approved it in a browser on any device.
+#[tauri::command]
+pub async fn cloud_start_device_code(client_id: String) -> Result<GraphDeviceCode, String> {
+    let client = reqwest::Client::new();
+    let resp = client
+        .post(DEVICE_CODE_URL)
+        .form(&[("client_id", client_id.as_str()), ("scope", GRAPH_SCOPE)])
+        .send()
+        .await
+        .map_err(|e| e.to_string())?;
+
+    if !resp.status().is_success() {
+        return Err(format!("Failed to start device code flow: HTTP {}", resp.status()));
+    }
+
+    let body: DeviceCodeResponse = resp.json().await.map_err(|e| e.to_string())?;
+    Ok(GraphDeviceCode {
+        device_code: body.device_code,
+        user_code: body.user_code,
+        verification_uri: body.verification_uri,
+        expires_in: body.expires_in,
+        interval: body.interval,
+        message: body.message,
+    })
+}
+
+/// Step 2: polls the token endpoint once for the given `device_code`. Returns
+/// `Ok(None)` while the user hasn't finished authorizing yet (`authorization_pending`) so the
+/// frontend can keep calling this on `interval` seconds from [`GraphDeviceCode`]; any other
+/// error (expired code, denied) is returned as `Err`.
+#[tauri::command]
+pub async fn cloud_poll_device_code(client_id: String, device_code: String) -> Result<Option<GraphToken>, String> {
+    let client = reqwest::Client::new();
+    let resp = client
+        .post(TOKEN_URL)
+        .form(&[
+            ("client_id", client_id.as_str()),
+            ("device_code", device_code.as_str()),
+            ("grant_type", "urn:ietf:params:oauth:grant-type:device_code"),
+        ])
+        .send()
+        .await
+        .map_err(|e| e.to_string())?;
+
+    if resp.status().is_success() {
+        let body: TokenResponse = resp.json().await.map_err(|e| e.to_string())?;
+        return Ok(Some(GraphToken {
+            access_token: body.access_token,
+            refresh_token: body.refresh_token,
+            expires_in: body.expires_in,
+        }));
+    }
+
+    let body: TokenErrorResponse = resp.json().await.map_err(|e| e.to_string())?;
+    if body.error == "authorization_pending" {
+        return Ok(None);
+    }
+    Err(format!("Device code authorization failed: {}", body.error))
+}
+
+/// Exchanges a previously-granted `refresh_token` for a new access token without another
+/// device-code round trip, so a backup triggered hours after sign-in doesn't need user
+/// interaction again.
+#[tauri::command]
+pub async fn cloud_refresh_token(client_id: String, refresh_token: String) -> Result<GraphToken, String> {
+    let client = reqwest::Client::new();
+    let resp = client
+        .post(TOKEN_URL)
+        .form(&[
+            ("client_id", client_id.as_str()),
+            ("refresh_token", refresh_token.as_str()),
+            ("grant_type", "refresh_token"),
+        ])
+        .send()
+        .await
+        .map_err(|e| e.to_string())?;
+
+    if !resp.status().is_success() {
+        return Err(format!("Failed to refresh token: HTTP {}", resp.status()));
+    }
+
+    let body: TokenResponse = resp.json().await.map_err(|e| e.to_string())?;
+    Ok(GraphToken {
+        access_token: body.access_token,
+        refresh_token: body.refresh_token,
+        expires_in: body.expires_in,
+    })
+}
+
+/// Creates `path` (e.g. `"WalnutBook_Data/Backups"`) under the user's OneDrive root via
+/// `POST /me/drive/root/children`, using `conflictBehavior: "fail"` combined with treating a
+/// 409 response as success — Graph has no "create if missing" verb, so this is the standard
+/// idempotent-create pattern for it.
+#[tauri::command]
+pub async fn cloud_create_directory(access_token: String, path: String) -> Result<(), String> {
+    let (parent, name) = match path.rsplit_once('/') {
+        Some((parent, name)) => (format!(":/{}:", parent), name.to_string()),
+        None => (String::new(), path.clone()),
+    };
+    let url = format!("{}/me/drive/root{}/children", GRAPH_BASE, parent);
+
+    let client = reqwest::Client::new();
+    let resp = client
+        .post(&url)
+        .bearer_auth(access_token)
+        .json(&serde_json::json!({
+            "name": name,
+            "folder": {},
+            "@microsoft.graph.conflictBehavior": "fail"
+        }))
+        .send()
+        .await
+        .map_err(|e| e.to_string())?;
+
+    // A 409 means the folder is already there, which is exactly what we want.
+    if resp.status().is_success() || resp.status() == reqwest::StatusCode::CONFLICT {
+        return Ok(());
+    }
+    Err(format!("Failed to create cloud directory {}: HTTP {}", path, resp.status()))
+}
+
+/// Uploads `bytes` to `remote_path` (e.g. `"WalnutBook_Data/Backups/2026-07-29.bak"`) via
+/// `PUT /me/drive/root:/{path}:/content`, overwriting whatever was there — Graph's simple
+/// upload endpoint handles files up to 4MB, which comfortably covers this app's database-sized
+/// backups without needing the resumable upload session API.
+#[tauri::command]
+pub async fn cloud_upload_backup(access_token: String, remote_path: String, bytes: Vec<u8>) -> Result<(), String> {
+    let url = format!("{}/me/drive/root:/{}:/content", GRAPH_BASE, remote_path);
+
+    let client = reqwest::Client::new();
+    let resp = client
+        .put(&url)
+        .bearer_auth(access_token)
+        .body(bytes)
+        .send()
+        .await
+        .map_err(|e| e.to_string())?;
+
+    if resp.status().is_success() {
+        return Ok(());
+    }
+    Err(format!("Failed to upload backup to {}: HTTP {}", remote_path, resp.status()))
+}
@@ -0,0 +1,142 @@
+use std::sync::{Arc, Mutex};
+
+use rusqlite::Connection;
+use tauri::State;
+
+use crate::utils::{get_db_path, DbPoolHandle};
+
+/// Holds the passphrase unlocked for the current session, shared with the pooled connection
+/// manager so every newly-opened physical connection gets `PRAGMA key` applied automatically.
+/// `None` means the database is either not yet encrypted or still locked.
+pub struct DbKeyState(pub Arc<Mutex<Option<String>>>);
+
+impl DbKeyState {
+    pub fn new() -> Self {
+        DbKeyState(Arc::new(Mutex::new(None)))
+    }
+
+    pub fn handle(&self) -> Arc<Mutex<Option<String>>> {
+        self.0.clone()
+    }
+}
+
+fn escape_passphrase(passphrase: &str) -> String {
+    passphrase.replace('\'', "''")
+}
+
+/// Value [`m0023_db_integrity_check`](crate::migrations) seeds into `_db_integrity_check`, the
+/// same constant [`verify_sentinel`] reads back to confirm a passphrase unlocked the real
+/// database rather than failing open on garbage.
+const SENTINEL_MARKER: &str = "walnutbook-ok";
+
+/// Reads the sentinel row [`m0023_db_integrity_check`](crate::migrations) seeds, turning
+/// SQLCipher's opaque "file is encrypted or is not a database" error into a clear "Incorrect
+/// passphrase" instead.
+fn verify_sentinel(conn: &Connection) -> Result<(), String> {
+    conn.query_row("SELECT marker FROM _db_integrity_check WHERE id = 1", [], |row| row.get::<_, String>(0))
+        .map_err(|_| "Incorrect passphrase".to_string())
+        .and_then(|marker| {
+            if marker == SENTINEL_MARKER {
+                Ok(())
+            } else {
+                Err("Incorrect passphrase".to_string())
+            }
+        })
+}
+
+/// Applies `PRAGMA key` (and `cipher_migrate`, for a connection that was opened against a
+/// previously-plaintext file) to `conn`, then confirms it with [`verify_sentinel`].
+fn apply_key(conn: &Connection, passphrase: &str) -> Result<(), String> {
+    conn.execute_batch(&format!(
+        "PRAGMA key = '{}'; PRAGMA cipher_migrate;",
+        escape_passphrase(passphrase)
+    ))
+    .map_err(|e| e.to_string())?;
+    verify_sentinel(conn)
+}
+
+/// Opens a fresh connection to the app database and applies whatever passphrase is held in
+/// `key_state` for the session, so commands touching sensitive tables (reminders, payment
+/// history) never fall back to a plain, unkeyed `Connection::open` that would silently read an
+/// encrypted file as garbage. Returns a plain, unkeyed connection when no passphrase has been
+/// unlocked (the database was never encrypted).
+pub fn open_encrypted(app: &tauri::AppHandle, key_state: &DbKeyState) -> Result<Connection, String> {
+    let conn = Connection::open(get_db_path(app)).map_err(|e| e.to_string())?;
+    let passphrase = key_state.0.lock().map_err(|e| e.to_string())?.clone();
+    if let Some(passphrase) = passphrase {
+        apply_key(&conn, &passphrase)?;
+    }
+    Ok(conn)
+}
+
+/// First-time encryption of a plaintext `walnutbook.db`: re-keys it in place via
+/// `ATTACH DATABASE ... KEY` + `sqlcipher_export`, then swaps the exported (encrypted) copy
+/// in as the real database file.
+#[tauri::command]
+pub fn set_db_passphrase(
+    app: tauri::AppHandle,
+    pool: State<'_, DbPoolHandle>,
+    key_state: State<'_, DbKeyState>,
+    passphrase: String,
+) -> Result<(), String> {
+    let path = get_db_path(&app);
+    let encrypted_path = path.with_extension("db.encrypted");
+
+    {
+        let conn = Connection::open(&path).map_err(|e| e.to_string())?;
+        conn.execute_batch(&format!(
+            "ATTACH DATABASE '{}' AS encrypted KEY '{}';",
+            encrypted_path.display(),
+            escape_passphrase(&passphrase)
+        ))
+        .map_err(|e| e.to_string())?;
+        conn.query_row("SELECT sqlcipher_export('encrypted')", [], |_| Ok(()))
+            .map_err(|e| e.to_string())?;
+        conn.execute_batch("DETACH DATABASE encrypted;").map_err(|e| e.to_string())?;
+    }
+
+    std::fs::rename(&encrypted_path, &path).map_err(|e| e.to_string())?;
+
+    *key_state.0.lock().map_err(|e| e.to_string())? = Some(passphrase);
+    // Existing pooled connections (including any idle ones) were opened against the plaintext
+    // file, which the rename above just unlinked - `pool.get()` alone only checks out one
+    // connection and leaves the rest of the pool holding handles to that now-deleted inode.
+    // Build a fresh pool bound to the renamed (encrypted) path and swap it into the handle so
+    // every command's next `DbPoolHandle::current()` call picks it up instead.
+    pool.replace(crate::utils::init_db_pool(&app, key_state.handle()));
+    Ok(())
+}
+
+/// Unlocks an already-encrypted database for the session by storing the passphrase where the
+/// pool's connection manager will find it for every newly-opened physical connection, then
+/// rebuilding the pool so connections that were already idle (opened before the key was known,
+/// e.g. at app startup) are replaced rather than kept around unkeyed.
+#[tauri::command]
+pub fn unlock_db(app: tauri::AppHandle, pool: State<'_, DbPoolHandle>, key_state: State<'_, DbKeyState>, passphrase: String) -> Result<(), String> {
+    let conn = Connection::open(get_db_path(&app)).map_err(|e| e.to_string())?;
+    apply_key(&conn, &passphrase)?;
+    *key_state.0.lock().map_err(|e| e.to_string())? = Some(passphrase);
+    pool.replace(crate::utils::init_db_pool(&app, key_state.handle()));
+    Ok(())
+}
+
+/// Re-keys the already-unlocked database with a new passphrase via `PRAGMA rekey`, then rebuilds
+/// the pool so idle connections still carrying the old key (and so unable to decrypt pages
+/// written after the rekey) are replaced instead of being handed back out.
+#[tauri::command]
+pub fn change_db_passphrase(
+    app: tauri::AppHandle,
+    pool: State<'_, DbPoolHandle>,
+    key_state: State<'_, DbKeyState>,
+    old_passphrase: String,
+    new_passphrase: String,
+) -> Result<(), String> {
+    let conn = Connection::open(get_db_path(&app)).map_err(|e| e.to_string())?;
+    apply_key(&conn, &old_passphrase)?;
+    conn.execute_batch(&format!("PRAGMA rekey = '{}';", escape_passphrase(&new_passphrase)))
+        .map_err(|e| e.to_string())?;
+    drop(conn);
+    *key_state.0.lock().map_err(|e| e.to_string())? = Some(new_passphrase);
+    pool.replace(crate::utils::init_db_pool(&app, key_state.handle()));
+    Ok(())
+}
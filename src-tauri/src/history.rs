@@ -0,0 +1,33 @@
+use serde_json::Value;
+use tauri::{AppHandle, State};
+
+use crate::backup::dump_table_rows_where;
+use crate::encryption::{open_encrypted, DbKeyState};
+
+/// Maps the `entity` a caller asks [`get_entity_history`] about to the `{entity}_history` table
+/// and `{entity_singular}_id` foreign key column [`crate::migrations::m0029_entity_history`]
+/// creates for it - the same three tables that migration adds triggers for.
+fn history_table_for(entity: &str) -> Result<(&'static str, &'static str), String> {
+    match entity {
+        "transactions" => Ok(("transactions_history", "transaction_id")),
+        "budgets" => Ok(("budgets_history", "budget_id")),
+        "accounts" => Ok(("accounts_history", "account_id")),
+        other => Err(format!("No change history is tracked for entity '{}'", other)),
+    }
+}
+
+/// Returns every prior version of `entity` row `id`, oldest first, as recorded by
+/// [`crate::migrations::m0029_entity_history`]'s triggers - an UPDATE's row is the state just
+/// before that edit, a DELETE's row is the state just before it was removed, so the last entry
+/// for a deleted row is everything needed to recreate it.
+#[tauri::command]
+pub fn get_entity_history(app: AppHandle, key_state: State<'_, DbKeyState>, entity: String, id: i64) -> Result<Vec<Value>, String> {
+    let (history_table, fk_column) = history_table_for(&entity)?;
+    let conn = open_encrypted(&app, &key_state)?;
+
+    dump_table_rows_where(
+        &conn,
+        history_table,
+        &format!(" WHERE {} = {} ORDER BY history_id", fk_column, id),
+    )
+}
@@ -5,56 +5,121 @@ mod transactions;
 mod categories;
 mod budgets;
 mod recurring;
+mod recurring_rules;
+mod recurring_exceptions;
 mod backup;
 mod reminders;
+mod reconciliation;
+mod sync;
+mod encryption;
+mod migrations;
+mod recurrence;
+mod attachments;
+mod cloud;
+mod storage;
+mod notifications;
+mod merge;
+mod ynab_sync;
+mod history;
+mod csv_import;
+mod payee_aliases;
+
+pub use sync::{trigger_data_change_sync, confirm_risky_sync, start_remote_watch, stop_remote_watch};
+pub use encryption::{set_db_passphrase, unlock_db, change_db_passphrase, DbKeyState};
+pub use migrations::{get_schema_version, current_schema_version};
 
 
-use std::sync::Mutex;
-use rusqlite::Connection;
 use tauri::Manager;
 
 // Re-export specific types and functions from models
-pub use models::{Account, Transaction, Category, Budget, AccountImportSettings, RecurringItem};
+pub use models::{
+    Account, Transaction, Category, Budget, AccountImportSettings, RecurringItem,
+    RecurringItemInput, RecurringFrequency, IntervalUnit
+};
 
 // Re-export utility functions
 pub use utils::{init_db, home_dir, get_onedrive_path, reset_database};
 
 // Re-export account functions
-pub use accounts::{get_accounts, create_account, update_account, delete_account};
+pub use accounts::{get_accounts, create_account, update_account, delete_account, set_exchange_rate, get_exchange_rates, refresh_fx_rates, get_net_worth, recompute_account_balances};
+
+// Re-export reconciliation functions
+pub use reconciliation::{create_balance_assertion, get_balance_assertions, delete_balance_assertion, verify_balance_assertions, reconcile_account};
 
 // Re-export transaction functions
 pub use transactions::{
     get_transactions, create_transaction, update_transaction, delete_transaction,
-    bulk_update_transactions, import_transactions, save_transaction_attachment, delete_transaction_attachment, open_transaction_attachment
+    bulk_update_transactions, import_transactions, save_transaction_attachment, delete_transaction_attachment, open_transaction_attachment,
+    get_transactions_net_value, get_transactions_net, validate_transfers, reconcile_transactions, set_flag,
+    list_import_sessions, undo_import, search_transactions
 };
 
 // Re-export category functions
 pub use categories::{
     get_categories, get_categories_full, add_category, update_category, delete_category,
-    get_spending_by_category, get_income_vs_expenses, get_net_worth_history
+    get_spending_by_category, get_income_vs_expenses, get_net_worth_history,
+    get_monthly_summary, get_budget_vs_actual, get_balance_history
 };
 
 // Re-export budget functions
-pub use budgets::{get_budgets, add_budget, update_budget, delete_budget};
+pub use budgets::{get_budgets, add_budget, update_budget, delete_budget, rollover_recurring_budgets, get_budget_status};
 
 // Re-export recurring functions
 pub use recurring::{
     get_recurring_items, add_recurring_item, update_recurring_item, delete_recurring_item,
-    update_recurring_check, get_recurring_checks
+    update_recurring_check, get_recurring_checks, get_upcoming_recurring, run_due_recurring,
+    run_due_recurring_with_pool, get_recurring_item_occurrences, get_recurring_occurrences,
+    post_due_recurring_items, unpost_recurring_occurrence, get_upcoming_recurring_digest
+};
+
+// Re-export per-occurrence recurring-item exception functions (skip/reschedule/override)
+pub use recurring_exceptions::{set_recurring_exception, clear_recurring_exception};
+
+// Re-export scheduled transaction functions (scheduler-driven templates, distinct from recurring_items)
+pub use recurring_rules::{
+    list_scheduled_transactions, create_scheduled_transaction, delete_scheduled_transaction,
+    materialize_due_transactions, create_template, list_templates, apply_due_templates
 };
 
 // Re-export backup functions
 pub use backup::{
     backup_database, restore_database, export_database, import_database, create_backup_folder,
-    manual_backup_to_onedrive, get_backup_history, delete_backup_from_history, restore_backup_from_history, BackupInfo
+    manual_backup_to_onedrive, get_backup_history, delete_backup_from_history, restore_backup_from_history, BackupInfo,
+    export_backup, import_backup, write_backup_file, save_backup, BackupRotationMode,
+    prune_backups, BackupRetentionPolicy, RetentionResult,
+    list_pre_migration_backups, restore_backup
 };
 
+// Re-export per-transaction attachment functions (distinct from the single attachment_path column)
+pub use attachments::{list_attachments, add_attachment, open_attachment, delete_attachment};
+
+// Re-export direct-to-cloud Microsoft Graph functions (distinct from the locally-mounted OneDrive sync in `sync`)
+pub use cloud::{cloud_start_device_code, cloud_poll_device_code, cloud_refresh_token, cloud_create_directory, cloud_upload_backup};
+
 // Re-export settings functions
 pub use accounts::{
     get_account_import_settings, update_account_import_settings,
     get_csv_sign_logic_for_account
 };
 
+// Re-export background due-date notification functions
+pub use notifications::{
+    get_notification_settings, set_notification_settings, snooze_reminder,
+    get_budget_report_settings, set_budget_report_settings
+};
+
+// Re-export YNAB delta-sync functions
+pub use ynab_sync::{ynab_connect, ynab_pull, ynab_push, import_from_ynab};
+
+// Re-export change-history functions
+pub use history::get_entity_history;
+
+// Re-export backend CSV importer functions (bank profiles + column-mapped parsing)
+pub use csv_import::{list_bank_profiles, upsert_bank_profile, import_csv, import_transactions_csv};
+
+// Re-export payee-normalization functions (applied automatically inside import_transactions)
+pub use payee_aliases::{list_payee_aliases, upsert_payee_alias, reapply_payee_aliases};
+
 #[cfg_attr(mobile, tauri::mobile_entry_point)]
 pub fn run() -> Result<(), Box<dyn std::error::Error>> {
     let context = tauri::generate_context!();
@@ -64,10 +129,63 @@ pub fn run() -> Result<(), Box<dyn std::error::Error>> {
             let _ = crate::transactions::migrate_attachment_paths_to_relative(app.handle().clone());
             // Initialize SQLite database schema
             utils::init_db(&app.handle()).map_err(|e| e.to_string())?;
-            // Create and manage database connection
-            let db_path = utils::get_db_path(&app.handle());
-            let conn = Connection::open(&db_path).expect("Failed to open DB");
-            app.manage(Mutex::new(conn));
+            // Create and manage a pooled connection (WAL + shared busy-timeout) so
+            // commands no longer each open their own `Connection::open(..)`. The pool itself
+            // lives behind a `DbPoolHandle` rather than being `manage()`d directly, since
+            // Tauri's `manage()` refuses to overwrite a value already managed for a type -
+            // `set_db_passphrase` needs to swap in a freshly-built pool after rekeying the
+            // database file, and every long-lived holder below re-fetches the current pool
+            // via the handle instead of capturing one snapshot at startup.
+            let key_state = DbKeyState::new();
+            let db_pool = utils::init_db_pool(&app.handle(), key_state.handle());
+            let pool_handle = utils::DbPoolHandle::new(db_pool);
+            app.manage(pool_handle.clone());
+            app.manage(key_state);
+            // Post any scheduled transactions that came due while the app was closed.
+            let sync_handle = app.handle().clone();
+            let startup_pool = pool_handle.current();
+            tauri::async_runtime::spawn(async move {
+                let today = chrono::Local::now().format("%Y-%m-%d").to_string();
+                if let Err(e) = recurring_rules::materialize_due_transactions_with_pool(sync_handle, startup_pool, today).await {
+                    log::error!("failed to materialize due scheduled transactions: {}", e);
+                }
+            });
+            // Post any due recurring_items that came due while the app was closed, same as the
+            // scheduled-transactions hook above.
+            let recurring_items_handle = app.handle().clone();
+            let recurring_items_pool = pool_handle.current();
+            tauri::async_runtime::spawn(async move {
+                if let Err(e) = run_due_recurring_with_pool(recurring_items_handle, recurring_items_pool).await {
+                    log::error!("failed to post due recurring items: {}", e);
+                }
+            });
+            // Periodically scan reminders due soon and fire OS notifications for them, so
+            // users don't miss a payment just because the app window is closed.
+            let notification_handle = app.handle().clone();
+            let notification_pool_handle = pool_handle.clone();
+            tauri::async_runtime::spawn(async move {
+                let mut interval = tokio::time::interval(std::time::Duration::from_secs(30 * 60));
+                loop {
+                    interval.tick().await;
+                    if let Err(e) = notifications::scan_due_reminders(notification_handle.clone(), notification_pool_handle.current()).await {
+                        log::error!("reminder due-date scan failed: {}", e);
+                    }
+                }
+            });
+            // Periodically check whether a budget-vs-actual/net-worth report is due for the
+            // configured cadence (weekly/monthly) and fire one desktop notification for it.
+            let report_handle = app.handle().clone();
+            let report_pool_handle = pool_handle.clone();
+            tauri::async_runtime::spawn(async move {
+                let mut interval = tokio::time::interval(std::time::Duration::from_secs(30 * 60));
+                loop {
+                    interval.tick().await;
+                    if let Err(e) = notifications::scan_budget_report(report_handle.clone(), report_pool_handle.current()).await {
+                        log::error!("budget report scan failed: {}", e);
+                    }
+                }
+            });
+            app.handle().plugin(tauri_plugin_notification::init())?;
             // Enable logging plugin in development
             if cfg!(debug_assertions) {
                 app.handle().plugin(
@@ -87,6 +205,16 @@ pub fn run() -> Result<(), Box<dyn std::error::Error>> {
             create_account,
             update_account,
             delete_account,
+            set_exchange_rate,
+            get_exchange_rates,
+            refresh_fx_rates,
+            get_net_worth,
+            recompute_account_balances,
+            create_balance_assertion,
+            get_balance_assertions,
+            delete_balance_assertion,
+            verify_balance_assertions,
+            reconcile_account,
             get_transactions,
             create_transaction,
             update_transaction,
@@ -96,33 +224,83 @@ pub fn run() -> Result<(), Box<dyn std::error::Error>> {
             save_transaction_attachment,
             delete_transaction_attachment,
             open_transaction_attachment,
+            get_transactions_net_value,
+            get_transactions_net,
+            list_import_sessions,
+            undo_import,
+            search_transactions,
+            validate_transfers,
+            reconcile_transactions,
+            set_flag,
+            set_db_passphrase,
+            unlock_db,
+            change_db_passphrase,
             get_budgets,
             add_budget,
             update_budget,
             delete_budget,
+            rollover_recurring_budgets,
+            get_budget_status,
             get_recurring_items,
             add_recurring_item,
             update_recurring_item,
             delete_recurring_item,
             update_recurring_check,
             get_recurring_checks,
+            get_upcoming_recurring,
+            run_due_recurring,
+            get_recurring_item_occurrences,
+            get_recurring_occurrences,
+            post_due_recurring_items,
+            unpost_recurring_occurrence,
+            set_recurring_exception,
+            clear_recurring_exception,
+            get_upcoming_recurring_digest,
+            list_scheduled_transactions,
+            create_scheduled_transaction,
+            delete_scheduled_transaction,
+            materialize_due_transactions,
+            create_template,
+            list_templates,
+            apply_due_templates,
             get_categories,
             get_categories_full,
             add_category,
             update_category,
             delete_category,
             backup_database,
+            get_schema_version,
+            current_schema_version,
             restore_database,
             export_database,
             import_database,
             create_backup_folder,
+            write_backup_file,
+            save_backup,
+            prune_backups,
             manual_backup_to_onedrive,
             get_backup_history,
             delete_backup_from_history,
             restore_backup_from_history,
+            export_backup,
+            import_backup,
+            list_pre_migration_backups,
+            restore_backup,
+            list_attachments,
+            add_attachment,
+            open_attachment,
+            delete_attachment,
+            cloud_start_device_code,
+            cloud_poll_device_code,
+            cloud_refresh_token,
+            cloud_create_directory,
+            cloud_upload_backup,
             get_spending_by_category,
             get_income_vs_expenses,
             get_net_worth_history,
+            get_monthly_summary,
+            get_budget_vs_actual,
+            get_balance_history,
             get_account_import_settings,
             update_account_import_settings,
             get_csv_sign_logic_for_account,
@@ -140,8 +318,27 @@ pub fn run() -> Result<(), Box<dyn std::error::Error>> {
             reminders::delete_reminder_payment_history,
             reminders::update_reminder_payment_history_note,
             reminders::get_statement_balance,
+            reminders::compute_statement,
+            reminders::reconcile_reimbursements,
             reminders::add_note_to_reminder,
             reminders::delete_note_from_reminder,
+            get_notification_settings,
+            set_notification_settings,
+            snooze_reminder,
+            get_budget_report_settings,
+            set_budget_report_settings,
+            ynab_connect,
+            ynab_pull,
+            ynab_push,
+            import_from_ynab,
+            get_entity_history,
+            list_bank_profiles,
+            upsert_bank_profile,
+            import_csv,
+            import_transactions_csv,
+            list_payee_aliases,
+            upsert_payee_alias,
+            reapply_payee_aliases,
 
         ])
         .run(context)
@@ -0,0 +1,145 @@
+use std::collections::HashMap;
+use std::path::Path;
+use rusqlite::{params, Connection};
+use serde_json::Value;
+
+use crate::backup::{dump_table_rows, json_value_to_sql};
+
+/// Tables the OneDrive sync conflict path merges at row granularity instead of overwriting
+/// one whole file with the other, in dependency order so inserting a child row (`transactions`)
+/// after its parent (`accounts`/`categories`) never trips a foreign key check.
+const MERGE_TABLES: &[&str] = &["accounts", "categories", "budgets", "transactions"];
+
+fn rows_by_id(rows: Vec<Value>) -> HashMap<i64, Value> {
+    rows.into_iter()
+        .filter_map(|row| row.get("id").and_then(Value::as_i64).map(|id| (id, row)))
+        .collect()
+}
+
+fn updated_at(row: Option<&Value>) -> &str {
+    row.and_then(|r| r.get("updated_at")).and_then(Value::as_str).unwrap_or("")
+}
+
+/// Inserts, updates, or (if `remote_row` is `None`) deletes `table`'s row `id` in `conn` so it
+/// matches `remote_row` - the same "does this row already exist, `UPDATE` or `INSERT`" choice
+/// `backup::merge_reminders_rows` makes for natural-key upserts, here keyed by the real primary
+/// key instead.
+fn apply_row(conn: &Connection, table: &str, id: i64, local_exists: bool, remote_row: Option<&Value>) -> Result<(), String> {
+    let Some(row) = remote_row else {
+        if local_exists {
+            conn.execute(&format!("DELETE FROM {} WHERE id = ?1", table), params![id]).map_err(|e| e.to_string())?;
+        }
+        return Ok(());
+    };
+
+    let obj = row.as_object().ok_or_else(|| format!("Malformed merged row in table {}", table))?;
+    let columns: Vec<&String> = obj.keys().collect();
+
+    if local_exists {
+        let assignments: Vec<String> = columns.iter().enumerate().map(|(i, c)| format!("{} = ?{}", c, i + 1)).collect();
+        let sql = format!("UPDATE {} SET {} WHERE id = ?{}", table, assignments.join(", "), columns.len() + 1);
+        let mut boxed_values: Vec<Box<dyn rusqlite::ToSql>> = columns.iter().map(|c| json_value_to_sql(&obj[*c])).collect();
+        boxed_values.push(Box::new(id));
+        let values: Vec<&dyn rusqlite::ToSql> = boxed_values.iter().map(|b| b.as_ref()).collect();
+        conn.execute(&sql, values.as_slice()).map_err(|e| e.to_string())?;
+    } else {
+        let placeholders: Vec<String> = (1..=columns.len()).map(|i| format!("?{}", i)).collect();
+        let sql = format!(
+            "INSERT INTO {} ({}) VALUES ({})",
+            table,
+            columns.iter().map(|c| c.as_str()).collect::<Vec<_>>().join(", "),
+            placeholders.join(", ")
+        );
+        let boxed_values: Vec<Box<dyn rusqlite::ToSql>> = columns.iter().map(|c| json_value_to_sql(&obj[*c])).collect();
+        let values: Vec<&dyn rusqlite::ToSql> = boxed_values.iter().map(|b| b.as_ref()).collect();
+        conn.execute(&sql, values.as_slice()).map_err(|e| e.to_string())?;
+    }
+    Ok(())
+}
+
+/// Records the side that lost a same-row conflict into `sync_conflicts` so the user can still
+/// recover it after [`merge_table`] decided the other side's edit wins.
+fn record_conflict(conn: &Connection, table: &str, id: i64, local_row: Option<&Value>, remote_row: Option<&Value>, kept_remote: bool) -> Result<(), String> {
+    let resolution = if kept_remote { "kept_remote" } else { "kept_local" };
+    conn.execute(
+        "INSERT INTO sync_conflicts (table_name, row_id, local_value, remote_value, resolution) VALUES (?1, ?2, ?3, ?4, ?5)",
+        params![
+            table,
+            id,
+            local_row.map(|v| v.to_string()),
+            remote_row.map(|v| v.to_string()),
+            resolution,
+        ],
+    ).map_err(|e| e.to_string())?;
+    Ok(())
+}
+
+/// Three-way-compares `table` across the last-synced `base` snapshot, the live `local`
+/// database, and the `remote` copy just pulled (or about to be pushed to), and brings `local`
+/// (via `conn`, a handle already open on it) up to date: a row only `remote` touched is
+/// applied, a row only `local` touched is left alone, and a row both sides touched since the
+/// base snapshot is a genuine conflict - resolved in favor of whichever has the newer
+/// `updated_at`, with the losing version written to `sync_conflicts`. Returns how many rows
+/// needed that arbitration.
+fn merge_table(conn: &Connection, table: &str, base_rows: &HashMap<i64, Value>, local_rows: &HashMap<i64, Value>, remote_rows: &HashMap<i64, Value>) -> Result<usize, String> {
+    let mut ids: Vec<i64> = base_rows.keys().chain(local_rows.keys()).chain(remote_rows.keys()).copied().collect();
+    ids.sort_unstable();
+    ids.dedup();
+
+    let mut conflicts = 0;
+    for id in ids {
+        let base = base_rows.get(&id);
+        let local_row = local_rows.get(&id);
+        let remote_row = remote_rows.get(&id);
+
+        let remote_changed = remote_row != base;
+        if !remote_changed {
+            continue; // remote agrees with the base snapshot - local's copy stands unchanged
+        }
+
+        let local_changed = local_row != base;
+        if !local_changed {
+            // Only remote moved on from the base snapshot: adopt its value (insert/update/delete).
+            apply_row(conn, table, id, local_row.is_some(), remote_row)?;
+            continue;
+        }
+
+        if local_row == remote_row {
+            continue; // both sides independently made the identical change
+        }
+
+        // Both sides changed this row since the last sync this machine completed: keep
+        // whichever edit is newer and preserve the other for the user to recover.
+        let keep_remote = updated_at(remote_row) > updated_at(local_row);
+        record_conflict(conn, table, id, local_row, remote_row, keep_remote)?;
+        conflicts += 1;
+        if keep_remote {
+            apply_row(conn, table, id, local_row.is_some(), remote_row)?;
+        }
+    }
+    Ok(conflicts)
+}
+
+/// Merges `remote` onto `local` at SQLite-row granularity across [`MERGE_TABLES`], comparing
+/// both against the `base` snapshot this machine last completed a sync against, instead of the
+/// wholesale `fs::copy` overwrite sync used before a divergence could be detected per row. Runs
+/// inside a single transaction on `local` so a failure partway through never leaves it with a
+/// mix of merged and stale tables. Returns the total number of rows that had to be arbitrated
+/// by newest `updated_at` across all tables.
+pub fn merge_databases(base: &Path, local: &Path, remote: &Path) -> Result<usize, String> {
+    let base_conn = Connection::open(base).map_err(|e| format!("Failed to open sync merge base snapshot: {}", e))?;
+    let remote_conn = Connection::open(remote).map_err(|e| format!("Failed to open remote database for merge: {}", e))?;
+    let mut local_conn = Connection::open(local).map_err(|e| format!("Failed to open local database for merge: {}", e))?;
+
+    let tx = local_conn.transaction().map_err(|e| e.to_string())?;
+    let mut total_conflicts = 0;
+    for table in MERGE_TABLES {
+        let base_rows = rows_by_id(dump_table_rows(&base_conn, table)?);
+        let local_rows = rows_by_id(dump_table_rows(&tx, table)?);
+        let remote_rows = rows_by_id(dump_table_rows(&remote_conn, table)?);
+        total_conflicts += merge_table(&tx, table, &base_rows, &local_rows, &remote_rows)?;
+    }
+    tx.commit().map_err(|e| e.to_string())?;
+
+    Ok(total_conflicts)
+}
@@ -9,6 +9,29 @@ pub struct Account {
     pub balance: f64,
     pub description: Option<String>,
     pub created_at: String,
+    /// ISO-4217 code the account's own transactions are recorded in.
+    #[serde(default = "default_currency")]
+    pub currency: String,
+    /// `balance` converted to the app's base currency using the exchange rate
+    /// effective on each transaction's date. Equal to `balance` for accounts
+    /// already in the base currency.
+    #[serde(default)]
+    pub base_balance: f64,
+    /// Annual percentage rate, for [`reminders::compute_statement`](crate::reminders::compute_statement)'s
+    /// interest projection. `None` on non-credit accounts, or a credit account that hasn't had a
+    /// rate entered yet.
+    #[serde(default)]
+    pub apr: Option<f64>,
+    /// Fixed floor a minimum payment never falls below, regardless of `min_payment_pct`.
+    #[serde(default)]
+    pub min_payment_floor: Option<f64>,
+    /// Minimum payment as a fraction of the statement balance, e.g. `0.02` for 2%.
+    #[serde(default)]
+    pub min_payment_pct: Option<f64>,
+}
+
+fn default_currency() -> String {
+    "USD".to_string()
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
@@ -25,8 +48,27 @@ pub struct Transaction {
     pub notes: Option<String>,
     pub transfer_id: Option<i64>,
     pub to_account_id: Option<i64>,
+    /// Separate fee charged alongside the transaction (e.g. a wire fee or ATM surcharge).
+    /// Always reduces the account balance regardless of the transaction's own sign.
+    #[serde(default)]
+    pub fee: Option<f64>,
     #[serde(default)]
     pub created_at: String,
+    /// Stable dedup key for CSV re-imports, e.g. `"-2599:2024-03-04:1"`. `None` for rows
+    /// created before this column existed or through the regular transaction form.
+    #[serde(default)]
+    pub import_id: Option<String>,
+    /// YNAB-style clearing state: `"uncleared"` (default) -> `"cleared"` -> `"reconciled"`.
+    /// Reconciled rows are protected from edits in `update_transaction`/`delete_transaction`.
+    #[serde(default = "default_cleared_status")]
+    pub cleared_status: String,
+    /// Free-form highlight label (e.g. `"red"`), independent of `cleared_status`.
+    #[serde(default)]
+    pub flag_color: Option<String>,
+}
+
+fn default_cleared_status() -> String {
+    "uncleared".to_string()
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
@@ -37,6 +79,10 @@ pub struct Budget {
     pub month: String,
     pub notes: Option<String>,
     pub created_at: String,
+    /// `recurring_rules.id` this budget auto-rolls forward from, via `rollover_recurring_budgets`.
+    /// `None` for a one-off budget entered by hand.
+    #[serde(default)]
+    pub recurring_rule_id: Option<i64>,
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
@@ -49,6 +95,10 @@ pub struct Category {
     pub is_reimbursement: bool,
     #[serde(default)]
     pub reimbursement_target_category_id: Option<i64>,
+    /// Lets `budgets::get_budget_status`'s month-to-month carryover go negative for this
+    /// category instead of clamping an overspend to 0 before it rolls into next month.
+    #[serde(default)]
+    pub carry_overspending: bool,
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
@@ -79,6 +129,162 @@ pub struct RecurringItem {
     pub interval_value: i32,
     #[serde(default = "default_interval_unit")]
     pub interval_unit: String,
+    /// Most recent occurrence [`crate::recurring::run_due_recurring`] has already posted into
+    /// `transactions`. `None` means the item has never been auto-posted yet.
+    #[serde(default)]
+    pub last_posted_date: Option<String>,
+    /// Optional RFC 5545 `RRULE` value (`crate::recurrence::RRule`) giving this item the richer
+    /// recurrence vocabulary `BYDAY`/negative `BYMONTHDAY` support - `None` means it uses
+    /// `frequency`, or `repeat_type`/`interval_value`/`interval_unit`/`day_of_month` without one.
+    #[serde(default)]
+    pub rrule: Option<String>,
+    /// [`RecurringFrequency`], serialized to JSON. Set by [`crate::recurring::add_recurring_item`]/
+    /// [`crate::recurring::update_recurring_item`] alongside `repeat_type`/`interval_value`/
+    /// `interval_unit`/`day_of_month`, which are kept in sync from it
+    /// (`RecurringFrequency::to_legacy_columns`) so every reader of those columns needs no
+    /// changes. `recurrence::rrule_for_item` reads this field before the legacy columns. `None` for rows created before this column
+    /// existed; `m0041_recurring_items_frequency` backfills it for them.
+    #[serde(default)]
+    pub frequency: Option<String>,
+}
+
+/// Single typed payload for [`crate::recurring::add_recurring_item`]/
+/// [`crate::recurring::update_recurring_item`], replacing the old pattern of one parameter plus an
+/// `_alt` twin per field (`category_id`/`category_id_alt`, etc.) that existed to tolerate both
+/// snake_case and camelCase argument names from the frontend. `#[serde(rename_all = "camelCase")]`
+/// makes that tolerance unnecessary: Tauri deserializes the single JS object directly.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct RecurringItemInput {
+    pub name: String,
+    pub amount: f64,
+    #[serde(rename = "type")]
+    pub item_type: String,
+    pub category_id: i64,
+    pub account_id: i64,
+    #[serde(default = "default_true")]
+    pub is_active: bool,
+    pub notes: Option<String>,
+    pub start_date: Option<String>,
+    pub frequency: RecurringFrequency,
+}
+
+fn default_true() -> bool {
+    true
+}
+
+/// The step unit (day, week or month) of [`RecurringFrequency::EveryN`]'s interval.
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub enum IntervalUnit {
+    Day,
+    Week,
+    Month,
+}
+
+impl IntervalUnit {
+    fn as_column_str(&self) -> &'static str {
+        match self {
+            IntervalUnit::Day => "day",
+            IntervalUnit::Week => "week",
+            IntervalUnit::Month => "month",
+        }
+    }
+
+    fn from_column_str(s: &str) -> IntervalUnit {
+        match s {
+            "day" => IntervalUnit::Day,
+            "week" => IntervalUnit::Week,
+            _ => IntervalUnit::Month,
+        }
+    }
+}
+
+/// Typed recurrence cadence for a [`RecurringItem`], replacing the old combination of a free-form
+/// `repeat_type` string plus whichever of `interval_value`/`interval_unit`/`day_of_month` happened
+/// to apply to it. Distinct from the similarly-named [`Frequency`] (a [`Reminder`]'s cadence) -
+/// the two model unrelated entities and evolved independently; this one is serialized to
+/// `recurring_items.frequency` as JSON rather than matched against a fixed set of SQL columns.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(tag = "type", rename_all = "camelCase")]
+pub enum RecurringFrequency {
+    /// One or more day-of-month anchors, posted every month (`repeat_type = "monthly_date"`).
+    MonthlyByDate { days: Vec<i32> },
+    /// Specific weekdays (`Date.getDay()` numbering), every `interval` week(s). Has no direct
+    /// legacy-column equivalent - `to_legacy_columns` falls back to `repeat_type = "interval"`/
+    /// `interval_unit = "week"` for it, which loses which weekdays in particular but still fires
+    /// on the right cadence; the weekday list itself survives in the `frequency` JSON column.
+    Weekly { weekdays: Vec<i32>, interval: i32 },
+    /// Steps by `value` of `unit` from `start_date` (`repeat_type = "interval"`).
+    EveryN { value: i32, unit: IntervalUnit },
+}
+
+impl RecurringFrequency {
+    /// Derives the `repeat_type`/`day_of_month`/`interval_value`/`interval_unit` column values
+    /// this frequency corresponds to, so every existing reader of those columns (such as
+    /// `recurrence::rrule_for_item`'s fallback for rows without `frequency`) keeps working unchanged.
+    pub fn to_legacy_columns(&self) -> (String, String, i32, String) {
+        match self {
+            RecurringFrequency::MonthlyByDate { days } => (
+                "monthly_date".to_string(),
+                serde_json::to_string(days).unwrap_or_else(|_| "[1]".to_string()),
+                1,
+                "month".to_string(),
+            ),
+            RecurringFrequency::Weekly { interval, .. } => (
+                "interval".to_string(),
+                "[1]".to_string(),
+                (*interval).max(1),
+                "week".to_string(),
+            ),
+            RecurringFrequency::EveryN { value, unit } => (
+                "interval".to_string(),
+                "[1]".to_string(),
+                (*value).max(1),
+                unit.as_column_str().to_string(),
+            ),
+        }
+    }
+
+    /// Reconstructs a [`RecurringFrequency`] from a pre-existing row's legacy columns, for
+    /// `m0041_recurring_items_frequency`'s backfill. Loses no information `occurrences_between`
+    /// itself could see - a legacy row never had a `Weekly`-style weekday list to begin with.
+    pub fn from_legacy_columns(repeat_type: &str, day_of_month: &str, interval_value: i32, interval_unit: &str) -> RecurringFrequency {
+        if repeat_type == "interval" {
+            RecurringFrequency::EveryN { value: interval_value.max(1), unit: IntervalUnit::from_column_str(interval_unit) }
+        } else {
+            let days: Vec<i32> = serde_json::from_str(day_of_month).unwrap_or_else(|_| vec![1]);
+            RecurringFrequency::MonthlyByDate { days }
+        }
+    }
+}
+
+/// Recurrence cadence for a [`Reminder`], serialized to the `reminders.frequency` column as
+/// internally-tagged JSON (e.g. `{"type":"Monthly","day":15}`) so `check_reminder` can compute
+/// the next `next_payment_date`/`statement_date` itself instead of requiring the frontend to
+/// work out and pass in the rolled-forward dates.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(tag = "type")]
+pub enum Frequency {
+    Weekly,
+    /// `anchor_date` (yyyy-MM-dd) is the first occurrence; later ones fall exactly 14 days
+    /// apart from it so a missed check-in never shifts the cadence.
+    BiWeekly { anchor_date: String },
+    /// `day` is the day-of-month anchor (1-31); months shorter than that clamp to their own
+    /// last day (e.g. day 31 in February lands on the 28th/29th) instead of rolling over.
+    Monthly { day: u8 },
+    /// Every 3 months from the current date, re-anchored to `day`, so a Feb/May/Aug/Nov reminder
+    /// advances the same way a Jan/Apr/Jul/Oct one does. `month_offset` (0-2) is stored with the
+    /// cadence but not read by `reminders::next_occurrence`.
+    Quarterly { month_offset: u8, day: u8 },
+    /// Keeps the current day-of-month, 6 months out.
+    SemiAnnual,
+    /// `month` (1-12) and `day` anchor a once-a-year reminder, e.g. an annual subscription.
+    Annual { month: u8, day: u8 },
+}
+
+fn default_frequency() -> Frequency {
+    Frequency::Monthly { day: 1 }
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
@@ -92,6 +298,10 @@ pub struct Reminder {
     pub notes: Option<Vec<String>>, // 여러 노트 저장
     pub created_at: String,
     pub statement_date: String, // Statement date (yyyy-MM-dd)
+    /// Recurrence rule driving `check_reminder`'s roll-forward. Defaults to the reminder's
+    /// `payment_day` as a monthly cadence for callers that don't set it explicitly.
+    #[serde(default = "default_frequency")]
+    pub frequency: Frequency,
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
@@ -105,6 +315,27 @@ pub struct ReminderPaymentHistory {
     pub note: Option<String>,
 }
 
+/// Result of [`reminders::compute_statement`](crate::reminders::compute_statement): the raw
+/// statement sum plus the payment figures a credit-card reminder actually needs.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct StatementSummary {
+    /// Sum of the period's non-transfer transactions — what `get_statement_balance` returns.
+    pub statement_balance: f64,
+    /// `statement_balance` with every reimbursement-category expense that already has a
+    /// [`reminders::reconcile_reimbursements`](crate::reminders::reconcile_reimbursements) link
+    /// backed out, i.e. what's actually owed once pending reimbursements are paid back.
+    pub net_balance: f64,
+    /// Balance carried into the start of the period, i.e. the statement sum for everything
+    /// before `startDate`.
+    pub prior_balance: f64,
+    /// `max(min_payment_floor, min_payment_pct * statement_balance)`, using the account's
+    /// stored terms (`0.0` if neither is configured).
+    pub minimum_payment: f64,
+    /// Estimated interest for the next cycle if only `minimum_payment` is paid, computed from
+    /// the account's `apr` and the period's average daily balance.
+    pub projected_interest: f64,
+}
+
 fn default_repeat_type() -> String {
     "monthly_date".to_string()
 }
@@ -0,0 +1,296 @@
+use chrono::{Datelike, Local, NaiveDate, NaiveTime};
+use rusqlite::{params, OptionalExtension};
+use serde::{Deserialize, Serialize};
+use tauri::{AppHandle, Manager, State};
+use tauri_plugin_notification::NotificationExt;
+
+use crate::encryption::DbKeyState;
+use crate::models::Reminder;
+use crate::reminders;
+use crate::utils::{DbPool, DbPoolHandle};
+
+/// How far ahead (and whether at all) the background scanner in [`scan_due_reminders`] warns
+/// about an upcoming reminder, plus a quiet window it won't fire OS notifications in.
+/// Persisted as one JSON row in the generic `settings` table, keyed [`NOTIFICATION_SETTINGS_KEY`].
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct NotificationSettings {
+    pub enabled: bool,
+    /// Days-before-due to notify on, e.g. `[3, 0]` for "due in 3 days" and "due today". A due
+    /// date notifies only once (see `notified_dates`), on the first of these the scanner reaches.
+    pub lead_days: Vec<i64>,
+    /// Local "HH:MM" bounds of a window the scanner skips firing notifications in (e.g.
+    /// overnight); `None` means no quiet hours are configured.
+    pub quiet_hours_start: Option<String>,
+    pub quiet_hours_end: Option<String>,
+}
+
+impl Default for NotificationSettings {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            lead_days: vec![3, 0],
+            quiet_hours_start: None,
+            quiet_hours_end: None,
+        }
+    }
+}
+
+const NOTIFICATION_SETTINGS_KEY: &str = "notification_settings";
+
+#[tauri::command]
+pub fn get_notification_settings(pool: State<'_, DbPoolHandle>) -> Result<NotificationSettings, String> {
+    let conn = pool.current().get().map_err(|e| e.to_string())?;
+    let value: Option<String> = conn
+        .query_row("SELECT value FROM settings WHERE key = ?1", params![NOTIFICATION_SETTINGS_KEY], |row| row.get(0))
+        .optional()
+        .map_err(|e| e.to_string())?;
+    Ok(value
+        .and_then(|v| serde_json::from_str(&v).ok())
+        .unwrap_or_default())
+}
+
+#[tauri::command]
+pub fn set_notification_settings(pool: State<'_, DbPoolHandle>, settings: NotificationSettings) -> Result<NotificationSettings, String> {
+    let conn = pool.current().get().map_err(|e| e.to_string())?;
+    let json = serde_json::to_string(&settings).map_err(|e| e.to_string())?;
+    conn.execute(
+        "INSERT INTO settings (key, value) VALUES (?1, ?2) ON CONFLICT(key) DO UPDATE SET value = excluded.value",
+        params![NOTIFICATION_SETTINGS_KEY, json],
+    ).map_err(|e| e.to_string())?;
+    Ok(settings)
+}
+
+/// Pushes `id`'s `next_payment_date` out to `until_date` without marking it checked, so it
+/// drops off the due-soon window the scanner watches without recording a payment against it.
+#[tauri::command]
+pub fn snooze_reminder(app: AppHandle, key_state: State<'_, DbKeyState>, id: i64, until_date: String) -> Result<Vec<Reminder>, String> {
+    let conn = crate::encryption::open_encrypted(&app, &key_state)?;
+    conn.execute(
+        "UPDATE reminders SET next_payment_date = ?1 WHERE id = ?2",
+        params![until_date, id],
+    ).map_err(|e| e.to_string())?;
+    reminders::fetch_reminders(&conn)
+}
+
+fn parse_notified_dates(notified_dates_json: Option<&str>) -> Vec<String> {
+    notified_dates_json
+        .and_then(|s| serde_json::from_str(s).ok())
+        .unwrap_or_default()
+}
+
+/// `true` when `now` falls inside the `[start, end)` window, wrapping past midnight if `end`
+/// is earlier than `start` (e.g. a 22:00-07:00 overnight quiet window).
+fn in_quiet_hours(now: NaiveTime, start: &str, end: &str) -> bool {
+    let (Ok(start), Ok(end)) = (
+        NaiveTime::parse_from_str(start, "%H:%M"),
+        NaiveTime::parse_from_str(end, "%H:%M"),
+    ) else {
+        return false;
+    };
+    if start <= end {
+        now >= start && now < end
+    } else {
+        now >= start || now < end
+    }
+}
+
+/// Scans every unchecked reminder for one whose due date falls exactly `lead_days` away from
+/// today, firing a native OS notification for it through the Tauri notification plugin. Each
+/// due date only ever fires once per reminder — `notified_dates` records the ones already
+/// shown, since the scanner reruns on a fixed interval and would otherwise re-notify every tick
+/// until the reminder is checked off.
+pub async fn scan_due_reminders(app: AppHandle, pool: DbPool) -> Result<(), String> {
+    let settings = {
+        let conn = pool.get().map_err(|e| e.to_string())?;
+        let value: Option<String> = conn
+            .query_row("SELECT value FROM settings WHERE key = ?1", params![NOTIFICATION_SETTINGS_KEY], |row| row.get(0))
+            .optional()
+            .map_err(|e| e.to_string())?;
+        value.and_then(|v| serde_json::from_str(&v).ok()).unwrap_or_else(NotificationSettings::default)
+    };
+
+    if !settings.enabled {
+        return Ok(());
+    }
+    if let (Some(start), Some(end)) = (&settings.quiet_hours_start, &settings.quiet_hours_end) {
+        if in_quiet_hours(Local::now().time(), start, end) {
+            return Ok(());
+        }
+    }
+
+    let today = Local::now().date_naive();
+
+    let due: Vec<(i64, String, String, Option<String>)> = {
+        let conn = pool.get().map_err(|e| e.to_string())?;
+        let mut stmt = conn
+            .prepare("SELECT id, account_name, next_payment_date, notified_dates FROM reminders WHERE is_checked = 0")
+            .map_err(|e| e.to_string())?;
+        stmt.query_map([], |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?, row.get(3)?)))
+            .map_err(|e| e.to_string())?
+            .collect::<Result<_, _>>()
+            .map_err(|e| e.to_string())?
+    };
+
+    for (id, account_name, next_payment_date, notified_dates_json) in due {
+        let Ok(due_date) = NaiveDate::parse_from_str(&next_payment_date, "%Y-%m-%d") else { continue };
+        let days_until = (due_date - today).num_days();
+        if !settings.lead_days.contains(&days_until) {
+            continue;
+        }
+
+        let mut notified_dates = parse_notified_dates(notified_dates_json.as_deref());
+        if notified_dates.iter().any(|d| d == &next_payment_date) {
+            continue;
+        }
+
+        let body = if days_until == 0 {
+            format!("{} is due today", account_name)
+        } else {
+            format!("{} is due in {} day{}", account_name, days_until, if days_until == 1 { "" } else { "s" })
+        };
+        if let Err(e) = app.notification().builder().title("Payment due").body(&body).show() {
+            log::error!("failed to show reminder notification for reminder {}: {}", id, e);
+            continue;
+        }
+
+        notified_dates.push(next_payment_date);
+        let notified_dates_json = serde_json::to_string(&notified_dates).map_err(|e| e.to_string())?;
+        let conn = pool.get().map_err(|e| e.to_string())?;
+        conn.execute("UPDATE reminders SET notified_dates = ?1 WHERE id = ?2", params![notified_dates_json, id])
+            .map_err(|e| e.to_string())?;
+    }
+
+    Ok(())
+}
+
+/// How often [`scan_budget_report`] raises its budget-vs-actual/net-worth summary, and in what
+/// currency to roll net worth up into. Persisted the same way as [`NotificationSettings`], under
+/// its own `settings` key so the two background jobs can be toggled independently.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BudgetReportSettings {
+    pub enabled: bool,
+    /// `"weekly"` or `"monthly"`.
+    pub cadence: String,
+    pub base_currency: String,
+}
+
+impl Default for BudgetReportSettings {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            cadence: "monthly".to_string(),
+            base_currency: "USD".to_string(),
+        }
+    }
+}
+
+/// What [`scan_budget_report`] last reported, so it only fires once per cadence period instead
+/// of every time the scanner ticks, and so the next report can say how net worth moved since.
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+struct BudgetReportState {
+    last_period: String,
+    last_net_worth: f64,
+}
+
+const BUDGET_REPORT_SETTINGS_KEY: &str = "budget_report_settings";
+const BUDGET_REPORT_STATE_KEY: &str = "budget_report_state";
+
+#[tauri::command]
+pub fn get_budget_report_settings(pool: State<'_, DbPoolHandle>) -> Result<BudgetReportSettings, String> {
+    let conn = pool.current().get().map_err(|e| e.to_string())?;
+    let value: Option<String> = conn
+        .query_row("SELECT value FROM settings WHERE key = ?1", params![BUDGET_REPORT_SETTINGS_KEY], |row| row.get(0))
+        .optional()
+        .map_err(|e| e.to_string())?;
+    Ok(value.and_then(|v| serde_json::from_str(&v).ok()).unwrap_or_default())
+}
+
+#[tauri::command]
+pub fn set_budget_report_settings(pool: State<'_, DbPoolHandle>, settings: BudgetReportSettings) -> Result<BudgetReportSettings, String> {
+    let conn = pool.current().get().map_err(|e| e.to_string())?;
+    let json = serde_json::to_string(&settings).map_err(|e| e.to_string())?;
+    conn.execute(
+        "INSERT INTO settings (key, value) VALUES (?1, ?2) ON CONFLICT(key) DO UPDATE SET value = excluded.value",
+        params![BUDGET_REPORT_SETTINGS_KEY, json],
+    ).map_err(|e| e.to_string())?;
+    Ok(settings)
+}
+
+/// `"YYYY-MM"` for a monthly cadence, `"YYYY-Www"` (ISO week) for a weekly one — whichever
+/// period `today` falls in, and the key [`scan_budget_report`] dedupes against so it reports
+/// each period exactly once.
+fn report_period_key(today: NaiveDate, cadence: &str) -> String {
+    if cadence == "weekly" {
+        let week = today.iso_week();
+        format!("{}-W{:02}", week.year(), week.week())
+    } else {
+        today.format("%Y-%m").to_string()
+    }
+}
+
+/// Once per `settings.cadence` period, computes this month's budget-vs-actual totals (via
+/// [`crate::budgets::get_budget_status`]) and the net-worth delta since the last report (via
+/// [`crate::accounts::get_net_worth`]), and raises a single desktop notification summarizing
+/// both — the same "scan on a timer, skip unless something is actually due" shape as
+/// [`scan_due_reminders`], except what's "due" here is the reporting period rather than an
+/// individual reminder.
+pub async fn scan_budget_report(app: AppHandle, pool: DbPool) -> Result<(), String> {
+    let settings: BudgetReportSettings = {
+        let conn = pool.get().map_err(|e| e.to_string())?;
+        let value: Option<String> = conn
+            .query_row("SELECT value FROM settings WHERE key = ?1", params![BUDGET_REPORT_SETTINGS_KEY], |row| row.get(0))
+            .optional()
+            .map_err(|e| e.to_string())?;
+        value.and_then(|v| serde_json::from_str(&v).ok()).unwrap_or_default()
+    };
+
+    if !settings.enabled {
+        return Ok(());
+    }
+
+    let today = Local::now().date_naive();
+    let period_key = report_period_key(today, &settings.cadence);
+
+    let previous: Option<BudgetReportState> = {
+        let conn = pool.get().map_err(|e| e.to_string())?;
+        let value: Option<String> = conn
+            .query_row("SELECT value FROM settings WHERE key = ?1", params![BUDGET_REPORT_STATE_KEY], |row| row.get(0))
+            .optional()
+            .map_err(|e| e.to_string())?;
+        value.and_then(|v| serde_json::from_str(&v).ok())
+    };
+    if previous.as_ref().map(|s| s.last_period.as_str()) == Some(period_key.as_str()) {
+        return Ok(());
+    }
+
+    let current_month = today.format("%Y-%m").to_string();
+    let statuses = crate::budgets::get_budget_status(app.clone(), app.state::<DbKeyState>(), current_month)?;
+    let budgeted: f64 = statuses.iter().map(|s| s.budgeted).sum();
+    let activity: f64 = statuses.iter().map(|s| s.activity).sum();
+
+    let net_worth = crate::accounts::get_net_worth(app.state::<DbPoolHandle>(), settings.base_currency.clone())?;
+
+    let body = match &previous {
+        Some(prev) => format!(
+            "Budgeted {:.2}, spent {:.2}. Net worth {:.2} ({}{:.2} since last report)",
+            budgeted, activity, net_worth.total,
+            if net_worth.total >= prev.last_net_worth { "+" } else { "" },
+            net_worth.total - prev.last_net_worth,
+        ),
+        None => format!("Budgeted {:.2}, spent {:.2}. Net worth {:.2}", budgeted, activity, net_worth.total),
+    };
+
+    if let Err(e) = app.notification().builder().title("Budget report").body(&body).show() {
+        log::error!("failed to show budget report notification: {}", e);
+    }
+
+    let new_state = BudgetReportState { last_period: period_key, last_net_worth: net_worth.total };
+    let json = serde_json::to_string(&new_state).map_err(|e| e.to_string())?;
+    let conn = pool.get().map_err(|e| e.to_string())?;
+    conn.execute(
+        "INSERT INTO settings (key, value) VALUES (?1, ?2) ON CONFLICT(key) DO UPDATE SET value = excluded.value",
+        params![BUDGET_REPORT_STATE_KEY, json],
+    ).map_err(|e| e.to_string())?;
+
+    Ok(())
+}
@@ -0,0 +1,147 @@
+use regex::Regex;
+use rusqlite::{params, Connection};
+use serde::{Deserialize, Serialize};
+use tauri::{AppHandle, State};
+
+use crate::encryption::{open_encrypted, DbKeyState};
+use crate::models::Transaction;
+
+/// One payee-normalization rule, matched against a raw imported payee string (e.g. from a bank
+/// CSV) and rewritten to a canonical name, optionally filling in a default category the way the
+/// YNAB-import payee map this is modeled on does.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct PayeeAlias {
+    #[serde(default)]
+    pub id: i64,
+    pub pattern: String,
+    pub is_regex: bool,
+    pub canonical_payee: String,
+    pub default_category_id: Option<i64>,
+}
+
+fn fetch_payee_aliases(conn: &Connection) -> Result<Vec<PayeeAlias>, String> {
+    let mut stmt = conn.prepare(
+        "SELECT id, pattern, is_regex, canonical_payee, default_category_id FROM payee_aliases ORDER BY id"
+    ).map_err(|e| e.to_string())?;
+
+    let rows = stmt.query_map([], |row| {
+        Ok(PayeeAlias {
+            id: row.get(0)?,
+            pattern: row.get(1)?,
+            is_regex: row.get(2)?,
+            canonical_payee: row.get(3)?,
+            default_category_id: row.get(4)?,
+        })
+    }).map_err(|e| e.to_string())?;
+
+    let mut aliases = Vec::new();
+    for a in rows {
+        aliases.push(a.map_err(|e| e.to_string())?);
+    }
+    Ok(aliases)
+}
+
+#[tauri::command]
+pub fn list_payee_aliases(app: AppHandle, key_state: State<'_, DbKeyState>) -> Result<Vec<PayeeAlias>, String> {
+    let conn = open_encrypted(&app, &key_state)?;
+    fetch_payee_aliases(&conn)
+}
+
+#[tauri::command]
+pub fn upsert_payee_alias(app: AppHandle, key_state: State<'_, DbKeyState>, alias: PayeeAlias) -> Result<Vec<PayeeAlias>, String> {
+    let conn = open_encrypted(&app, &key_state)?;
+    if alias.id == 0 {
+        conn.execute(
+            "INSERT INTO payee_aliases (pattern, is_regex, canonical_payee, default_category_id) VALUES (?1, ?2, ?3, ?4)",
+            params![alias.pattern, alias.is_regex, alias.canonical_payee, alias.default_category_id],
+        ).map_err(|e| e.to_string())?;
+    } else {
+        conn.execute(
+            "UPDATE payee_aliases SET pattern = ?1, is_regex = ?2, canonical_payee = ?3, default_category_id = ?4 WHERE id = ?5",
+            params![alias.pattern, alias.is_regex, alias.canonical_payee, alias.default_category_id, alias.id],
+        ).map_err(|e| e.to_string())?;
+    }
+    fetch_payee_aliases(&conn)
+}
+
+/// First alias whose `pattern` matches `payee` — a plain case-insensitive substring check, or a
+/// compiled `Regex` search when `is_regex` is set. Invalid regexes are skipped rather than
+/// aborting the whole match pass, since one bad pattern shouldn't block every other alias.
+fn find_match<'a>(payee: &str, aliases: &'a [PayeeAlias]) -> Option<&'a PayeeAlias> {
+    let lower = payee.to_lowercase();
+    aliases.iter().find(|alias| {
+        if alias.is_regex {
+            Regex::new(&alias.pattern).map(|re| re.is_match(payee)).unwrap_or(false)
+        } else {
+            lower.contains(&alias.pattern.to_lowercase())
+        }
+    })
+}
+
+/// Rewrites `payee` to its canonical name and, if `category_id` is still unset, fills in the
+/// alias's `default_category_id` — in place, so both `transactions::import_transactions` and
+/// `csv_import::import_csv` can run every incoming row through the same engine before the usual
+/// dedup/validation path sees it.
+pub fn apply_aliases(transactions: &mut [Transaction], aliases: &[PayeeAlias]) {
+    if aliases.is_empty() {
+        return;
+    }
+    for t in transactions.iter_mut() {
+        if let Some(alias) = find_match(&t.payee, aliases) {
+            t.payee = alias.canonical_payee.clone();
+            if t.category_id.is_none() {
+                t.category_id = alias.default_category_id;
+            }
+        }
+    }
+}
+
+/// Re-runs [`apply_aliases`] against every already-imported row, for aliases added or edited
+/// after the fact. A transfer's departure/arrival legs share a `transfer_id` and must end up
+/// with the same canonical payee, so they're rewritten together from the departure leg's match
+/// rather than independently (a transfer leg's `category_id` is always null by design, so no
+/// `default_category_id` is ever applied to one).
+#[tauri::command]
+pub fn reapply_payee_aliases(app: AppHandle, key_state: State<'_, DbKeyState>) -> Result<usize, String> {
+    let conn = open_encrypted(&app, &key_state)?;
+    let aliases = fetch_payee_aliases(&conn)?;
+    if aliases.is_empty() {
+        return Ok(0);
+    }
+
+    let rows: Vec<(i64, String, Option<i64>, Option<i64>)> = {
+        let mut stmt = conn.prepare(
+            "SELECT id, payee, category_id, transfer_id FROM transactions
+             WHERE transfer_id IS NULL OR id = (SELECT MIN(id) FROM transactions t2 WHERE t2.transfer_id = transactions.transfer_id)"
+        ).map_err(|e| e.to_string())?;
+        stmt.query_map([], |row| {
+            Ok((row.get(0)?, row.get(1)?, row.get(2)?, row.get(3)?))
+        }).map_err(|e| e.to_string())?
+            .collect::<Result<_, _>>()
+            .map_err(|e| e.to_string())?
+    };
+
+    let mut updated = 0usize;
+    for (id, payee, category_id, transfer_id) in rows {
+        let Some(alias) = find_match(&payee, &aliases) else { continue };
+        if alias.canonical_payee == payee && (transfer_id.is_some() || category_id.is_some() || alias.default_category_id.is_none()) {
+            continue;
+        }
+
+        if let Some(transfer_id) = transfer_id {
+            conn.execute(
+                "UPDATE transactions SET payee = ?1 WHERE transfer_id = ?2",
+                params![alias.canonical_payee, transfer_id],
+            ).map_err(|e| e.to_string())?;
+        } else {
+            let new_category_id = category_id.or(alias.default_category_id);
+            conn.execute(
+                "UPDATE transactions SET payee = ?1, category_id = ?2 WHERE id = ?3",
+                params![alias.canonical_payee, new_category_id, id],
+            ).map_err(|e| e.to_string())?;
+        }
+        updated += 1;
+    }
+
+    Ok(updated)
+}
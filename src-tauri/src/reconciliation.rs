@@ -0,0 +1,182 @@
+use rusqlite::params;
+use tauri::State;
+
+use crate::accounts::BALANCE_ASSERTION_SQL;
+use crate::utils::DbPoolHandle;
+
+/// Tolerance below which a computed balance is considered to match the asserted one.
+/// Guards against floating point noise in the running `SUM`, not real discrepancies.
+const EPSILON: f64 = 0.005;
+
+/// Result of [`reconcile_account`]: unlike `transactions::reconcile_transactions`, a mismatch
+/// is reported back structurally instead of returned as an `Err`, so the caller can show the
+/// user the actual discrepancy (and decide whether to retry with different transactions)
+/// instead of just seeing a failed request.
+#[derive(serde::Serialize)]
+pub struct ReconcileResult {
+    pub matched: bool,
+    pub computed_balance: f64,
+    pub difference: f64,
+}
+
+#[derive(serde::Serialize)]
+pub struct BalanceAssertion {
+    pub id: i64,
+    pub account_id: i64,
+    pub assert_date: String,
+    pub expected_balance: f64,
+    pub created_at: String,
+}
+
+#[derive(serde::Serialize)]
+pub struct BalanceAssertionResult {
+    pub id: i64,
+    pub account_id: i64,
+    pub assert_date: String,
+    pub expected_balance: f64,
+    pub computed_balance: f64,
+    pub delta: f64,
+}
+
+#[tauri::command]
+pub fn create_balance_assertion(pool: State<'_, DbPoolHandle>, account_id: i64, assert_date: String, expected_balance: f64) -> Result<Vec<BalanceAssertion>, String> {
+    let conn = pool.current().get().map_err(|e| e.to_string())?;
+    conn.execute(
+        "INSERT INTO balance_assertions (account_id, assert_date, expected_balance) VALUES (?1, ?2, ?3)",
+        params![account_id, assert_date, expected_balance],
+    ).map_err(|e| e.to_string())?;
+    get_balance_assertions(pool, account_id)
+}
+
+#[tauri::command]
+pub fn get_balance_assertions(pool: State<'_, DbPoolHandle>, account_id: i64) -> Result<Vec<BalanceAssertion>, String> {
+    let conn = pool.current().get().map_err(|e| e.to_string())?;
+    let mut stmt = conn.prepare(
+        "SELECT id, account_id, assert_date, expected_balance, created_at FROM balance_assertions WHERE account_id = ?1 ORDER BY assert_date"
+    ).map_err(|e| e.to_string())?;
+    let rows = stmt.query_map(params![account_id], |row| {
+        Ok(BalanceAssertion {
+            id: row.get(0)?,
+            account_id: row.get(1)?,
+            assert_date: row.get(2)?,
+            expected_balance: row.get(3)?,
+            created_at: row.get(4)?,
+        })
+    }).map_err(|e| e.to_string())?;
+    let mut assertions = Vec::new();
+    for a in rows {
+        assertions.push(a.map_err(|e| e.to_string())?);
+    }
+    Ok(assertions)
+}
+
+#[tauri::command]
+pub fn delete_balance_assertion(pool: State<'_, DbPoolHandle>, id: i64) -> Result<(), String> {
+    let conn = pool.current().get().map_err(|e| e.to_string())?;
+    conn.execute("DELETE FROM balance_assertions WHERE id = ?1", params![id]).map_err(|e| e.to_string())?;
+    Ok(())
+}
+
+/// Recomputes each assertion's balance as of `assert_date` using the same sign logic as
+/// `get_accounts`, and returns only the ones where the computed balance has drifted from
+/// the expected one by more than [`EPSILON`] — a hint that a transaction between two
+/// reconciled dates is missing or duplicated.
+#[tauri::command]
+pub fn verify_balance_assertions(pool: State<'_, DbPoolHandle>) -> Result<Vec<BalanceAssertionResult>, String> {
+    let conn = pool.current().get().map_err(|e| e.to_string())?;
+    let mut stmt = conn.prepare(
+        "SELECT id, account_id, assert_date, expected_balance FROM balance_assertions ORDER BY assert_date"
+    ).map_err(|e| e.to_string())?;
+    let assertions: Vec<(i64, i64, String, f64)> = stmt
+        .query_map([], |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?, row.get(3)?)))
+        .map_err(|e| e.to_string())?
+        .collect::<Result<_, _>>()
+        .map_err(|e| e.to_string())?;
+
+    let mut balance_stmt = conn.prepare(BALANCE_ASSERTION_SQL).map_err(|e| e.to_string())?;
+
+    let mut mismatches = Vec::new();
+    for (id, account_id, assert_date, expected_balance) in assertions {
+        let computed_balance: f64 = balance_stmt
+            .query_row(params![account_id, assert_date], |row| row.get(0))
+            .map_err(|e| e.to_string())?;
+        let delta = computed_balance - expected_balance;
+        if delta.abs() > EPSILON {
+            mismatches.push(BalanceAssertionResult {
+                id,
+                account_id,
+                assert_date,
+                expected_balance,
+                computed_balance,
+                delta,
+            });
+        }
+    }
+    Ok(mismatches)
+}
+
+/// YNAB-style reconcile: the sum of `transaction_ids`' net effects (via `v_transactions_net`)
+/// plus whatever was already reconciled on `account_id` up to `statement_date` must equal
+/// `statement_balance`. On a match, marks `transaction_ids` `"reconciled"` and records a
+/// matching checkpoint; on a mismatch, nothing is marked — only the checkpoint is recorded, so
+/// the attempt is auditable — and the caller gets back the actual discrepancy instead of a bare
+/// error, letting the UI suggest which transactions might be missing or wrong.
+#[tauri::command]
+pub fn reconcile_account(
+    pool: State<'_, DbPoolHandle>,
+    account_id: i64,
+    statement_date: String,
+    statement_balance: f64,
+    transaction_ids: Vec<i64>,
+) -> Result<ReconcileResult, String> {
+    if transaction_ids.is_empty() {
+        return Err("No transactions selected to reconcile".to_string());
+    }
+
+    let mut conn = pool.current().get().map_err(|e| e.to_string())?;
+    let tx = conn.transaction().map_err(|e| e.to_string())?;
+
+    let placeholders = transaction_ids.iter().map(|_| "?").collect::<Vec<_>>().join(",");
+
+    let prior_reconciled: f64 = tx.query_row(
+        "SELECT IFNULL(SUM(v.net_value), 0) FROM v_transactions_net v
+         JOIN transactions t ON t.id = v.transaction_id
+         WHERE t.account_id = ?1 AND t.cleared_status = 'reconciled' AND t.date <= ?2",
+        params![account_id, statement_date],
+        |row| row.get(0),
+    ).map_err(|e| e.to_string())?;
+
+    let batch_net: f64 = {
+        let sql = format!(
+            "SELECT IFNULL(SUM(net_value), 0) FROM v_transactions_net WHERE transaction_id IN ({})",
+            placeholders
+        );
+        let mut stmt = tx.prepare(&sql).map_err(|e| e.to_string())?;
+        let bind_params: Vec<&dyn rusqlite::ToSql> = transaction_ids.iter().map(|id| id as &dyn rusqlite::ToSql).collect();
+        stmt.query_row(bind_params.as_slice(), |row| row.get(0)).map_err(|e| e.to_string())?
+    };
+
+    let computed_balance = prior_reconciled + batch_net;
+    let difference = computed_balance - statement_balance;
+    let matched = difference.abs() <= EPSILON;
+
+    if matched {
+        let sql = format!("UPDATE transactions SET cleared_status = 'reconciled' WHERE id IN ({})", placeholders);
+        let bind_params: Vec<&dyn rusqlite::ToSql> = transaction_ids.iter().map(|id| id as &dyn rusqlite::ToSql).collect();
+        tx.execute(&sql, bind_params.as_slice()).map_err(|e| e.to_string())?;
+    }
+
+    tx.execute(
+        "INSERT INTO reconciliation_checkpoints (account_id, statement_date, statement_balance, computed_balance, matched)
+         VALUES (?1, ?2, ?3, ?4, ?5)",
+        params![account_id, statement_date, statement_balance, computed_balance, matched],
+    ).map_err(|e| e.to_string())?;
+
+    tx.commit().map_err(|e| e.to_string())?;
+
+    if matched {
+        crate::accounts::recompute_balances(&conn)?;
+    }
+
+    Ok(ReconcileResult { matched, computed_balance, difference })
+}
@@ -0,0 +1,461 @@
+use crate::models::{IntervalUnit, RecurringFrequency, RecurringItem};
+use chrono::{Datelike, NaiveDate, Weekday};
+use std::collections::VecDeque;
+
+/// How often an [`RRule`] repeats. Mirrors RFC 5545's `FREQ` values this crate actually needs —
+/// `SECONDLY`/`MINUTELY`/`HOURLY` have no use case for a personal-finance recurring item.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Freq {
+    Daily,
+    Weekly,
+    Monthly,
+    Yearly,
+}
+
+/// One `BYDAY` token, e.g. `2TU` (the second Tuesday of the period) or `-1FR` (the last Friday).
+/// `ordinal == 0` means "every occurrence of this weekday in the period" (a plain `BYDAY=TU`
+/// with no ordinal, used for Weekly rules).
+#[derive(Debug, Clone, Copy)]
+pub struct ByDay {
+    pub ordinal: i32,
+    pub weekday: Weekday,
+}
+
+/// How an [`RRule`] stops producing occurrences.
+#[derive(Debug, Clone, Copy)]
+pub enum Termination {
+    Count(u32),
+    Until(NaiveDate),
+    Never,
+}
+
+/// A parsed RFC 5545 `RRULE` value (the part after `RRULE:`), e.g.
+/// `"FREQ=MONTHLY;INTERVAL=1;BYMONTHDAY=1,15;COUNT=12"` or `"FREQ=MONTHLY;BYDAY=2TU;UNTIL=20261231"`.
+/// Stored as this struct rather than the raw string so [`RRule::occurrences`] doesn't re-parse on
+/// every call; [`RRule::parse`] is the only place that touches the wire format.
+#[derive(Debug, Clone)]
+pub struct RRule {
+    pub freq: Freq,
+    pub interval: u32,
+    /// 1-31 for a day counted from the start of the month, or negative (e.g. `-1` for "last
+    /// day") counted back from the end of the month. Monthly/Yearly only.
+    pub by_month_day: Vec<i32>,
+    /// `BYDAY=2TU,-1FR` etc. Ordinals apply within a month (Monthly/Yearly); Weekly uses only
+    /// the weekday.
+    pub by_day: Vec<ByDay>,
+    pub termination: Termination,
+}
+
+fn parse_weekday(code: &str) -> Result<Weekday, String> {
+    match code {
+        "MO" => Ok(Weekday::Mon),
+        "TU" => Ok(Weekday::Tue),
+        "WE" => Ok(Weekday::Wed),
+        "TH" => Ok(Weekday::Thu),
+        "FR" => Ok(Weekday::Fri),
+        "SA" => Ok(Weekday::Sat),
+        "SU" => Ok(Weekday::Sun),
+        other => Err(format!("invalid BYDAY weekday code: {}", other)),
+    }
+}
+
+/// Parses one `BYDAY` token (`"2TU"`, `"-1FR"`, or a bare `"TU"`) into its ordinal/weekday parts.
+fn parse_by_day_token(token: &str) -> Result<ByDay, String> {
+    let split_at = token
+        .find(|c: char| c.is_ascii_alphabetic())
+        .ok_or_else(|| format!("invalid BYDAY token: {}", token))?;
+    let (ordinal_str, code) = token.split_at(split_at);
+    let ordinal = if ordinal_str.is_empty() { 0 } else {
+        ordinal_str.parse::<i32>().map_err(|e| e.to_string())?
+    };
+    Ok(ByDay { ordinal, weekday: parse_weekday(code)? })
+}
+
+impl RRule {
+    /// Parses a semicolon-separated `RRULE` value. Unrecognized keys are ignored rather than
+    /// rejected, so a future RFC 5545 field this engine doesn't yet implement (`BYSETPOS`,
+    /// `WKST`, ...) doesn't break an otherwise-valid rule.
+    pub fn parse(rrule: &str) -> Result<RRule, String> {
+        let mut freq = None;
+        let mut interval = 1u32;
+        let mut by_month_day = Vec::new();
+        let mut by_day = Vec::new();
+        let mut count = None;
+        let mut until = None;
+
+        for part in rrule.split(';') {
+            let part = part.trim();
+            if part.is_empty() {
+                continue;
+            }
+            let (key, value) = part.split_once('=').ok_or_else(|| format!("malformed RRULE part: {}", part))?;
+            match key {
+                "FREQ" => {
+                    freq = Some(match value {
+                        "DAILY" => Freq::Daily,
+                        "WEEKLY" => Freq::Weekly,
+                        "MONTHLY" => Freq::Monthly,
+                        "YEARLY" => Freq::Yearly,
+                        other => return Err(format!("unsupported FREQ: {}", other)),
+                    });
+                }
+                "INTERVAL" => interval = value.parse().map_err(|e: std::num::ParseIntError| e.to_string())?,
+                "BYMONTHDAY" => {
+                    for day in value.split(',') {
+                        by_month_day.push(day.parse::<i32>().map_err(|e| e.to_string())?);
+                    }
+                }
+                "BYDAY" => {
+                    for token in value.split(',') {
+                        by_day.push(parse_by_day_token(token)?);
+                    }
+                }
+                "COUNT" => count = Some(value.parse::<u32>().map_err(|e| e.to_string())?),
+                "UNTIL" => until = Some(
+                    NaiveDate::parse_from_str(value, "%Y%m%d")
+                        .map_err(|e| format!("invalid UNTIL date {}: {}", value, e))?,
+                ),
+                _ => {}
+            }
+        }
+
+        let termination = match (count, until) {
+            (Some(n), _) => Termination::Count(n),
+            (None, Some(d)) => Termination::Until(d),
+            (None, None) => Termination::Never,
+        };
+
+        Ok(RRule {
+            freq: freq.ok_or_else(|| "RRULE is missing FREQ".to_string())?,
+            interval: interval.max(1),
+            by_month_day,
+            by_day,
+            termination,
+        })
+    }
+
+    /// Lazily iterates every occurrence from `dtstart` onward.
+    pub fn iter_from(&self, dtstart: NaiveDate) -> RRuleIter {
+        RRuleIter {
+            rule: self.clone(),
+            dtstart,
+            counter_date: period_start(dtstart, self.freq),
+            remain: VecDeque::new(),
+            yielded: 0,
+            finished: false,
+            empty_periods: 0,
+        }
+    }
+
+    /// Every occurrence from `dtstart` in `[from, to]`, inclusive on both ends (`UNTIL`
+    /// comparisons are likewise inclusive, per RFC 5545).
+    pub fn occurrences(&self, dtstart: NaiveDate, from: NaiveDate, to: NaiveDate) -> Vec<NaiveDate> {
+        self.iter_from(dtstart)
+            .skip_while(|d| *d < from)
+            .take_while(|d| *d <= to)
+            .collect()
+    }
+}
+
+fn period_start(date: NaiveDate, freq: Freq) -> NaiveDate {
+    match freq {
+        Freq::Daily => date,
+        Freq::Weekly => date - chrono::Duration::days(date.weekday().num_days_from_monday() as i64),
+        Freq::Monthly => NaiveDate::from_ymd_opt(date.year(), date.month(), 1).unwrap(),
+        Freq::Yearly => NaiveDate::from_ymd_opt(date.year(), 1, 1).unwrap(),
+    }
+}
+
+/// Last valid day of `year`-`month`, used to resolve a negative `BYMONTHDAY` (`-1` = last day of
+/// the month) and to detect a month that lacks a requested positive day (e.g. `BYMONTHDAY=31` in
+/// February) so it can be skipped rather than clamped.
+fn last_day_of_month(year: i32, month: u32) -> u32 {
+    let (next_year, next_month) = if month == 12 { (year + 1, 1) } else { (year, month + 1) };
+    NaiveDate::from_ymd_opt(next_year, next_month, 1).unwrap().pred_opt().unwrap().day()
+}
+
+/// Every date in `year`-`month` matching `by_day`'s ordinal/weekday (`2TU` = the second Tuesday,
+/// `-1FR` = the last Friday, a bare weekday with `ordinal == 0` = every occurrence of it).
+fn by_day_matches_in_month(year: i32, month: u32, by_day: &[ByDay]) -> Vec<NaiveDate> {
+    let days_in_month = last_day_of_month(year, month);
+    let mut matches = Vec::new();
+    for rule in by_day {
+        let all: Vec<NaiveDate> = (1..=days_in_month)
+            .filter_map(|d| NaiveDate::from_ymd_opt(year, month, d))
+            .filter(|d| d.weekday() == rule.weekday)
+            .collect();
+        if rule.ordinal == 0 {
+            matches.extend(all);
+        } else if rule.ordinal > 0 {
+            if let Some(d) = all.get((rule.ordinal - 1) as usize) {
+                matches.push(*d);
+            }
+        } else if let Some(d) = all.iter().rev().nth((-rule.ordinal - 1) as usize) {
+            matches.push(*d);
+        }
+    }
+    matches
+}
+
+/// Lazy iterator over an [`RRule`]'s occurrence dates, built by [`RRule::iter_from`].
+///
+/// `counter_date` anchors the period currently being expanded (truncated to its start — the
+/// first of the month for Monthly, Jan 1 for Yearly, etc.); `remain` buffers the dates already
+/// expanded out of that period but not yet yielded. `next()` only expands one more period at a
+/// time so an unbounded rule (`Termination::Never`) never tries to materialize more than it's
+/// asked for.
+pub struct RRuleIter {
+    rule: RRule,
+    dtstart: NaiveDate,
+    counter_date: NaiveDate,
+    remain: VecDeque<NaiveDate>,
+    yielded: u32,
+    finished: bool,
+    empty_periods: u32,
+}
+
+/// Safety cap on consecutive empty periods (e.g. a Monthly rule whose `BYMONTHDAY`/`BYDAY` never
+/// matches) before giving up instead of looping forever looking for a candidate that doesn't
+/// exist.
+const MAX_CONSECUTIVE_EMPTY_PERIODS: u32 = 1000;
+
+impl RRuleIter {
+    /// Expands `counter_date`'s period into every candidate date (before DTSTART/UNTIL
+    /// filtering), sorted ascending.
+    fn expand_period(&self) -> Vec<NaiveDate> {
+        let year = self.counter_date.year();
+        let month = self.counter_date.month();
+
+        let mut candidates = match self.rule.freq {
+            Freq::Daily => vec![self.counter_date],
+            Freq::Weekly => {
+                if self.rule.by_day.is_empty() {
+                    // No BYDAY means "repeat on DTSTART's own weekday" (RFC 5545), not on
+                    // whatever weekday the period happens to start (counter_date is always
+                    // Monday-anchored by period_start).
+                    let offset = self.dtstart.weekday().num_days_from_monday() as i64;
+                    vec![self.counter_date + chrono::Duration::days(offset)]
+                } else {
+                    self.rule.by_day.iter()
+                        .filter_map(|d| {
+                            let offset = d.weekday.num_days_from_monday() as i64;
+                            Some(self.counter_date + chrono::Duration::days(offset))
+                        })
+                        .collect()
+                }
+            }
+            Freq::Monthly => {
+                let mut dates = Vec::new();
+                for &day in &self.rule.by_month_day {
+                    // A month lacking the requested day (BYMONTHDAY=31 in February) is skipped
+                    // outright rather than clamped to the month's last day.
+                    let resolved = if day < 0 {
+                        last_day_of_month(year, month) as i32 + day + 1
+                    } else {
+                        day
+                    };
+                    if resolved < 1 || resolved as u32 > last_day_of_month(year, month) {
+                        continue;
+                    }
+                    if let Some(d) = NaiveDate::from_ymd_opt(year, month, resolved as u32) {
+                        dates.push(d);
+                    }
+                }
+                dates.extend(by_day_matches_in_month(year, month, &self.rule.by_day));
+                dates
+            }
+            Freq::Yearly => {
+                // Yearly reuses the Monthly BYMONTHDAY/BYDAY expansion across every month in the
+                // year when no month restriction narrows it further (BYMONTH isn't implemented
+                // since no caller in this app needs it yet).
+                let mut dates = Vec::new();
+                for m in 1..=12u32 {
+                    for &day in &self.rule.by_month_day {
+                        let resolved = if day < 0 { last_day_of_month(year, m) as i32 + day + 1 } else { day };
+                        if resolved < 1 || resolved as u32 > last_day_of_month(year, m) {
+                            continue;
+                        }
+                        if let Some(d) = NaiveDate::from_ymd_opt(year, m, resolved as u32) {
+                            dates.push(d);
+                        }
+                    }
+                    dates.extend(by_day_matches_in_month(year, m, &self.rule.by_day));
+                }
+                if self.rule.by_month_day.is_empty() && self.rule.by_day.is_empty() {
+                    dates.push(self.counter_date);
+                }
+                dates
+            }
+        };
+
+        candidates.retain(|d| *d >= self.dtstart);
+        if let Termination::Until(until) = self.rule.termination {
+            candidates.retain(|d| *d <= until);
+        }
+        candidates.sort();
+        candidates.dedup();
+        candidates
+    }
+
+    fn advance_period(&mut self) {
+        self.counter_date = match self.rule.freq {
+            Freq::Daily => self.counter_date + chrono::Duration::days(self.rule.interval as i64),
+            Freq::Weekly => self.counter_date + chrono::Duration::weeks(self.rule.interval as i64),
+            Freq::Monthly => {
+                let total_months = self.counter_date.year() * 12 + (self.counter_date.month() as i32 - 1) + self.rule.interval as i32;
+                let year = total_months.div_euclid(12);
+                let month = (total_months.rem_euclid(12) + 1) as u32;
+                NaiveDate::from_ymd_opt(year, month, 1).unwrap()
+            }
+            Freq::Yearly => NaiveDate::from_ymd_opt(self.counter_date.year() + self.rule.interval as i32, 1, 1).unwrap(),
+        };
+    }
+}
+
+impl Iterator for RRuleIter {
+    type Item = NaiveDate;
+
+    fn next(&mut self) -> Option<NaiveDate> {
+        loop {
+            if let Some(date) = self.remain.pop_front() {
+                if let Termination::Count(n) = self.rule.termination {
+                    if self.yielded >= n {
+                        self.finished = true;
+                        self.remain.clear();
+                        return None;
+                    }
+                }
+                self.yielded += 1;
+                return Some(date);
+            }
+            if self.finished {
+                return None;
+            }
+            if let Termination::Until(until) = self.rule.termination {
+                if self.counter_date > until {
+                    self.finished = true;
+                    return None;
+                }
+            }
+
+            let candidates = self.expand_period();
+            self.remain.extend(candidates);
+            self.advance_period();
+
+            // An empty period (e.g. Monthly BYMONTHDAY=31 landing on February) would otherwise
+            // spin forever without yielding; keep expanding subsequent periods until one has
+            // candidates or a termination condition stops us, but give up after
+            // MAX_CONSECUTIVE_EMPTY_PERIODS rather than looping forever on a rule that can never
+            // match (e.g. an empty BYMONTHDAY/BYDAY with no termination).
+            if self.remain.is_empty() {
+                self.empty_periods += 1;
+                if self.empty_periods >= MAX_CONSECUTIVE_EMPTY_PERIODS {
+                    self.finished = true;
+                    return None;
+                }
+                continue;
+            }
+            self.empty_periods = 0;
+        }
+    }
+}
+
+/// Converts a [`RecurringFrequency::Weekly`] weekday in JavaScript's `Date.getDay()` numbering
+/// (`Sunday = 0`) to [`chrono::Weekday`] (`Monday = 0`), wrapping any out-of-range value modulo 7
+/// rather than panicking on a malformed `frequency` column.
+fn js_weekday_to_chrono(day: i32) -> Weekday {
+    match day.rem_euclid(7) {
+        0 => Weekday::Sun,
+        1 => Weekday::Mon,
+        2 => Weekday::Tue,
+        3 => Weekday::Wed,
+        4 => Weekday::Thu,
+        5 => Weekday::Fri,
+        _ => Weekday::Sat,
+    }
+}
+
+/// Builds the [`RRule`] a [`RecurringFrequency`] corresponds to. `Weekly`'s `weekdays` become
+/// `BYDAY` tokens (the one piece of information `to_legacy_columns` can't round-trip through
+/// `repeat_type`/`day_of_month`), so a caller reading `item.frequency` directly - rather than the
+/// legacy columns it was derived from - sees the user's actual weekday selection.
+fn rrule_from_frequency(frequency: &RecurringFrequency) -> RRule {
+    match frequency {
+        RecurringFrequency::MonthlyByDate { days } => RRule {
+            freq: Freq::Monthly,
+            interval: 1,
+            by_month_day: days.clone(),
+            by_day: Vec::new(),
+            termination: Termination::Never,
+        },
+        RecurringFrequency::Weekly { weekdays, interval } => RRule {
+            freq: Freq::Weekly,
+            interval: (*interval).max(1) as u32,
+            by_month_day: Vec::new(),
+            by_day: weekdays.iter()
+                .map(|&d| ByDay { ordinal: 0, weekday: js_weekday_to_chrono(d) })
+                .collect(),
+            termination: Termination::Never,
+        },
+        RecurringFrequency::EveryN { value, unit } => RRule {
+            freq: match unit {
+                IntervalUnit::Day => Freq::Daily,
+                IntervalUnit::Week => Freq::Weekly,
+                IntervalUnit::Month => Freq::Monthly,
+            },
+            interval: (*value).max(1) as u32,
+            by_month_day: Vec::new(),
+            by_day: Vec::new(),
+            termination: Termination::Never,
+        },
+    }
+}
+
+/// Builds an equivalent [`RRule`] + DTSTART for a [`RecurringItem`], in order of preference: an
+/// explicit `rrule` column is used directly; otherwise `item.frequency` (the typed
+/// [`RecurringFrequency`] - notably the only place a `Weekly` item's actual weekday selection
+/// survives) is used; only a row predating both columns falls back to deriving one from the
+/// legacy `repeat_type`/`interval_value`/`interval_unit`/`day_of_month` columns, so
+/// [`crate::recurring::get_recurring_item_occurrences`] can project every item through this one
+/// engine instead of keeping two separate date-math implementations.
+pub fn rrule_for_item(item: &RecurringItem) -> Result<(RRule, NaiveDate), String> {
+    let dtstart = item.start_date.as_deref()
+        .map(|d| NaiveDate::parse_from_str(d, "%Y-%m-%d"))
+        .transpose()
+        .map_err(|e| e.to_string())?
+        .unwrap_or_else(|| chrono::Local::now().date_naive());
+
+    if let Some(rrule_str) = &item.rrule {
+        return Ok((RRule::parse(rrule_str)?, dtstart));
+    }
+
+    if let Some(frequency_json) = &item.frequency {
+        if let Ok(frequency) = serde_json::from_str::<RecurringFrequency>(frequency_json) {
+            return Ok((rrule_from_frequency(&frequency), dtstart));
+        }
+    }
+
+    if item.repeat_type == "interval" {
+        let freq = match item.interval_unit.as_str() {
+            "day" => Freq::Daily,
+            "week" => Freq::Weekly,
+            _ => Freq::Monthly,
+        };
+        return Ok((
+            RRule {
+                freq,
+                interval: item.interval_value.max(1) as u32,
+                by_month_day: Vec::new(),
+                by_day: Vec::new(),
+                termination: Termination::Never,
+            },
+            dtstart,
+        ));
+    }
+
+    let by_month_day: Vec<i32> = serde_json::from_str(&item.day_of_month).unwrap_or_default();
+    Ok((
+        RRule { freq: Freq::Monthly, interval: 1, by_month_day, by_day: Vec::new(), termination: Termination::Never },
+        dtstart,
+    ))
+}
@@ -1,17 +1,18 @@
-use crate::models::RecurringItem;
-use rusqlite::{Connection, Result};
-use std::sync::Mutex;
-use tauri::State;
+use crate::models::{RecurringItem, RecurringItemInput};
+use crate::trigger_data_change_sync;
+use crate::utils::{DbPool, DbPoolHandle};
+use chrono::NaiveDate;
+use rusqlite::{params, Connection, Result};
+use serde::Serialize;
+use tauri::{AppHandle, State};
 
-pub type DbState = Mutex<Connection>;
-
-#[tauri::command]
-pub fn get_recurring_items(state: State<DbState>) -> Result<Vec<RecurringItem>, String> {
-    let conn = state.lock().unwrap();
+/// Shared by every command below (pool-backed, same as the rest of this file) so both reads and
+/// writes go through the same columns the same way.
+fn fetch_recurring_items(conn: &Connection) -> Result<Vec<RecurringItem>, String> {
     let mut stmt = conn.prepare(
-        "SELECT id, name, amount, type, category_id, account_id, day_of_month, is_active, notes, created_at, 
-                repeat_type, start_date, interval_value, interval_unit
-         FROM recurring_items 
+        "SELECT id, name, amount, type, category_id, account_id, day_of_month, is_active, notes, created_at,
+                repeat_type, start_date, interval_value, interval_unit, last_posted_date, rrule, frequency
+         FROM recurring_items
          ORDER BY name"
     ).map_err(|e| e.to_string())?;
 
@@ -31,6 +32,9 @@ pub fn get_recurring_items(state: State<DbState>) -> Result<Vec<RecurringItem>,
             start_date: row.get(11)?,
             interval_value: row.get(12)?,
             interval_unit: row.get(13)?,
+            last_posted_date: row.get(14)?,
+            rrule: row.get(15)?,
+            frequency: row.get(16)?,
         })
     }).map_err(|e| e.to_string())?;
 
@@ -41,172 +45,493 @@ pub fn get_recurring_items(state: State<DbState>) -> Result<Vec<RecurringItem>,
     Ok(result)
 }
 
+#[tauri::command]
+pub fn get_recurring_items(pool: State<'_, DbPoolHandle>) -> Result<Vec<RecurringItem>, String> {
+    let conn = pool.current().get().map_err(|e| e.to_string())?;
+    fetch_recurring_items(&conn)
+}
 
-
+/// Takes one typed `input` object instead of the old one-parameter-plus-`_alt`-twin-per-field
+/// pattern (`category_id`/`category_id_alt`, etc.) that tolerated both snake_case and camelCase
+/// argument names; `RecurringItemInput`'s `#[serde(rename_all = "camelCase")]` makes that
+/// tolerance unnecessary. `input.frequency` is stored both as JSON (`frequency`) and, via
+/// `RecurringFrequency::to_legacy_columns`, as the `repeat_type`/`day_of_month`/`interval_value`/
+/// `interval_unit` columns every existing reader still uses.
 #[tauri::command]
-pub fn add_recurring_item(
-    state: State<DbState>,
-    name: String,
-    amount: f64,
-    item_type: Option<String>,
-    item_type_alt: Option<String>, // For itemType from JS
-    category_id: Option<i64>,
-    category_id_alt: Option<i64>, // For categoryId from JS
-    account_id: Option<i64>,
-    account_id_alt: Option<i64>, // For accountId from JS
-    day_of_month: Option<String>,
-    day_of_month_alt: Option<String>, // For dayOfMonth from JS
-    is_active: Option<bool>,
-    is_active_alt: Option<bool>, // For isActive from JS
-    notes: Option<String>,
-    repeat_type: Option<String>,
-    repeat_type_alt: Option<String>, // For repeatType from JS
-    start_date: Option<String>,
-    start_date_alt: Option<String>, // For startDate from JS
-    interval_value: Option<i32>,
-    interval_value_alt: Option<i32>, // For intervalValue from JS
-    interval_unit: Option<String>,
-    interval_unit_alt: Option<String>, // For intervalUnit from JS
-) -> Result<Vec<RecurringItem>, String> {
-    // Use whichever values are provided
-    let final_item_type = item_type.or(item_type_alt).unwrap_or_else(|| "Expense".to_string());
-    let final_category_id = category_id.or(category_id_alt).unwrap_or(0);
-    let final_account_id = account_id.or(account_id_alt).unwrap_or(0);
-    let final_day_of_month = day_of_month.or(day_of_month_alt).unwrap_or_else(|| "[1]".to_string());
-    let final_is_active = is_active.or(is_active_alt).unwrap_or(true);
-    let final_notes = notes.unwrap_or_else(String::new);
-    let final_repeat_type = repeat_type.or(repeat_type_alt).unwrap_or_else(|| "monthly_date".to_string());
-    let final_start_date = start_date.or(start_date_alt);
-    let final_interval_value = interval_value.or(interval_value_alt).unwrap_or(1);
-    let final_interval_unit = interval_unit.or(interval_unit_alt).unwrap_or_else(|| "month".to_string());
-    
+pub fn add_recurring_item(pool: State<'_, DbPoolHandle>, input: RecurringItemInput) -> Result<Vec<RecurringItem>, String> {
+    let (repeat_type, day_of_month, interval_value, interval_unit) = input.frequency.to_legacy_columns();
+    let frequency_json = serde_json::to_string(&input.frequency).map_err(|e| e.to_string())?;
+
     {
-        let conn = state.lock().unwrap();
+        let conn = pool.current().get().map_err(|e| e.to_string())?;
         conn.execute(
-            "INSERT INTO recurring_items (name, amount, type, category_id, account_id, day_of_month, is_active, notes, created_at, 
-                                       repeat_type, start_date, interval_value, interval_unit) 
-             VALUES (?, ?, ?, ?, ?, ?, ?, ?, datetime('now'), ?, ?, ?, ?)",
+            "INSERT INTO recurring_items (name, amount, type, category_id, account_id, day_of_month, is_active, notes, created_at,
+                                       repeat_type, start_date, interval_value, interval_unit, frequency)
+             VALUES (?, ?, ?, ?, ?, ?, ?, ?, datetime('now'), ?, ?, ?, ?, ?)",
             rusqlite::params![
-                name, amount, final_item_type, final_category_id, final_account_id, final_day_of_month, final_is_active, final_notes,
-                final_repeat_type,
-                final_start_date,
-                final_interval_value,
-                final_interval_unit
+                input.name, input.amount, input.item_type, input.category_id, input.account_id, day_of_month,
+                input.is_active, input.notes.unwrap_or_default(),
+                repeat_type, input.start_date, interval_value, interval_unit, frequency_json
             ],
         ).map_err(|e| e.to_string())?;
     }
 
-    get_recurring_items(state)
+    get_recurring_items(pool)
 }
 
-
-
 #[tauri::command]
-pub fn update_recurring_item(
-    state: State<DbState>,
-    id: i64,
-    name: String,
-    amount: f64,
-    item_type: Option<String>,
-    item_type_alt: Option<String>, // For itemType from JS
-    category_id: Option<i64>,
-    category_id_alt: Option<i64>, // For categoryId from JS
-    account_id: Option<i64>,
-    account_id_alt: Option<i64>, // For accountId from JS
-    day_of_month: Option<String>,
-    day_of_month_alt: Option<String>, // For dayOfMonth from JS
-    is_active: Option<bool>,
-    is_active_alt: Option<bool>, // For isActive from JS
-    notes: Option<String>,
-    repeat_type: Option<String>,
-    repeat_type_alt: Option<String>, // For repeatType from JS
-    start_date: Option<String>,
-    start_date_alt: Option<String>, // For startDate from JS
-    interval_value: Option<i32>,
-    interval_value_alt: Option<i32>, // For intervalValue from JS
-    interval_unit: Option<String>,
-    interval_unit_alt: Option<String>, // For intervalUnit from JS
-) -> Result<Vec<RecurringItem>, String> {
-    // Use whichever values are provided
-    let final_item_type = item_type.or(item_type_alt).unwrap_or_else(|| "Expense".to_string());
-    let final_category_id = category_id.or(category_id_alt).unwrap_or(0);
-    let final_account_id = account_id.or(account_id_alt).unwrap_or(0);
-    let final_day_of_month = day_of_month.or(day_of_month_alt).unwrap_or_else(|| "[1]".to_string());
-    let final_is_active = is_active.or(is_active_alt).unwrap_or(true);
-    let final_notes = notes.unwrap_or_else(String::new);
-    let final_repeat_type = repeat_type.or(repeat_type_alt).unwrap_or_else(|| "monthly_date".to_string());
-    let final_start_date = start_date.or(start_date_alt);
-    let final_interval_value = interval_value.or(interval_value_alt).unwrap_or(1);
-    let final_interval_unit = interval_unit.or(interval_unit_alt).unwrap_or_else(|| "month".to_string());
-    
+pub fn update_recurring_item(pool: State<'_, DbPoolHandle>, id: i64, input: RecurringItemInput) -> Result<Vec<RecurringItem>, String> {
+    let (repeat_type, day_of_month, interval_value, interval_unit) = input.frequency.to_legacy_columns();
+    let frequency_json = serde_json::to_string(&input.frequency).map_err(|e| e.to_string())?;
+
     {
-        let conn = state.lock().unwrap();
+        let conn = pool.current().get().map_err(|e| e.to_string())?;
         conn.execute(
-            "UPDATE recurring_items 
+            "UPDATE recurring_items
              SET name = ?, amount = ?, type = ?, category_id = ?, account_id = ?, day_of_month = ?, is_active = ?, notes = ?,
-                 repeat_type = ?, start_date = ?, interval_value = ?, interval_unit = ?
+                 repeat_type = ?, start_date = ?, interval_value = ?, interval_unit = ?, frequency = ?
              WHERE id = ?",
             rusqlite::params![
-                name, amount, final_item_type, final_category_id, final_account_id, final_day_of_month, final_is_active, final_notes,
-                final_repeat_type,
-                final_start_date,
-                final_interval_value,
-                final_interval_unit,
+                input.name, input.amount, input.item_type, input.category_id, input.account_id, day_of_month,
+                input.is_active, input.notes.unwrap_or_default(),
+                repeat_type, input.start_date, interval_value, interval_unit, frequency_json,
                 id
             ],
         ).map_err(|e| e.to_string())?;
     }
 
-    get_recurring_items(state)
+    get_recurring_items(pool)
 }
 
 #[tauri::command]
-pub fn delete_recurring_item(state: State<DbState>, id: i64) -> Result<(), String> {
-    let conn = state.lock().unwrap();
-    
+pub fn delete_recurring_item(pool: State<'_, DbPoolHandle>, id: i64) -> Result<(), String> {
+    let conn = pool.current().get().map_err(|e| e.to_string())?;
+
     conn.execute("DELETE FROM recurring_items WHERE id = ?", rusqlite::params![id])
         .map_err(|e| e.to_string())?;
 
     Ok(())
-} 
+}
 
 #[tauri::command]
 pub fn update_recurring_check(
-    state: State<DbState>,
+    pool: State<'_, DbPoolHandle>,
     occurrence_id: String,
     month: String,
     is_checked: bool,
 ) -> Result<(), String> {
-    let conn = state.lock().unwrap();
-    
+    let conn = pool.current().get().map_err(|e| e.to_string())?;
+
     // Insert or replace the check status for the specific occurrence and month
     conn.execute(
-        "INSERT OR REPLACE INTO recurring_checks (occurrence_id, month, is_checked, updated_at) 
+        "INSERT OR REPLACE INTO recurring_checks (occurrence_id, month, is_checked, updated_at)
          VALUES (?, ?, ?, datetime('now'))",
         rusqlite::params![occurrence_id, month, is_checked],
     ).map_err(|e| e.to_string())?;
-    
+
     Ok(())
 }
 
 #[tauri::command]
 pub fn get_recurring_checks(
-    state: State<DbState>,
+    pool: State<'_, DbPoolHandle>,
     month: String,
 ) -> Result<Vec<String>, String> {
-    let conn = state.lock().unwrap();
-    let mut stmt = conn.prepare(
-        "SELECT occurrence_id FROM recurring_checks 
-         WHERE month = ? AND is_checked = 1"
-    ).map_err(|e| e.to_string())?;
+    let conn = pool.current().get().map_err(|e| e.to_string())?;
+    get_recurring_checks_for_month(&conn, &month)
+}
+/// Every occurrence date for `item` strictly after `after` (or, if `after` is `None`, from its
+/// recurrence rule's DTSTART onward) and on or before `through`. Projects through
+/// [`crate::recurrence::rrule_for_item`] — the same RFC 5545 engine [`get_recurring_item_occurrences`]
+/// and [`post_due_recurring_items`] use — so every recurring-items reader agrees on what's due
+/// regardless of which one it asks; an `after` cutoff (rather than `post_due_recurring_items`'s
+/// own `recurring_postings`-table idempotency) is how `last_posted_date`-tracked callers
+/// (`run_due_recurring`, `get_upcoming_recurring`) avoid re-surfacing an occurrence already
+/// handled.
+fn occurrences_between(item: &RecurringItem, after: Option<NaiveDate>, through: NaiveDate) -> Result<Vec<NaiveDate>, String> {
+    let (rule, dtstart) = crate::recurrence::rrule_for_item(item)?;
+    let from = after.map(|a| a + chrono::Duration::days(1)).unwrap_or(dtstart);
+    Ok(rule.occurrences(dtstart, from, through))
+}
 
-    let items = stmt.query_map([month], |row| {
-        Ok(row.get(0)?)
-    }).map_err(|e| e.to_string())?;
+/// One occurrence [`run_due_recurring`] would post (or already has), for the UI's "upcoming
+/// recurring" preview.
+#[derive(Serialize)]
+pub struct UpcomingRecurringOccurrence {
+    pub item_id: i64,
+    pub name: String,
+    pub date: String,
+}
+
+/// Every occurrence of every active recurring item due within `days` of today, including ones
+/// already past due but not yet posted — lets the UI preview what `run_due_recurring` would do
+/// (or let the user manually trigger it) without actually posting anything.
+#[tauri::command]
+pub fn get_upcoming_recurring(pool: State<'_, DbPoolHandle>, days: i64) -> Result<Vec<UpcomingRecurringOccurrence>, String> {
+    let conn = pool.current().get().map_err(|e| e.to_string())?;
+    let items = fetch_recurring_items(&conn)?;
+    let horizon = chrono::Local::now().date_naive() + chrono::Duration::days(days.max(0));
 
+    let mut upcoming = Vec::new();
+    for item in items.into_iter().filter(|i| i.is_active) {
+        let after = item.last_posted_date.as_deref()
+            .map(|d| NaiveDate::parse_from_str(d, "%Y-%m-%d"))
+            .transpose()
+            .map_err(|e| e.to_string())?;
+        for date in occurrences_between(&item, after, horizon)? {
+            upcoming.push(UpcomingRecurringOccurrence {
+                item_id: item.id,
+                name: item.name.clone(),
+                date: date.format("%Y-%m-%d").to_string(),
+            });
+        }
+    }
+    upcoming.sort_by(|a, b| a.date.cmp(&b.date));
+    Ok(upcoming)
+}
+
+/// Every concrete due date an item's recurrence rule produces in `[from, to]`, inclusive — the
+/// new RFC 5545 engine ([`crate::recurrence::rrule_for_item`]) projects items that have set an
+/// explicit `rrule`, and an equivalent rule derived from `frequency` or, without one, the legacy
+/// `repeat_type`/`interval_value`/`interval_unit`/`day_of_month` columns otherwise, so callers get one consistent date list
+/// regardless of which vocabulary the item was created with.
+#[tauri::command]
+pub fn get_recurring_item_occurrences(
+    pool: State<'_, DbPoolHandle>, id: i64, from: String, to: String,
+) -> Result<Vec<String>, String> {
+    let conn = pool.current().get().map_err(|e| e.to_string())?;
+    let item = fetch_recurring_items(&conn)?
+        .into_iter()
+        .find(|i| i.id == id)
+        .ok_or_else(|| "Recurring item not found".to_string())?;
+
+    let from_date = NaiveDate::parse_from_str(&from, "%Y-%m-%d").map_err(|e| e.to_string())?;
+    let to_date = NaiveDate::parse_from_str(&to, "%Y-%m-%d").map_err(|e| e.to_string())?;
+
+    let (rule, dtstart) = crate::recurrence::rrule_for_item(&item)?;
+    Ok(rule.occurrences(dtstart, from_date, to_date)
+        .into_iter()
+        .map(|d| d.format("%Y-%m-%d").to_string())
+        .collect())
+}
+
+/// One concrete due date of a [`RecurringItem`], joined with its per-month checked state — what
+/// [`get_recurring_occurrences`] returns so the UI doesn't have to separately call
+/// [`get_recurring_items`] and [`get_recurring_checks`] and join them itself.
+#[derive(Serialize)]
+pub struct RecurringOccurrence {
+    pub item_id: i64,
+    /// `"{item_id}:{due_date}"`, the same key [`update_recurring_check`]/[`get_recurring_checks`]
+    /// use to track checked state and [`run_due_recurring`]'s `import_id` uses to track posting.
+    pub occurrence_id: String,
+    pub due_date: String,
+    pub name: String,
+    pub amount: f64,
+    pub item_type: String,
+    pub category_id: i64,
+    pub account_id: i64,
+    pub is_checked: bool,
+}
+
+/// Every active item's concrete due dates in `[from_date, to_date]`, each resolved against
+/// `recurring_checks` for the occurrence's own month so the UI can render one flat, already-joined
+/// list instead of cross-referencing [`get_recurring_items`] and [`get_recurring_checks`] itself.
+#[tauri::command]
+pub fn get_recurring_occurrences(
+    pool: State<'_, DbPoolHandle>, from_date: String, to_date: String,
+) -> Result<Vec<RecurringOccurrence>, String> {
+    let conn = pool.current().get().map_err(|e| e.to_string())?;
+    let from = NaiveDate::parse_from_str(&from_date, "%Y-%m-%d").map_err(|e| e.to_string())?;
+    let to = NaiveDate::parse_from_str(&to_date, "%Y-%m-%d").map_err(|e| e.to_string())?;
+    compute_recurring_occurrences(&conn, Some(from), to)
+}
+
+/// Shared by [`get_recurring_occurrences`] and [`get_upcoming_recurring_digest`] so both project
+/// occurrences, resolve exceptions, and join checked state the same way.
+fn compute_recurring_occurrences(conn: &Connection, from: Option<NaiveDate>, to: NaiveDate) -> Result<Vec<RecurringOccurrence>, String> {
+    let items = fetch_recurring_items(conn)?;
+    let mut checked_by_month: std::collections::HashMap<String, std::collections::HashSet<String>> = std::collections::HashMap::new();
+
+    let mut occurrences = Vec::new();
+    for item in items.into_iter().filter(|i| i.is_active) {
+        for date in occurrences_between(&item, None, to)? {
+            if from.is_some_and(|from| date < from) {
+                continue;
+            }
+            let due_date = date.format("%Y-%m-%d").to_string();
+            let occurrence_id = format!("{}:{}", item.id, due_date);
+
+            let exception = crate::recurring_exceptions::get_exception(&conn, &occurrence_id)?;
+            let Some((due_date, amount, _notes)) = crate::recurring_exceptions::apply_exception(
+                exception.as_ref(), due_date, item.amount, item.notes.clone(),
+            ) else {
+                continue;
+            };
+
+            let month = due_date[0..7].to_string();
+            let checked = checked_by_month.entry(month.clone()).or_insert_with(|| {
+                get_recurring_checks_for_month(&conn, &month).unwrap_or_default().into_iter().collect()
+            });
+            occurrences.push(RecurringOccurrence {
+                item_id: item.id,
+                is_checked: checked.contains(&occurrence_id),
+                occurrence_id,
+                due_date,
+                name: item.name.clone(),
+                amount,
+                item_type: item.item_type.clone(),
+                category_id: item.category_id,
+                account_id: item.account_id,
+            });
+        }
+    }
+    occurrences.sort_by(|a, b| a.due_date.cmp(&b.due_date));
+    Ok(occurrences)
+}
+
+/// Shared by [`get_recurring_occurrences`] and [`get_recurring_checks`] so both read
+/// `recurring_checks` the same way.
+fn get_recurring_checks_for_month(conn: &Connection, month: &str) -> Result<Vec<String>, String> {
+    let mut stmt = conn.prepare(
+        "SELECT occurrence_id FROM recurring_checks WHERE month = ?1 AND is_checked = 1"
+    ).map_err(|e| e.to_string())?;
+    let items = stmt.query_map([month], |row| row.get(0)).map_err(|e| e.to_string())?;
     let mut result = Vec::new();
     for item in items {
         result.push(item.map_err(|e| e.to_string())?);
     }
     Ok(result)
-} 
\ No newline at end of file
+}
+
+/// One grouping key's running total in [`RecurringDigest`] (one entry per distinct `item_type` or
+/// `account_id`, covering every unchecked occurrence in the digest's window).
+#[derive(Serialize)]
+pub struct RecurringDigestTotal {
+    pub key: String,
+    pub total: f64,
+}
+
+/// [`get_upcoming_recurring_digest`]'s result: every unchecked occurrence due within the window,
+/// split into `overdue` (due date already passed) and `upcoming` (due date still ahead), each with
+/// a running total, plus totals broken down by `item_type` and by `account_id` across both groups
+/// combined - for a notification summary like "3 bills overdue ($420), 5 due this week ($610)".
+#[derive(Serialize)]
+pub struct RecurringDigest {
+    pub overdue: Vec<RecurringOccurrence>,
+    pub upcoming: Vec<RecurringOccurrence>,
+    pub overdue_total: f64,
+    pub upcoming_total: f64,
+    pub by_item_type: Vec<RecurringDigestTotal>,
+    pub by_account: Vec<RecurringDigestTotal>,
+}
+
+/// Every unchecked recurring occurrence due within `days_ahead` days of today (including ones
+/// already past due), grouped into `overdue`/`upcoming` for a notification digest. Reuses
+/// [`compute_recurring_occurrences`] so it honors the same exceptions and checked state as
+/// [`get_recurring_occurrences`].
+#[tauri::command]
+pub fn get_upcoming_recurring_digest(pool: State<'_, DbPoolHandle>, days_ahead: i64) -> Result<RecurringDigest, String> {
+    let conn = pool.current().get().map_err(|e| e.to_string())?;
+    let today = chrono::Local::now().date_naive();
+    let horizon = today + chrono::Duration::days(days_ahead.max(0));
+
+    let occurrences: Vec<RecurringOccurrence> = compute_recurring_occurrences(&conn, None, horizon)?
+        .into_iter()
+        .filter(|o| !o.is_checked)
+        .collect();
+
+    let today_str = today.format("%Y-%m-%d").to_string();
+    let mut overdue = Vec::new();
+    let mut upcoming = Vec::new();
+    let mut by_item_type: std::collections::HashMap<String, f64> = std::collections::HashMap::new();
+    let mut by_account: std::collections::HashMap<i64, f64> = std::collections::HashMap::new();
+
+    for occurrence in occurrences {
+        *by_item_type.entry(occurrence.item_type.clone()).or_insert(0.0) += occurrence.amount;
+        *by_account.entry(occurrence.account_id).or_insert(0.0) += occurrence.amount;
+        if occurrence.due_date < today_str {
+            overdue.push(occurrence);
+        } else {
+            upcoming.push(occurrence);
+        }
+    }
+
+    let overdue_total = overdue.iter().map(|o| o.amount).sum();
+    let upcoming_total = upcoming.iter().map(|o| o.amount).sum();
+
+    Ok(RecurringDigest {
+        overdue,
+        upcoming,
+        overdue_total,
+        upcoming_total,
+        by_item_type: by_item_type.into_iter().map(|(key, total)| RecurringDigestTotal { key, total }).collect(),
+        by_account: by_account.into_iter().map(|(account_id, total)| RecurringDigestTotal { key: account_id.to_string(), total }).collect(),
+    })
+}
+
+/// Materializes every past-due occurrence of every active recurring item into real
+/// `transactions` rows, keyed by `"recurring-item:{id}:{date}"` as the row's `import_id` so a
+/// re-run (the app's own startup hook, or the user hitting a manual "post now" button) can never
+/// double-post an occurrence already inserted. Advances each item's `last_posted_date` to the
+/// latest occurrence handled so future runs only look at the range after it.
+#[tauri::command]
+pub async fn run_due_recurring(app: AppHandle, pool: State<'_, DbPoolHandle>) -> Result<usize, String> {
+    run_due_recurring_with_pool(app, pool.current()).await
+}
+
+/// Core of [`run_due_recurring`], taking an owned pool so it can also be run from `setup()`
+/// before `State` extraction is available, mirroring
+/// `recurring_rules::materialize_due_transactions_with_pool`.
+pub async fn run_due_recurring_with_pool(app: AppHandle, pool: DbPool) -> Result<usize, String> {
+    let today = chrono::Local::now().date_naive();
+    let items = {
+        let conn = pool.get().map_err(|e| e.to_string())?;
+        fetch_recurring_items(&conn)?
+    };
+
+    let mut posted = 0usize;
+    for item in items.into_iter().filter(|i| i.is_active) {
+        let after = item.last_posted_date.as_deref()
+            .map(|d| NaiveDate::parse_from_str(d, "%Y-%m-%d"))
+            .transpose()
+            .map_err(|e| e.to_string())?;
+        let due = occurrences_between(&item, after, today)?;
+        if due.is_empty() {
+            continue;
+        }
+
+        let mut conn = pool.get().map_err(|e| e.to_string())?;
+        let mut last_date = after;
+        for date in due {
+            let date_str = date.format("%Y-%m-%d").to_string();
+            let import_id = format!("recurring-item:{}:{}", item.id, date_str);
+            let already_posted: bool = conn.query_row(
+                "SELECT EXISTS(SELECT 1 FROM transactions WHERE import_id = ?1)",
+                params![import_id],
+                |row| row.get(0),
+            ).map_err(|e| e.to_string())?;
+
+            if !already_posted {
+                let tx = conn.transaction().map_err(|e| e.to_string())?;
+                tx.execute(
+                    "INSERT INTO transactions (date, account_id, type, category_id, amount, payee, notes, import_id)
+                     VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8)",
+                    params![date_str, item.account_id, item.item_type, item.category_id, item.amount, item.name, item.notes, import_id],
+                ).map_err(|e| e.to_string())?;
+                tx.commit().map_err(|e| e.to_string())?;
+                posted += 1;
+            }
+            last_date = Some(date);
+        }
+
+        if let Some(last_date) = last_date {
+            conn.execute(
+                "UPDATE recurring_items SET last_posted_date = ?1 WHERE id = ?2",
+                params![last_date.format("%Y-%m-%d").to_string(), item.id],
+            ).map_err(|e| e.to_string())?;
+        }
+    }
+
+    if posted > 0 {
+        let conn = pool.get().map_err(|e| e.to_string())?;
+        crate::accounts::recompute_balances(&conn)?;
+        trigger_data_change_sync(&app).await;
+    }
+
+    Ok(posted)
+}
+
+/// Posts every active item's occurrences on or before `up_to_date` that haven't already been
+/// recorded in `recurring_postings`, recording each one there keyed by `occurrence_id` so
+/// [`unpost_recurring_occurrence`] can reverse it later and so re-running this command (e.g. from
+/// a different `up_to_date`) never double-posts. Distinct from [`run_due_recurring`], which is
+/// always "as of today" and tracks idempotency only via the transaction's own `import_id` — this
+/// command exists for callers (a manual "post through end of month" action, a scheduled reconcile
+/// job) that need an explicit cutoff date and a postable/reversible audit trail.
+#[tauri::command]
+pub async fn post_due_recurring_items(app: AppHandle, pool: State<'_, DbPoolHandle>, up_to_date: String) -> Result<usize, String> {
+    let cutoff = NaiveDate::parse_from_str(&up_to_date, "%Y-%m-%d").map_err(|e| e.to_string())?;
+    let items = {
+        let conn = pool.current().get().map_err(|e| e.to_string())?;
+        fetch_recurring_items(&conn)?
+    };
+
+    let mut posted = 0usize;
+    for item in items.into_iter().filter(|i| i.is_active) {
+        let (rule, dtstart) = crate::recurrence::rrule_for_item(&item)?;
+        let due = rule.occurrences(dtstart, dtstart, cutoff);
+        if due.is_empty() {
+            continue;
+        }
+
+        let mut conn = pool.current().get().map_err(|e| e.to_string())?;
+        for date in due {
+            let date_str = date.format("%Y-%m-%d").to_string();
+            let occurrence_id = format!("{}:{}", item.id, date_str);
+
+            let already_posted: bool = conn.query_row(
+                "SELECT EXISTS(SELECT 1 FROM recurring_postings WHERE occurrence_id = ?1)",
+                params![occurrence_id],
+                |row| row.get(0),
+            ).map_err(|e| e.to_string())?;
+            if already_posted {
+                continue;
+            }
+
+            let exception = crate::recurring_exceptions::get_exception(&conn, &occurrence_id)?;
+            let Some((posted_date, amount, notes)) = crate::recurring_exceptions::apply_exception(
+                exception.as_ref(), date_str.clone(), item.amount, item.notes.clone(),
+            ) else {
+                continue;
+            };
+
+            let tx = conn.transaction().map_err(|e| e.to_string())?;
+            tx.execute(
+                "INSERT INTO transactions (date, account_id, type, category_id, amount, payee, notes, import_id)
+                 VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8)",
+                params![posted_date, item.account_id, item.item_type, item.category_id, amount, item.name, notes,
+                        format!("recurring-item:{}:{}", item.id, date_str)],
+            ).map_err(|e| e.to_string())?;
+            let transaction_id = tx.last_insert_rowid();
+            tx.execute(
+                "INSERT INTO recurring_postings (occurrence_id, recurring_item_id, transaction_id) VALUES (?1, ?2, ?3)",
+                params![occurrence_id, item.id, transaction_id],
+            ).map_err(|e| e.to_string())?;
+            tx.commit().map_err(|e| e.to_string())?;
+            posted += 1;
+        }
+    }
+
+    if posted > 0 {
+        let conn = pool.current().get().map_err(|e| e.to_string())?;
+        crate::accounts::recompute_balances(&conn)?;
+        trigger_data_change_sync(&app).await;
+    }
+
+    Ok(posted)
+}
+
+/// Reverses a posting made by [`post_due_recurring_items`]: deletes the transaction it created
+/// and the `recurring_postings` row tracking it, so the occurrence is eligible to be posted again
+/// (e.g. after the user fixes a mis-categorized recurring item and wants it re-posted).
+#[tauri::command]
+pub async fn unpost_recurring_occurrence(app: AppHandle, pool: State<'_, DbPoolHandle>, occurrence_id: String) -> Result<(), String> {
+    let conn = pool.current().get().map_err(|e| e.to_string())?;
+    let transaction_id: Option<i64> = conn.query_row(
+        "SELECT transaction_id FROM recurring_postings WHERE occurrence_id = ?1",
+        params![occurrence_id],
+        |row| row.get(0),
+    ).ok();
+    let Some(transaction_id) = transaction_id else {
+        return Ok(());
+    };
+
+    conn.execute("DELETE FROM recurring_postings WHERE occurrence_id = ?1", params![occurrence_id])
+        .map_err(|e| e.to_string())?;
+    conn.execute("DELETE FROM transactions WHERE id = ?1", params![transaction_id])
+        .map_err(|e| e.to_string())?;
+
+    crate::accounts::recompute_balances(&conn)?;
+    trigger_data_change_sync(&app).await;
+    Ok(())
+}
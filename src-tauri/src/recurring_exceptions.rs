@@ -0,0 +1,88 @@
+use crate::utils::DbPoolHandle;
+use rusqlite::{params, Connection};
+use tauri::State;
+
+/// One per-occurrence override of a [`crate::models::RecurringItem`]'s otherwise-computed
+/// schedule: skip it, move it to a different date, or post it with a different amount/notes.
+/// Looked up by [`crate::recurring::get_recurring_occurrences`] and
+/// [`crate::recurring::post_due_recurring_items`] so both honor the same exceptions.
+#[derive(Debug, Clone)]
+pub struct RecurringException {
+    pub action: String,
+    pub new_amount: Option<f64>,
+    pub new_date: Option<String>,
+    pub new_notes: Option<String>,
+}
+
+/// Reads the exception for `occurrence_id`, if any. Takes a plain `&Connection` so it works
+/// against any pooled connection `recurring` checks out, regardless of which command is asking.
+pub fn get_exception(conn: &Connection, occurrence_id: &str) -> Result<Option<RecurringException>, String> {
+    match conn.query_row(
+        "SELECT action, new_amount, new_date, new_notes FROM recurring_exceptions WHERE occurrence_id = ?1",
+        params![occurrence_id],
+        |row| {
+            Ok(RecurringException {
+                action: row.get(0)?,
+                new_amount: row.get(1)?,
+                new_date: row.get(2)?,
+                new_notes: row.get(3)?,
+            })
+        },
+    ) {
+        Ok(exception) => Ok(Some(exception)),
+        Err(rusqlite::Error::QueryReturnedNoRows) => Ok(None),
+        Err(e) => Err(e.to_string()),
+    }
+}
+
+/// Applies `exception` (if any) to an occurrence's otherwise-computed `(due_date, amount, notes)`,
+/// returning `None` if the occurrence should be dropped entirely (a `'skip'` exception).
+pub fn apply_exception(
+    exception: Option<&RecurringException>, due_date: String, amount: f64, notes: Option<String>,
+) -> Option<(String, f64, Option<String>)> {
+    let Some(exception) = exception else { return Some((due_date, amount, notes)) };
+    match exception.action.as_str() {
+        "skip" => None,
+        "reschedule" => Some((exception.new_date.clone().unwrap_or(due_date), amount, notes)),
+        "override" => Some((
+            due_date,
+            exception.new_amount.unwrap_or(amount),
+            exception.new_notes.clone().or(notes),
+        )),
+        _ => Some((due_date, amount, notes)),
+    }
+}
+
+#[tauri::command]
+pub fn set_recurring_exception(
+    pool: State<'_, DbPoolHandle>, occurrence_id: String, action: String,
+    new_amount: Option<f64>, new_date: Option<String>, new_notes: Option<String>,
+) -> Result<(), String> {
+    if !matches!(action.as_str(), "skip" | "reschedule" | "override") {
+        return Err(format!("invalid recurring exception action: {}", action));
+    }
+    let recurring_item_id: i64 = occurrence_id
+        .split(':')
+        .next()
+        .and_then(|s| s.parse().ok())
+        .ok_or_else(|| format!("malformed occurrence_id: {}", occurrence_id))?;
+
+    let conn = pool.current().get().map_err(|e| e.to_string())?;
+    conn.execute(
+        "INSERT INTO recurring_exceptions (occurrence_id, recurring_item_id, action, new_amount, new_date, new_notes)
+         VALUES (?1, ?2, ?3, ?4, ?5, ?6)
+         ON CONFLICT(occurrence_id) DO UPDATE SET
+            action = excluded.action, new_amount = excluded.new_amount,
+            new_date = excluded.new_date, new_notes = excluded.new_notes",
+        params![occurrence_id, recurring_item_id, action, new_amount, new_date, new_notes],
+    ).map_err(|e| e.to_string())?;
+    Ok(())
+}
+
+#[tauri::command]
+pub fn clear_recurring_exception(pool: State<'_, DbPoolHandle>, occurrence_id: String) -> Result<(), String> {
+    let conn = pool.current().get().map_err(|e| e.to_string())?;
+    conn.execute("DELETE FROM recurring_exceptions WHERE occurrence_id = ?1", params![occurrence_id])
+        .map_err(|e| e.to_string())?;
+    Ok(())
+}
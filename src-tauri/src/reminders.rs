@@ -1,31 +1,90 @@
+use chrono::{Datelike, NaiveDate};
 use rusqlite::{params, Connection};
-use tauri::AppHandle;
-use crate::models::Reminder;
-use crate::utils::get_db_path;
+use tauri::{AppHandle, State};
+use crate::encryption::{open_encrypted, DbKeyState};
+use crate::models::{Frequency, Reminder};
 use serde_json;
-use crate::models::ReminderPaymentHistory;
+use crate::models::{ReminderPaymentHistory, StatementSummary};
 
-#[tauri::command]
-pub fn get_reminders(app: AppHandle) -> Result<Vec<Reminder>, String> {
-    let path = get_db_path(&app);
-    let conn = Connection::open(path).map_err(|e| e.to_string())?;
-    let mut stmt = conn.prepare("SELECT id, account_id, account_name, payment_day, next_payment_date, is_checked, notes, created_at, statement_date FROM reminders ORDER BY is_checked ASC, next_payment_date ASC").map_err(|e| e.to_string())?;
+/// Last valid day of `year`-`month`, used to clamp a `Monthly`/`Quarterly`/`Annual` day anchor
+/// of 29-31 instead of overflowing into the next month (e.g. day 31 in April lands on April 30).
+fn last_day_of_month(year: i32, month: u32) -> u32 {
+    let (next_year, next_month) = if month == 12 { (year + 1, 1) } else { (year, month + 1) };
+    NaiveDate::from_ymd_opt(next_year, next_month, 1)
+        .unwrap()
+        .pred_opt()
+        .unwrap()
+        .day()
+}
+
+/// `from` shifted forward by `months`, re-anchored to `day` and clamped to the target month's
+/// last day.
+fn add_months_clamped(from: NaiveDate, months: i32, day: u32) -> NaiveDate {
+    let total_months = from.year() * 12 + (from.month() as i32 - 1) + months;
+    let year = total_months.div_euclid(12);
+    let month = (total_months.rem_euclid(12) + 1) as u32;
+    let day = day.clamp(1, 31).min(last_day_of_month(year, month));
+    NaiveDate::from_ymd_opt(year, month, day).unwrap()
+}
+
+/// Computes the next occurrence of `freq` strictly after `from`. The critical edge case is
+/// month-end clamping (`Monthly { day: 31 }` in February lands on the 28th/29th); bi-weekly
+/// cadences instead advance from `anchor_date` in fixed 14-day steps so a late check-in never
+/// drifts the cadence forward.
+pub fn next_occurrence(from: NaiveDate, freq: &Frequency) -> NaiveDate {
+    match freq {
+        Frequency::Weekly => from + chrono::Duration::days(7),
+        Frequency::BiWeekly { anchor_date } => {
+            let anchor = NaiveDate::parse_from_str(anchor_date, "%Y-%m-%d").unwrap_or(from);
+            let mut next = anchor;
+            while next <= from {
+                next += chrono::Duration::days(14);
+            }
+            next
+        }
+        Frequency::Monthly { day } => add_months_clamped(from, 1, *day as u32),
+        Frequency::Quarterly { day, .. } => add_months_clamped(from, 3, *day as u32),
+        Frequency::SemiAnnual => add_months_clamped(from, 6, from.day()),
+        Frequency::Annual { month, day } => {
+            let year = from.year() + 1;
+            let day = (*day as u32).clamp(1, 31).min(last_day_of_month(year, *month as u32));
+            NaiveDate::from_ymd_opt(year, *month as u32, day).unwrap()
+        }
+    }
+}
+
+/// Reminders created before the `frequency` column existed (and any row where it failed to
+/// deserialize) fall back to the `Monthly { day: payment_day }` cadence `check_reminder` used
+/// implicitly beforehand.
+fn parse_frequency(frequency_json: Option<&str>, payment_day: u8) -> Frequency {
+    frequency_json
+        .and_then(|s| serde_json::from_str(s).ok())
+        .unwrap_or(Frequency::Monthly { day: payment_day })
+}
+
+/// Shared query behind [`get_reminders`] and every other command here that returns the full
+/// reminder list after mutating it, so each only ever opens one connection for the whole call.
+pub(crate) fn fetch_reminders(conn: &Connection) -> Result<Vec<Reminder>, String> {
+    let mut stmt = conn.prepare("SELECT id, account_id, account_name, payment_day, next_payment_date, is_checked, notes, created_at, statement_date, frequency FROM reminders ORDER BY is_checked ASC, next_payment_date ASC").map_err(|e| e.to_string())?;
     let rows = stmt.query_map([], |row| {
         let notes_str: Option<String> = row.get(6)?;
         let notes: Option<Vec<String>> = match notes_str {
             Some(s) => serde_json::from_str(&s).ok(),
             None => None,
         };
+        let payment_day: u8 = row.get(3)?;
+        let frequency_json: Option<String> = row.get(9)?;
         Ok(Reminder {
             id: row.get(0)?,
             account_id: row.get(1)?,
             account_name: row.get(2)?,
-            payment_day: row.get(3)?,
+            payment_day,
             next_payment_date: row.get(4)?,
             is_checked: row.get(5)?,
             notes,
             created_at: row.get(7)?,
             statement_date: row.get(8)?,
+            frequency: parse_frequency(frequency_json.as_deref(), payment_day),
         })
     }).map_err(|e| e.to_string())?;
     let mut reminders = Vec::new();
@@ -36,12 +95,18 @@ pub fn get_reminders(app: AppHandle) -> Result<Vec<Reminder>, String> {
 }
 
 #[tauri::command]
-pub fn add_reminder(app: AppHandle, reminder: Reminder) -> Result<Vec<Reminder>, String> {
-    let path = get_db_path(&app);
-    let conn = Connection::open(path).map_err(|e| e.to_string())?;
+pub fn get_reminders(app: AppHandle, key_state: State<'_, DbKeyState>) -> Result<Vec<Reminder>, String> {
+    let conn = open_encrypted(&app, &key_state)?;
+    fetch_reminders(&conn)
+}
+
+#[tauri::command]
+pub fn add_reminder(app: AppHandle, key_state: State<'_, DbKeyState>, reminder: Reminder) -> Result<Vec<Reminder>, String> {
+    let conn = open_encrypted(&app, &key_state)?;
     let notes_json = reminder.notes.as_ref().map(|n| serde_json::to_string(n).unwrap_or_default());
+    let frequency_json = serde_json::to_string(&reminder.frequency).map_err(|e| e.to_string())?;
     conn.execute(
-        "INSERT INTO reminders (account_id, account_name, payment_day, next_payment_date, is_checked, notes, statement_date) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)",
+        "INSERT INTO reminders (account_id, account_name, payment_day, next_payment_date, is_checked, notes, statement_date, frequency) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8)",
         params![
             reminder.account_id,
             reminder.account_name,
@@ -50,18 +115,19 @@ pub fn add_reminder(app: AppHandle, reminder: Reminder) -> Result<Vec<Reminder>,
             reminder.is_checked,
             notes_json,
             reminder.statement_date,
+            frequency_json,
         ],
     ).map_err(|e| e.to_string())?;
-    get_reminders(app)
+    fetch_reminders(&conn)
 }
 
 #[tauri::command]
-pub fn update_reminder(app: AppHandle, reminder: Reminder) -> Result<Vec<Reminder>, String> {
-    let path = get_db_path(&app);
-    let conn = Connection::open(path).map_err(|e| e.to_string())?;
+pub fn update_reminder(app: AppHandle, key_state: State<'_, DbKeyState>, reminder: Reminder) -> Result<Vec<Reminder>, String> {
+    let conn = open_encrypted(&app, &key_state)?;
     let notes_json = reminder.notes.as_ref().map(|n| serde_json::to_string(n).unwrap_or_default());
+    let frequency_json = serde_json::to_string(&reminder.frequency).map_err(|e| e.to_string())?;
     conn.execute(
-        "UPDATE reminders SET account_id = ?1, account_name = ?2, payment_day = ?3, next_payment_date = ?4, is_checked = ?5, notes = ?6, statement_date = ?7 WHERE id = ?8",
+        "UPDATE reminders SET account_id = ?1, account_name = ?2, payment_day = ?3, next_payment_date = ?4, is_checked = ?5, notes = ?6, statement_date = ?7, frequency = ?8 WHERE id = ?9",
         params![
             reminder.account_id,
             reminder.account_name,
@@ -70,35 +136,55 @@ pub fn update_reminder(app: AppHandle, reminder: Reminder) -> Result<Vec<Reminde
             reminder.is_checked,
             notes_json,
             reminder.statement_date,
+            frequency_json,
             reminder.id,
         ],
     ).map_err(|e| e.to_string())?;
-    get_reminders(app)
+    fetch_reminders(&conn)
 }
 
 #[tauri::command]
-pub fn delete_reminder(app: AppHandle, id: i64) -> Result<Vec<Reminder>, String> {
-    let path = get_db_path(&app);
-    let conn = Connection::open(path).map_err(|e| e.to_string())?;
+pub fn delete_reminder(app: AppHandle, key_state: State<'_, DbKeyState>, id: i64) -> Result<Vec<Reminder>, String> {
+    let conn = open_encrypted(&app, &key_state)?;
     conn.execute("DELETE FROM reminders WHERE id = ?1", params![id]).map_err(|e| e.to_string())?;
-    get_reminders(app)
+    fetch_reminders(&conn)
 }
 
+/// Marks `id` checked and rolls `next_payment_date`/`statement_date` forward by the reminder's
+/// stored `frequency`, so the frontend no longer has to work out the rolled-forward dates itself.
 #[tauri::command]
-pub fn check_reminder(app: AppHandle, id: i64, next_payment_date: String, next_statement_date: String) -> Result<Vec<Reminder>, String> {
-    let path = get_db_path(&app);
-    let conn = Connection::open(path).map_err(|e| e.to_string())?;
+pub fn check_reminder(app: AppHandle, key_state: State<'_, DbKeyState>, id: i64) -> Result<Vec<Reminder>, String> {
+    let conn = open_encrypted(&app, &key_state)?;
+
+    let (payment_day, next_payment_date, statement_date, frequency_json): (u8, String, String, Option<String>) = conn
+        .query_row(
+            "SELECT payment_day, next_payment_date, statement_date, frequency FROM reminders WHERE id = ?1",
+            params![id],
+            |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?, row.get(3)?)),
+        )
+        .map_err(|e| e.to_string())?;
+    let frequency = parse_frequency(frequency_json.as_deref(), payment_day);
+
+    let current_payment_date = NaiveDate::parse_from_str(&next_payment_date, "%Y-%m-%d").map_err(|e| e.to_string())?;
+    let new_payment_date = next_occurrence(current_payment_date, &frequency).format("%Y-%m-%d").to_string();
+
+    // Not every reminder tracks a statement date (only credit-card-style ones do); roll it
+    // forward on the same cadence when present, and leave it untouched otherwise.
+    let new_statement_date = match NaiveDate::parse_from_str(&statement_date, "%Y-%m-%d") {
+        Ok(current) => next_occurrence(current, &frequency).format("%Y-%m-%d").to_string(),
+        Err(_) => statement_date,
+    };
+
     conn.execute(
         "UPDATE reminders SET is_checked = 1, next_payment_date = ?1, statement_date = ?2 WHERE id = ?3",
-        params![next_payment_date, next_statement_date, id],
+        params![new_payment_date, new_statement_date, id],
     ).map_err(|e| e.to_string())?;
-    get_reminders(app)
+    fetch_reminders(&conn)
 }
 
 #[tauri::command]
-pub fn add_note_to_reminder(app: AppHandle, id: i64, note: String) -> Result<Vec<Reminder>, String> {
-    let path = get_db_path(&app);
-    let conn = Connection::open(&path).map_err(|e| e.to_string())?;
+pub fn add_note_to_reminder(app: AppHandle, key_state: State<'_, DbKeyState>, id: i64, note: String) -> Result<Vec<Reminder>, String> {
+    let conn = open_encrypted(&app, &key_state)?;
     let mut notes: Vec<String> = {
         let mut stmt = conn.prepare("SELECT notes FROM reminders WHERE id = ?1").map_err(|e| e.to_string())?;
         let notes_str: Option<String> = stmt.query_row(params![id], |row| row.get(0)).ok();
@@ -110,13 +196,12 @@ pub fn add_note_to_reminder(app: AppHandle, id: i64, note: String) -> Result<Vec
     notes.insert(0, note); // 최신순
     let notes_json = serde_json::to_string(&notes).unwrap_or_default();
     conn.execute("UPDATE reminders SET notes = ?1 WHERE id = ?2", params![notes_json, id]).map_err(|e| e.to_string())?;
-    get_reminders(app)
+    fetch_reminders(&conn)
 }
 
 #[tauri::command]
-pub fn delete_note_from_reminder(app: AppHandle, id: i64, note_index: usize) -> Result<Vec<Reminder>, String> {
-    let path = get_db_path(&app);
-    let conn = Connection::open(&path).map_err(|e| e.to_string())?;
+pub fn delete_note_from_reminder(app: AppHandle, key_state: State<'_, DbKeyState>, id: i64, note_index: usize) -> Result<Vec<Reminder>, String> {
+    let conn = open_encrypted(&app, &key_state)?;
     let mut notes: Vec<String> = {
         let mut stmt = conn.prepare("SELECT notes FROM reminders WHERE id = ?1").map_err(|e| e.to_string())?;
         let notes_str: Option<String> = stmt.query_row(params![id], |row| row.get(0)).ok();
@@ -130,13 +215,12 @@ pub fn delete_note_from_reminder(app: AppHandle, id: i64, note_index: usize) ->
     }
     let notes_json = serde_json::to_string(&notes).unwrap_or_default();
     conn.execute("UPDATE reminders SET notes = ?1 WHERE id = ?2", params![notes_json, id]).map_err(|e| e.to_string())?;
-    get_reminders(app)
+    fetch_reminders(&conn)
 }
 
 #[tauri::command]
-pub fn get_reminder_payment_history(app: AppHandle, reminder_id: i64) -> Result<Vec<ReminderPaymentHistory>, String> {
-    let path = get_db_path(&app);
-    let conn = Connection::open(path).map_err(|e| e.to_string())?;
+pub fn get_reminder_payment_history(app: AppHandle, key_state: State<'_, DbKeyState>, reminder_id: i64) -> Result<Vec<ReminderPaymentHistory>, String> {
+    let conn = open_encrypted(&app, &key_state)?;
     let mut stmt = conn.prepare(
         "SELECT id, reminder_id, paid_date, is_paid, created_at, statement_date, note
          FROM reminder_payment_history
@@ -162,9 +246,8 @@ pub fn get_reminder_payment_history(app: AppHandle, reminder_id: i64) -> Result<
 }
 
 #[tauri::command]
-pub fn add_reminder_payment_history(app: AppHandle, reminder_id: i64, paid_date: String, statement_date: Option<String>, note: Option<String>) -> Result<(), String> {
-    let path = get_db_path(&app);
-    let conn = Connection::open(path).map_err(|e| e.to_string())?;
+pub fn add_reminder_payment_history(app: AppHandle, key_state: State<'_, DbKeyState>, reminder_id: i64, paid_date: String, statement_date: Option<String>, note: Option<String>) -> Result<(), String> {
+    let conn = open_encrypted(&app, &key_state)?;
     if let Some(statement_date) = statement_date {
         conn.execute(
             "INSERT INTO reminder_payment_history (reminder_id, paid_date, is_paid, statement_date, note) VALUES (?1, ?2, 1, ?3, ?4)",
@@ -180,9 +263,8 @@ pub fn add_reminder_payment_history(app: AppHandle, reminder_id: i64, paid_date:
 }
 
 #[tauri::command]
-pub fn uncheck_reminder_payment_history(app: AppHandle, id: i64) -> Result<(), String> {
-    let path = get_db_path(&app);
-    let conn = Connection::open(path).map_err(|e| e.to_string())?;
+pub fn uncheck_reminder_payment_history(app: AppHandle, key_state: State<'_, DbKeyState>, id: i64) -> Result<(), String> {
+    let conn = open_encrypted(&app, &key_state)?;
     conn.execute(
         "UPDATE reminder_payment_history SET is_paid = 0 WHERE id = ?1",
         params![id],
@@ -191,9 +273,8 @@ pub fn uncheck_reminder_payment_history(app: AppHandle, id: i64) -> Result<(), S
 }
 
 #[tauri::command]
-pub fn delete_reminder_payment_history(app: AppHandle, id: i64) -> Result<(), String> {
-    let path = get_db_path(&app);
-    let conn = Connection::open(path).map_err(|e| e.to_string())?;
+pub fn delete_reminder_payment_history(app: AppHandle, key_state: State<'_, DbKeyState>, id: i64) -> Result<(), String> {
+    let conn = open_encrypted(&app, &key_state)?;
     // Get reminder_id, paid_date, and statement_date before deleting
     let mut stmt = conn.prepare("SELECT reminder_id, paid_date, statement_date FROM reminder_payment_history WHERE id = ?1").map_err(|e| e.to_string())?;
     let (reminder_id, paid_date, statement_date): (i64, String, Option<String>) = stmt.query_row(params![id], |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?))).map_err(|e| e.to_string())?;
@@ -209,9 +290,8 @@ pub fn delete_reminder_payment_history(app: AppHandle, id: i64) -> Result<(), St
 }
 
 #[tauri::command]
-pub fn update_reminder_payment_history_note(app: AppHandle, id: i64, note: String) -> Result<(), String> {
-    let path = get_db_path(&app);
-    let conn = Connection::open(path).map_err(|e| e.to_string())?;
+pub fn update_reminder_payment_history_note(app: AppHandle, key_state: State<'_, DbKeyState>, id: i64, note: String) -> Result<(), String> {
+    let conn = open_encrypted(&app, &key_state)?;
     conn.execute(
         "UPDATE reminder_payment_history SET note = ?1 WHERE id = ?2",
         params![note, id],
@@ -221,102 +301,217 @@ pub fn update_reminder_payment_history_note(app: AppHandle, id: i64, note: Strin
 
 #[tauri::command]
 #[allow(non_snake_case)]
-pub fn get_statement_balance(app: AppHandle, accountId: i64, startDate: String, endDate: String) -> Result<f64, String> {
-    let path = get_db_path(&app);
-    let conn = Connection::open(path).map_err(|e| e.to_string())?;
-    
-
-    
-         // Statement Balance 계산: Transfer가 아닌 모든 트랜잭션 포함 (Reimbursement 카테고리 포함)
-     let mut stmt = conn.prepare(
-         "SELECT SUM(t.amount) 
-          FROM transactions t 
-          WHERE t.account_id = ?1 
-          AND t.date >= ?2 
-          AND t.date <= ?3 
-          AND t.type != 'Transfer'"
-     ).map_err(|e| e.to_string())?;
-    
-    let sum: f64 = stmt.query_row(params![accountId, startDate, endDate], |row| row.get(0)).unwrap_or(0.0);
-    
-         // 디버깅: Reimbursement 카테고리 트랜잭션도 확인
-     let mut reimbursement_stmt = conn.prepare(
-         "SELECT SUM(t.amount), COUNT(*) 
-          FROM transactions t 
-          LEFT JOIN categories c ON t.category_id = c.id 
-          WHERE t.account_id = ?1 
-          AND t.date >= ?2 
-          AND t.date <= ?3 
-          AND t.type != 'Transfer' 
-          AND c.is_reimbursement = 1"
-     ).map_err(|e| e.to_string())?;
-    
-    let _reimbursement_result: (f64, i64) = reimbursement_stmt.query_row(
-        params![accountId, startDate, endDate], 
-        |row| Ok((row.get(0)?, row.get(1)?))
-    ).unwrap_or((0.0, 0));
-    
-         // 디버깅: 해당 기간의 모든 트랜잭션 세부 정보 출력
-     let mut detail_stmt = conn.prepare(
-         "SELECT t.date, t.type, t.amount, c.name as category_name, c.is_reimbursement
-          FROM transactions t 
-          LEFT JOIN categories c ON t.category_id = c.id 
-          WHERE t.account_id = ?1 
-          AND t.date >= ?2 
-          AND t.date <= ?3 
-          AND t.type != 'Transfer'
-          ORDER BY t.date DESC"
-     ).map_err(|e| e.to_string())?;
-     
-     let _rows = detail_stmt.query_map(params![accountId, startDate, endDate], |row| {
-         Ok((
-             row.get::<_, String>(0)?,
-             row.get::<_, String>(1)?,
-             row.get::<_, Option<String>>(3)?,
-             row.get::<_, Option<bool>>(4)?
-         ))
-     }).map_err(|e| e.to_string())?;
-     
-
-     
-     // 트랜잭션 목록을 다시 조회 (count()로 인해 iterator가 소비됨)
-     let mut detail_stmt2 = conn.prepare(
-         "SELECT t.date, t.type, t.amount, c.name as category_name, c.is_reimbursement
-          FROM transactions t 
-          LEFT JOIN categories c ON t.category_id = c.id 
-          WHERE t.account_id = ?1 
-          AND t.date >= ?2 
-          AND t.date <= ?3 
-          AND t.type != 'Transfer'
-          ORDER BY t.date DESC"
-     ).map_err(|e| e.to_string())?;
-     
-     let rows2 = detail_stmt2.query_map(params![accountId, startDate, endDate], |row| {
-         Ok((
-             row.get::<_, String>(0)?,
-             row.get::<_, String>(1)?,
-             row.get::<_, f64>(2)?,
-             row.get::<_, Option<String>>(3)?,
-             row.get::<_, Option<bool>>(4)?
-         ))
-     }).map_err(|e| e.to_string())?;
-     
-     let mut _total_amount = 0.0;
-     let mut _transaction_count = 0;
-     
-     for row in rows2 {
-         if let Ok((_date, _tx_type, amount, category_name, is_reimbursement)) = row {
-             let _category_info = category_name.unwrap_or_else(|| "Unknown".to_string());
-             let _reimbursement_flag = is_reimbursement.unwrap_or(false);
-             
-             _total_amount += amount;
-             _transaction_count += 1;
-             
-
-         }
-     }
-    
-
-    
-    Ok(sum)
-} 
\ No newline at end of file
+pub fn get_statement_balance(app: AppHandle, key_state: State<'_, DbKeyState>, accountId: i64, startDate: String, endDate: String) -> Result<f64, String> {
+    let conn = open_encrypted(&app, &key_state)?;
+    statement_sum(&conn, accountId, &startDate, &endDate)
+}
+
+/// Sum of `accountId`'s non-transfer transactions in `[start_date, end_date]`, same convention
+/// `get_statement_balance` uses (raw signed `amount`, so a credit account's statement balance
+/// comes out negative while it's in debt).
+fn statement_sum(conn: &Connection, account_id: i64, start_date: &str, end_date: &str) -> Result<f64, String> {
+    conn.query_row(
+        "SELECT IFNULL(SUM(amount), 0) FROM transactions WHERE account_id = ?1 AND date >= ?2 AND date <= ?3 AND type != 'Transfer'",
+        params![account_id, start_date, end_date],
+        |row| row.get(0),
+    ).map_err(|e| e.to_string())
+}
+
+/// Number of days between `start_date` and `end_date`, floored to 1 so a same-day statement
+/// period still gets a non-zero interest-projection denominator.
+fn days_in_cycle(start_date: &str, end_date: &str) -> Result<i64, String> {
+    let start = NaiveDate::parse_from_str(start_date, "%Y-%m-%d").map_err(|e| e.to_string())?;
+    let end = NaiveDate::parse_from_str(end_date, "%Y-%m-%d").map_err(|e| e.to_string())?;
+    Ok((end - start).num_days().max(1))
+}
+
+/// Walks the period's transactions in date order starting from `prior_balance`, weighting each
+/// running balance by the number of days it was held, to approximate the average daily balance
+/// a card issuer computes for interest purposes.
+fn average_daily_balance(conn: &Connection, account_id: i64, prior_balance: f64, start_date: &str, end_date: &str) -> Result<f64, String> {
+    let start = NaiveDate::parse_from_str(start_date, "%Y-%m-%d").map_err(|e| e.to_string())?;
+    let end = NaiveDate::parse_from_str(end_date, "%Y-%m-%d").map_err(|e| e.to_string())?;
+
+    let rows: Vec<(String, f64)> = {
+        let mut stmt = conn.prepare(
+            "SELECT date, amount FROM transactions WHERE account_id = ?1 AND date >= ?2 AND date <= ?3 AND type != 'Transfer' ORDER BY date ASC"
+        ).map_err(|e| e.to_string())?;
+        stmt.query_map(params![account_id, start_date, end_date], |row| Ok((row.get(0)?, row.get(1)?)))
+            .map_err(|e| e.to_string())?
+            .collect::<Result<_, _>>()
+            .map_err(|e| e.to_string())?
+    };
+
+    let mut running_balance = prior_balance;
+    let mut current_date = start;
+    let mut weighted_sum = 0.0;
+    let mut total_days = 0i64;
+
+    for (date_str, amount) in rows {
+        let date = NaiveDate::parse_from_str(&date_str, "%Y-%m-%d").map_err(|e| e.to_string())?;
+        let days_held = (date - current_date).num_days().max(0);
+        weighted_sum += running_balance * days_held as f64;
+        total_days += days_held;
+        running_balance += amount;
+        current_date = date;
+    }
+    let remaining_days = (end - current_date).num_days().max(0);
+    weighted_sum += running_balance * remaining_days as f64;
+    total_days += remaining_days;
+
+    if total_days == 0 {
+        Ok(running_balance)
+    } else {
+        Ok(weighted_sum / total_days as f64)
+    }
+}
+
+/// Sum of `|amount|` for every reimbursement-category expense in `[start_date, end_date]` that
+/// already has a `reimbursement_links` row, i.e. money the user fronted but will get back — what
+/// `compute_statement`'s net balance backs out of the gross statement balance.
+fn reimbursed_expense_total(conn: &Connection, account_id: i64, start_date: &str, end_date: &str) -> Result<f64, String> {
+    conn.query_row(
+        "SELECT IFNULL(SUM(ABS(t.amount)), 0)
+         FROM transactions t
+         JOIN reimbursement_links l ON l.expense_transaction_id = t.id
+         WHERE t.account_id = ?1 AND t.date >= ?2 AND t.date <= ?3",
+        params![account_id, start_date, end_date],
+        |row| row.get(0),
+    ).map_err(|e| e.to_string())
+}
+
+/// Richer sibling of [`get_statement_balance`]: the statement balance plus the prior balance
+/// carried in, the minimum payment due (the account's configured floor/percentage, whichever is
+/// larger), and a projected next-cycle interest charge if only the minimum is paid.
+#[tauri::command]
+#[allow(non_snake_case)]
+pub fn compute_statement(
+    app: AppHandle,
+    key_state: State<'_, DbKeyState>,
+    accountId: i64,
+    startDate: String,
+    endDate: String,
+) -> Result<StatementSummary, String> {
+    let conn = open_encrypted(&app, &key_state)?;
+
+    let statement_balance = statement_sum(&conn, accountId, &startDate, &endDate)?;
+    let net_balance = statement_balance + reimbursed_expense_total(&conn, accountId, &startDate, &endDate)?;
+    let prior_balance = conn.query_row(
+        "SELECT IFNULL(SUM(amount), 0) FROM transactions WHERE account_id = ?1 AND date < ?2 AND type != 'Transfer'",
+        params![accountId, startDate],
+        |row| row.get::<_, f64>(0),
+    ).map_err(|e| e.to_string())?;
+
+    let (apr, min_payment_floor, min_payment_pct): (Option<f64>, Option<f64>, Option<f64>) = conn
+        .query_row(
+            "SELECT apr, min_payment_floor, min_payment_pct FROM accounts WHERE id = ?1",
+            params![accountId],
+            |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?)),
+        )
+        .map_err(|e| e.to_string())?;
+
+    let minimum_payment = min_payment_floor
+        .unwrap_or(0.0)
+        .max(min_payment_pct.unwrap_or(0.0) * statement_balance.abs());
+
+    let projected_interest = match apr {
+        Some(apr) if apr > 0.0 => {
+            let avg_daily_balance = average_daily_balance(&conn, accountId, prior_balance, &startDate, &endDate)?;
+            let daily_rate = apr / 365.0;
+            avg_daily_balance.abs() * daily_rate * days_in_cycle(&startDate, &endDate)? as f64
+        }
+        _ => 0.0,
+    };
+
+    Ok(StatementSummary { statement_balance, net_balance, prior_balance, minimum_payment, projected_interest })
+}
+
+/// Amount difference within which two transactions are considered an exact reimbursement match —
+/// guards against floating point noise, not a real partial-reimbursement case.
+const REIMBURSEMENT_AMOUNT_EPSILON: f64 = 0.005;
+
+#[derive(Debug, serde::Serialize)]
+pub struct ReimbursementLink {
+    pub id: i64,
+    pub expense_transaction_id: i64,
+    pub credit_transaction_id: i64,
+    pub matched_amount: f64,
+}
+
+/// Greedily pairs unlinked reimbursement-category expenses in `[startDate, endDate]` with later
+/// credits on the same account whose amount matches within [`REIMBURSEMENT_AMOUNT_EPSILON`],
+/// walking expenses oldest-first and claiming each one's nearest-dated unclaimed candidate credit
+/// (credits aren't limited to the statement period, since a reimbursement often lands after it).
+/// Each transaction can only be linked once, so already-reconciled rows are skipped and a credit
+/// claimed by one expense can't also settle another. Returns the links created by this call.
+#[tauri::command]
+#[allow(non_snake_case)]
+pub fn reconcile_reimbursements(
+    app: AppHandle,
+    key_state: State<'_, DbKeyState>,
+    accountId: i64,
+    startDate: String,
+    endDate: String,
+) -> Result<Vec<ReimbursementLink>, String> {
+    let conn = open_encrypted(&app, &key_state)?;
+
+    let expenses: Vec<(i64, String, f64)> = {
+        let mut stmt = conn.prepare(
+            "SELECT t.id, t.date, t.amount
+             FROM transactions t
+             JOIN categories c ON t.category_id = c.id
+             WHERE t.account_id = ?1 AND t.date >= ?2 AND t.date <= ?3
+             AND t.type = 'Expense' AND c.is_reimbursement = 1
+             AND t.id NOT IN (SELECT expense_transaction_id FROM reimbursement_links)
+             ORDER BY t.date ASC, t.id ASC"
+        ).map_err(|e| e.to_string())?;
+        stmt.query_map(params![accountId, startDate, endDate], |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?)))
+            .map_err(|e| e.to_string())?
+            .collect::<Result<_, _>>()
+            .map_err(|e| e.to_string())?
+    };
+
+    let mut candidates: Vec<(i64, String, f64)> = {
+        let mut stmt = conn.prepare(
+            "SELECT t.id, t.date, t.amount
+             FROM transactions t
+             WHERE t.account_id = ?1 AND t.type = 'Income'
+             AND t.id NOT IN (SELECT credit_transaction_id FROM reimbursement_links)
+             ORDER BY t.date ASC, t.id ASC"
+        ).map_err(|e| e.to_string())?;
+        stmt.query_map(params![accountId], |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?)))
+            .map_err(|e| e.to_string())?
+            .collect::<Result<_, _>>()
+            .map_err(|e| e.to_string())?
+    };
+
+    let mut links = Vec::new();
+    for (expense_id, expense_date, expense_amount) in expenses {
+        let target = expense_amount.abs();
+        let nearest = candidates
+            .iter()
+            .enumerate()
+            .filter(|(_, (_, credit_date, credit_amount))| {
+                credit_date >= &expense_date && (credit_amount.abs() - target).abs() <= REIMBURSEMENT_AMOUNT_EPSILON
+            })
+            .min_by(|(_, (_, a_date, _)), (_, (_, b_date, _))| a_date.cmp(b_date))
+            .map(|(idx, _)| idx);
+
+        let Some(idx) = nearest else { continue };
+        let (credit_id, _, _) = candidates.remove(idx);
+
+        conn.execute(
+            "INSERT INTO reimbursement_links (expense_transaction_id, credit_transaction_id, matched_amount) VALUES (?1, ?2, ?3)",
+            params![expense_id, credit_id, target],
+        ).map_err(|e| e.to_string())?;
+
+        links.push(ReimbursementLink {
+            id: conn.last_insert_rowid(),
+            expense_transaction_id: expense_id,
+            credit_transaction_id: credit_id,
+            matched_amount: target,
+        });
+    }
+
+    Ok(links)
+}
@@ -0,0 +1,126 @@
+use std::fs;
+use std::path::{Component, Path, PathBuf};
+
+/// A named destination backups can be written to. `LocalDiskBackend` covers both a plain local
+/// folder and a locally-mounted OneDrive folder (OneDrive just happens to be a directory on
+/// disk from this app's point of view); `OneDriveBackend` is that same local-folder storage under
+/// its own name (the Graph API upload in `cloud.rs` is not behind this trait). Adding Google Drive or
+/// Dropbox later is a matter of implementing this trait once rather than threading a new
+/// branch through every backup command.
+#[async_trait::async_trait]
+pub trait StorageBackend: Send + Sync {
+    /// Reads `name` back, or `None` if it doesn't exist.
+    async fn get(&self, name: &str) -> Result<Option<Vec<u8>>, String>;
+    /// Writes `data` to `name`, creating or overwriting it.
+    async fn put(&self, name: &str, data: &[u8]) -> Result<(), String>;
+    /// Creates `path` (and any missing parents) if it doesn't already exist.
+    async fn create_dir(&self, path: &str) -> Result<(), String>;
+    /// Lists entry names directly under the backend's root.
+    async fn list(&self) -> Result<Vec<String>, String>;
+}
+
+/// Rejects any `name` containing a `..`, root or prefix component, so a caller-supplied filename can't escape
+/// `base_path` the way a raw `base_path.join(name)` would allow.
+fn safe_join(base_path: &Path, name: &str) -> Result<PathBuf, String> {
+    let candidate = Path::new(name);
+    if candidate.components().any(|c| matches!(c, Component::ParentDir | Component::RootDir | Component::Prefix(_))) {
+        return Err(format!("Refusing to resolve path outside backend root: {}", name));
+    }
+    Ok(base_path.join(candidate))
+}
+
+/// Stores everything under a single directory on the local filesystem. Blocking `std::fs` calls
+/// are offloaded to `tokio::task::spawn_blocking` so the Tauri command layer (which runs these
+/// on the async runtime) stays responsive even for a large backup file.
+pub struct LocalDiskBackend {
+    base_path: PathBuf,
+}
+
+impl LocalDiskBackend {
+    pub fn new(base_path: impl Into<PathBuf>) -> Self {
+        Self { base_path: base_path.into() }
+    }
+}
+
+#[async_trait::async_trait]
+impl StorageBackend for LocalDiskBackend {
+    async fn get(&self, name: &str) -> Result<Option<Vec<u8>>, String> {
+        let path = safe_join(&self.base_path, name)?;
+        tokio::task::spawn_blocking(move || match fs::read(&path) {
+            Ok(data) => Ok(Some(data)),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(None),
+            Err(e) => Err(e.to_string()),
+        })
+        .await
+        .map_err(|e| e.to_string())?
+    }
+
+    async fn put(&self, name: &str, data: &[u8]) -> Result<(), String> {
+        let path = safe_join(&self.base_path, name)?;
+        let data = data.to_vec();
+        tokio::task::spawn_blocking(move || {
+            if let Some(parent) = path.parent() {
+                fs::create_dir_all(parent).map_err(|e| e.to_string())?;
+            }
+            fs::write(&path, data).map_err(|e| e.to_string())
+        })
+        .await
+        .map_err(|e| e.to_string())?
+    }
+
+    async fn create_dir(&self, path: &str) -> Result<(), String> {
+        let target = safe_join(&self.base_path, path)?;
+        tokio::task::spawn_blocking(move || fs::create_dir_all(target).map_err(|e| e.to_string()))
+            .await
+            .map_err(|e| e.to_string())?
+    }
+
+    async fn list(&self) -> Result<Vec<String>, String> {
+        let base_path = self.base_path.clone();
+        tokio::task::spawn_blocking(move || {
+            let entries = fs::read_dir(&base_path).map_err(|e| e.to_string())?;
+            let mut names = Vec::new();
+            for entry in entries {
+                let entry = entry.map_err(|e| e.to_string())?;
+                if let Some(name) = entry.file_name().to_str() {
+                    names.push(name.to_string());
+                }
+            }
+            Ok(names)
+        })
+        .await
+        .map_err(|e| e.to_string())?
+    }
+}
+
+/// Backs onto a locally-mounted OneDrive folder — functionally identical to
+/// [`LocalDiskBackend`], kept as its own type so callers can select "OneDrive" from settings
+/// without caring that it happens to be implemented as a local path today.
+pub struct OneDriveBackend {
+    inner: LocalDiskBackend,
+}
+
+impl OneDriveBackend {
+    pub fn new(onedrive_root: impl Into<PathBuf>) -> Self {
+        Self { inner: LocalDiskBackend::new(onedrive_root) }
+    }
+}
+
+#[async_trait::async_trait]
+impl StorageBackend for OneDriveBackend {
+    async fn get(&self, name: &str) -> Result<Option<Vec<u8>>, String> {
+        self.inner.get(name).await
+    }
+
+    async fn put(&self, name: &str, data: &[u8]) -> Result<(), String> {
+        self.inner.put(name, data).await
+    }
+
+    async fn create_dir(&self, path: &str) -> Result<(), String> {
+        self.inner.create_dir(path).await
+    }
+
+    async fn list(&self) -> Result<Vec<String>, String> {
+        self.inner.list().await
+    }
+}
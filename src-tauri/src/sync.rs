@@ -1,14 +1,341 @@
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 use std::fs;
 use std::sync::Arc;
 use std::time::{Duration, SystemTime, UNIX_EPOCH};
+use rand::Rng;
 use rusqlite::Connection;
-use tauri::{AppHandle, Manager};
+use tauri::{AppHandle, Emitter, Manager};
 use serde::{Deserialize, Serialize};
 use tokio::sync::Mutex;
 use tokio::time::interval;
 
-use crate::utils::{get_db_path, get_onedrive_data_dir, get_onedrive_path};
+use crate::utils::{get_attachments_dir, get_db_path, get_onedrive_data_dir, get_onedrive_path};
+
+const GRAPH_BASE: &str = "https://graph.microsoft.com/v1.0";
+/// Graph's simple `PUT .../content` upload tops out around 4MB; [`GraphSyncBackend`] switches to
+/// a resumable upload session above this size.
+const GRAPH_SIMPLE_UPLOAD_LIMIT: u64 = 4 * 1024 * 1024;
+
+/// How many bytes [`quickxorhash_file`] reads into memory at a time, so hashing a large database
+/// doesn't require buffering the whole file.
+const HASH_CHUNK_SIZE: usize = 256 * 1024;
+
+/// Microsoft's QuickXorHash — the same 160-bit hash OneDrive itself reports for a file, so a
+/// locally-computed digest can later be cross-checked against what Graph returns. Used instead of
+/// (or alongside) the filesystem mtime comparisons elsewhere in this file, which the Windows
+/// branches below already concede are unreliable: an identical file hashes identically regardless
+/// of what OneDrive did to its timestamp, and a corrupted transfer hashes differently.
+struct QuickXorHash {
+    cells: [u64; 3],
+    bit_offset: usize,
+    length: u64,
+}
+
+impl QuickXorHash {
+    const CELL_BITS: usize = 64;
+    const WIDTH_BITS: usize = 160;
+    const SHIFT_BITS: usize = 11;
+
+    fn new() -> Self {
+        Self { cells: [0; 3], bit_offset: 0, length: 0 }
+    }
+
+    fn update(&mut self, data: &[u8]) {
+        for &byte in data {
+            let cell = self.bit_offset / Self::CELL_BITS;
+            let offset_in_cell = self.bit_offset % Self::CELL_BITS;
+            // The last cell holds only the 32 bits left of the 160-bit width.
+            let bits_in_cell = if cell == self.cells.len() - 1 { Self::WIDTH_BITS - 2 * Self::CELL_BITS } else { Self::CELL_BITS };
+            self.cells[cell] ^= (byte as u64) << offset_in_cell;
+            if offset_in_cell > bits_in_cell - 8 {
+                let next_cell = (cell + 1) % self.cells.len();
+                self.cells[next_cell] ^= (byte as u64) >> (bits_in_cell - offset_in_cell);
+            }
+            self.bit_offset = (self.bit_offset + Self::SHIFT_BITS) % Self::WIDTH_BITS;
+            self.length += 1;
+        }
+    }
+
+    /// Serializes the three cells little-endian into the 20-byte digest (the last cell only
+    /// contributes its low 32 bits), then XORs the little-endian total length into the final
+    /// 8 bytes as QuickXorHash's spec requires.
+    fn finalize(self) -> [u8; 20] {
+        let mut digest = [0u8; 20];
+        digest[0..8].copy_from_slice(&self.cells[0].to_le_bytes());
+        digest[8..16].copy_from_slice(&self.cells[1].to_le_bytes());
+        digest[16..20].copy_from_slice(&(self.cells[2] as u32).to_le_bytes());
+        for (i, byte) in self.length.to_le_bytes().iter().enumerate() {
+            digest[12 + i] ^= byte;
+        }
+        digest
+    }
+}
+
+/// Hashes `path` with [`QuickXorHash`] in fixed-size chunks so large databases never need to be
+/// fully buffered, returning the base64-encoded digest used as `SyncMetadata.hash`.
+fn quickxorhash_file(path: &Path) -> Result<String, String> {
+    use std::io::Read;
+    use base64::{engine::general_purpose::STANDARD, Engine};
+
+    let mut file = fs::File::open(path).map_err(|e| format!("Failed to open {} for hashing: {}", path.display(), e))?;
+    let mut hasher = QuickXorHash::new();
+    let mut buf = vec![0u8; HASH_CHUNK_SIZE];
+    loop {
+        let read = file.read(&mut buf).map_err(|e| format!("Failed to read {} while hashing: {}", path.display(), e))?;
+        if read == 0 {
+            break;
+        }
+        hasher.update(&buf[..read]);
+    }
+    Ok(STANDARD.encode(hasher.finalize()))
+}
+
+/// Matches `file_name` against a glob `pattern` containing at most one leading or trailing `*`
+/// (enough for the temp/lock-file patterns `SyncConfig.skip_file` is meant for, e.g. `~*`, `*.tmp`).
+fn matches_glob(file_name: &str, pattern: &str) -> bool {
+    if let Some(suffix) = pattern.strip_prefix('*') {
+        file_name.ends_with(suffix)
+    } else if let Some(prefix) = pattern.strip_suffix('*') {
+        file_name.starts_with(prefix)
+    } else {
+        file_name == pattern
+    }
+}
+
+/// Whether `file_name` matches any of `skip_patterns`, so `sync_attachments_dir` can leave temp
+/// and lock files (`~*`, `.~*`, `*.tmp`, `.DS_Store`, ...) out of the attachments mirror.
+fn is_skipped_file(file_name: &str, skip_patterns: &[String]) -> bool {
+    skip_patterns.iter().any(|pattern| matches_glob(file_name, pattern))
+}
+
+/// Path to the local-only record of the remote [`SyncMetadata`] this machine last completed a
+/// sync against. Neither side's own metadata can tell divergence ("both changed independently")
+/// apart from one side simply being ahead - that requires remembering what was last agreed on,
+/// which is what [`has_diverged`] compares against.
+fn last_synced_metadata_path() -> PathBuf {
+    let dir = dirs::data_dir().expect("Failed to get data dir").join("WalnutBook");
+    fs::create_dir_all(&dir).expect("Failed to create app data dir");
+    dir.join("last_synced_metadata.json")
+}
+
+fn load_last_synced_metadata() -> Option<SyncMetadata> {
+    let json = fs::read_to_string(last_synced_metadata_path()).ok()?;
+    serde_json::from_str(&json).ok()
+}
+
+fn save_last_synced_metadata(meta: &SyncMetadata) -> Result<(), String> {
+    let json = serde_json::to_string_pretty(meta).map_err(|e| e.to_string())?;
+    fs::write(last_synced_metadata_path(), json).map_err(|e| e.to_string())
+}
+
+/// Path to the local copy of the database this machine completed its last successful sync
+/// against - the "base" [`merge_diverged`] three-way-compares `local`/`remote` to when
+/// [`has_diverged`] finds both sides changed independently. Kept alongside, and always
+/// refreshed together with, [`last_synced_metadata_path`].
+fn last_synced_db_path() -> PathBuf {
+    let dir = dirs::data_dir().expect("Failed to get data dir").join("WalnutBook");
+    fs::create_dir_all(&dir).expect("Failed to create app data dir");
+    dir.join("last_synced_base.db")
+}
+
+fn save_base_snapshot(db_path: &Path) -> Result<(), String> {
+    fs::copy(db_path, last_synced_db_path())
+        .map(|_| ())
+        .map_err(|e| format!("Failed to save sync merge base snapshot: {}", e))
+}
+
+/// Resolves a detected divergence by merging `remote_snapshot` onto `db_path` at SQLite-row
+/// granularity (see [`crate::merge`]) against the last-synced base snapshot, instead of letting
+/// one whole-file copy silently clobber the other's edits. Leaves `db_path` holding the merged
+/// result and refreshes the base snapshot and `last_synced_metadata` to match it. Returns how
+/// many rows had to be arbitrated by newest `updated_at` - `0` means the divergence was fully
+/// resolved without discarding anything, except on the one-time fallback when no base snapshot
+/// exists yet, which adopts the remote copy wholesale and also returns `0`.
+fn merge_diverged(db_path: &Path, remote_snapshot: &Path, generation: u64, parent_hash: Option<String>) -> Result<usize, String> {
+    let base_path = last_synced_db_path();
+    if !base_path.exists() {
+        // Upgrading from a version that recorded `last_synced_metadata.json` but never kept a
+        // base snapshot to merge against - nothing to three-way-compare, so fall back to
+        // adopting the remote copy wholesale. A proper base snapshot gets saved below so every
+        // divergence after this one merges for real.
+        eprintln!("No sync merge base snapshot yet - adopting remote database wholesale this one time");
+        fs::copy(remote_snapshot, db_path).map_err(|e| format!("Failed to adopt remote database: {}", e))?;
+        save_base_snapshot(db_path)?;
+        let metadata = SyncMetadata {
+            last_modified: SystemTime::now().duration_since(UNIX_EPOCH).unwrap().as_secs(),
+            file_size: fs::metadata(db_path).map_err(|e| e.to_string())?.len(),
+            version: "1.0".to_string(),
+            hash: Some(quickxorhash_file(db_path)?),
+            schema_version: crate::migrations::MIGRATIONS.len() as i64,
+            generation,
+            parent_hash,
+        };
+        save_last_synced_metadata(&metadata)?;
+        return Ok(0);
+    }
+
+    let backup_path = format!(
+        "{}.premerge_{}",
+        db_path.to_string_lossy(),
+        SystemTime::now().duration_since(UNIX_EPOCH).unwrap().as_secs()
+    );
+    fs::copy(db_path, &backup_path).map_err(|e| format!("Failed to back up local database before merge: {}", e))?;
+
+    match crate::merge::merge_databases(&base_path, db_path, remote_snapshot) {
+        Ok(conflicts) => {
+            fs::remove_file(&backup_path).map_err(|e| format!("Failed to remove pre-merge backup: {}", e))?;
+            let metadata = SyncMetadata {
+                last_modified: SystemTime::now().duration_since(UNIX_EPOCH).unwrap().as_secs(),
+                file_size: fs::metadata(db_path).map_err(|e| e.to_string())?.len(),
+                version: "1.0".to_string(),
+                hash: Some(quickxorhash_file(db_path)?),
+                schema_version: crate::migrations::MIGRATIONS.len() as i64,
+                generation,
+                parent_hash,
+            };
+            save_base_snapshot(db_path)?;
+            save_last_synced_metadata(&metadata)?;
+            Ok(conflicts)
+        }
+        Err(e) => {
+            let _ = fs::copy(&backup_path, db_path);
+            Err(format!("Failed to merge diverged databases: {}", e))
+        }
+    }
+}
+
+/// The `"CONFLICT:"`-prefixed error [`merge_diverged`]'s callers report when it had to
+/// arbitrate at least one row, pointing at the `sync_conflicts` table instead of (as before a
+/// real merge existed) a whole preserved copy of the losing file.
+fn merge_conflict_message(conflicts: usize) -> String {
+    format!(
+        "CONFLICT: local and remote databases diverged, {} row(s) arbitrated by newest edit across accounts/categories/budgets/transactions - see the sync_conflicts table",
+        conflicts
+    )
+}
+
+/// Whether `remote` and the local database have genuinely diverged - both sides changed
+/// independently since the last sync this machine completed - rather than one side simply being
+/// ahead of the other. Never true before this machine has completed its first sync, since there's
+/// nothing yet to diverge from.
+fn has_diverged(remote: &SyncMetadata, local_hash: &str) -> bool {
+    let Some(last_synced) = load_last_synced_metadata() else { return false };
+    let Some(last_hash) = last_synced.hash.as_deref() else { return false };
+
+    let remote_moved_on = remote.hash.as_deref() != Some(last_hash);
+    let local_moved_on = local_hash != last_hash;
+    let contents_agree = remote.hash.as_deref() == Some(local_hash);
+
+    remote_moved_on && local_moved_on && !contents_agree
+}
+
+/// Tables [`classify_as_big_delete`] sums row counts across when deciding whether a pull looks
+/// like a catastrophic overwrite rather than a legitimate sync.
+const BIG_DELETE_TABLES: &[&str] = &["accounts", "budgets", "transactions"];
+
+/// Whether adopting `remote_db` in place of `local_db` would remove more rows than
+/// `SyncConfig::big_delete_row_threshold`/`big_delete_fraction` allow, summed across
+/// [`BIG_DELETE_TABLES`] - the same "would this sync erase more than it should" guard the
+/// reference OneDrive client calls `classify_as_big_delete`. Returns the number of rows that
+/// would be removed when the guard trips, or `None` when the pull is safe.
+fn classify_as_big_delete(remote_db: &Path, local_db: &Path, threshold: u32, fraction: f64) -> Result<Option<u32>, String> {
+    let remote_conn = Connection::open(remote_db).map_err(|e| format!("Failed to open remote database for big-delete check: {}", e))?;
+    let local_conn = Connection::open(local_db).map_err(|e| format!("Failed to open local database for big-delete check: {}", e))?;
+
+    let mut local_total: u32 = 0;
+    let mut removed_total: u32 = 0;
+    for table in BIG_DELETE_TABLES {
+        let remote_count: i64 = remote_conn
+            .query_row(&format!("SELECT COUNT(*) FROM {}", table), [], |row| row.get(0))
+            .unwrap_or(0);
+        let local_count: i64 = local_conn
+            .query_row(&format!("SELECT COUNT(*) FROM {}", table), [], |row| row.get(0))
+            .unwrap_or(0);
+
+        local_total += local_count.max(0) as u32;
+        removed_total += (local_count - remote_count).max(0) as u32;
+    }
+
+    if local_total == 0 {
+        return Ok(None);
+    }
+
+    let removed_fraction = removed_total as f64 / local_total as f64;
+    if removed_total >= threshold || removed_fraction >= fraction {
+        Ok(Some(removed_total))
+    } else {
+        Ok(None)
+    }
+}
+
+/// Required tables a published OneDrive database must contain - the same set
+/// `load_from_onedrive_static` checks for on the way in, checked here on the way out so a
+/// half-written or corrupt temp file is caught before it's ever promoted to be the live
+/// `walnutbook_sync.db`.
+const REQUIRED_SYNC_TABLES: &[&str] = &["accounts", "transactions", "categories", "budgets"];
+
+/// How many attempts in all [`publish_to_sync_dir`] makes, retrying a transient publish failure - OneDrive
+/// frequently returns "file busy"/permission errors on Windows while it's mid-sync - before
+/// giving up.
+const PUBLISH_MAX_ATTEMPTS: u32 = 5;
+
+/// Base delay for [`publish_to_sync_dir`]'s retry backoff; doubles with each further attempt.
+const PUBLISH_RETRY_BASE_MS: u64 = 200;
+
+/// Stages `db_path` into a temp file alongside `sync_db_path`, verifies it opens, matches
+/// `expected_hash`, and contains [`REQUIRED_SYNC_TABLES`], then atomically `rename`s it over
+/// `sync_db_path`. Same-directory temp file keeps the rename on one filesystem, so it's truly
+/// atomic rather than a copy another reader could observe half-written.
+fn try_publish_once(db_path: &Path, sync_db_path: &Path, expected_hash: &str) -> Result<(), String> {
+    let tmp_path = sync_db_path.with_file_name(format!(
+        ".walnutbook_sync.tmp.{}",
+        SystemTime::now().duration_since(UNIX_EPOCH).unwrap().as_nanos()
+    ));
+
+    fs::copy(db_path, &tmp_path).map_err(|e| format!("Failed to stage database for publish: {}", e))?;
+
+    let verify_result: Result<(), String> = (|| {
+        let actual_hash = quickxorhash_file(&tmp_path)?;
+        if actual_hash != expected_hash {
+            return Err("Staged database failed hash verification before publish".to_string());
+        }
+        let conn = Connection::open(&tmp_path).map_err(|e| format!("Failed to open staged database: {}", e))?;
+        for table in REQUIRED_SYNC_TABLES {
+            conn.prepare(&format!("SELECT 1 FROM {} LIMIT 1", table))
+                .map_err(|_| format!("Staged database is missing {} table", table))?;
+        }
+        Ok(())
+    })();
+
+    if let Err(e) = verify_result {
+        let _ = fs::remove_file(&tmp_path);
+        return Err(e);
+    }
+
+    if let Err(e) = fs::rename(&tmp_path, sync_db_path) {
+        let _ = fs::remove_file(&tmp_path);
+        return Err(format!("Failed to publish database to OneDrive: {}", e));
+    }
+
+    Ok(())
+}
+
+/// Retries [`try_publish_once`] with exponential backoff up to [`PUBLISH_MAX_ATTEMPTS`] times,
+/// surfacing a clear error only once every attempt has failed - transient "file busy" errors
+/// while OneDrive is mid-sync shouldn't fail an otherwise-healthy publish.
+fn publish_to_sync_dir(db_path: &Path, sync_db_path: &Path, expected_hash: &str) -> Result<(), String> {
+    let mut last_err = String::new();
+    for attempt in 0..PUBLISH_MAX_ATTEMPTS {
+        if attempt > 0 {
+            std::thread::sleep(Duration::from_millis(PUBLISH_RETRY_BASE_MS * 2u64.pow(attempt - 1)));
+        }
+        match try_publish_once(db_path, sync_db_path, expected_hash) {
+            Ok(()) => return Ok(()),
+            Err(e) => last_err = e,
+        }
+    }
+    Err(format!("Failed to publish database to OneDrive after {} attempts: {}", PUBLISH_MAX_ATTEMPTS, last_err))
+}
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub enum SyncError {
@@ -45,6 +372,17 @@ pub struct SyncStatus {
     pub onedrive_available: bool,
     pub retry_count: u32,
     pub last_error_time: Option<String>,
+    /// Unix timestamp (seconds, as a string like the other time fields here) before which
+    /// `start_auto_sync`'s loop skips attempts after a failure. `None` once a sync succeeds and
+    /// resets `retry_count` back to zero.
+    #[serde(default)]
+    pub next_retry_time: Option<String>,
+    /// Number of attachment files `sync_attachments` found to mirror on its most recent run.
+    #[serde(default)]
+    pub attachments_total: u32,
+    /// Number of those files it has copied or confirmed unchanged so far.
+    #[serde(default)]
+    pub attachments_synced: u32,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -53,6 +391,75 @@ pub struct SyncConfig {
     pub sync_interval_minutes: u64,
     pub onedrive_path: Option<String>,
     pub fallback_to_local: bool,
+    /// Which [`SyncBackend`] `perform_sync` uses: `"folder"` (default, the locally-mounted
+    /// OneDrive folder `try_onedrive_sync` has always copied into) or `"graph"` (direct
+    /// Microsoft Graph API, for machines with no OneDrive client installed).
+    #[serde(default = "default_sync_backend")]
+    pub backend: String,
+    /// Azure AD app registration client ID the `"graph"` backend's device-code sign-in
+    /// (`cloud::cloud_start_device_code`) and refresh-token renewal use. Required when
+    /// `backend == "graph"`.
+    #[serde(default)]
+    pub graph_client_id: Option<String>,
+    /// Whether `SyncManager::start_file_watcher` should watch the database file and sync shortly
+    /// after it changes, instead of waiting for `start_auto_sync`'s periodic timer. The timer
+    /// keeps running regardless, as a safety net for changes made on the remote side.
+    #[serde(default)]
+    pub watch_enabled: bool,
+    /// How long the database must sit unmodified before a watched change is synced, coalescing a
+    /// burst of writes (e.g. importing a CSV of transactions) into a single upload.
+    #[serde(default = "default_debounce_seconds")]
+    pub debounce_seconds: u64,
+    /// Backoff delay after the first consecutive auto-sync failure. Doubles with each further
+    /// failure (capped at `max_backoff_seconds`) instead of `start_auto_sync` hard-disabling
+    /// itself, so a transient OneDrive/network hiccup recovers on its own.
+    #[serde(default = "default_base_backoff_seconds")]
+    pub base_backoff_seconds: u64,
+    /// Ceiling the exponential backoff delay never grows past; `start_auto_sync` adds up to 20%
+    /// random jitter on top of the capped delay.
+    #[serde(default = "default_max_backoff_seconds")]
+    pub max_backoff_seconds: u64,
+    /// Glob patterns (single leading/trailing `*`) for attachment file names `sync_attachments`
+    /// never mirrors, e.g. editor/OS temp and lock files.
+    #[serde(default = "default_skip_file_patterns")]
+    pub skip_file: Vec<String>,
+    /// Minimum number of rows (summed across `accounts`/`budgets`/`transactions`) a pull from
+    /// OneDrive would have to remove before `load_from_onedrive_static` refuses it as a likely
+    /// catastrophic overwrite. Either this or `big_delete_fraction` tripping is enough to block.
+    #[serde(default = "default_big_delete_row_threshold")]
+    pub big_delete_row_threshold: u32,
+    /// Fraction of the local row count a pull would have to remove before it's refused, e.g.
+    /// `0.25` blocks a pull that would erase a quarter or more of the local database.
+    #[serde(default = "default_big_delete_fraction")]
+    pub big_delete_fraction: f64,
+}
+
+fn default_sync_backend() -> String {
+    "folder".to_string()
+}
+
+fn default_debounce_seconds() -> u64 {
+    8
+}
+
+fn default_base_backoff_seconds() -> u64 {
+    30
+}
+
+fn default_max_backoff_seconds() -> u64 {
+    30 * 60
+}
+
+fn default_skip_file_patterns() -> Vec<String> {
+    vec!["~*".to_string(), ".~*".to_string(), "*.tmp".to_string(), ".DS_Store".to_string()]
+}
+
+fn default_big_delete_row_threshold() -> u32 {
+    50
+}
+
+fn default_big_delete_fraction() -> f64 {
+    0.25
 }
 
 impl Default for SyncConfig {
@@ -62,6 +469,280 @@ impl Default for SyncConfig {
             sync_interval_minutes: 5,
             onedrive_path: None,
             fallback_to_local: true,
+            backend: default_sync_backend(),
+            graph_client_id: None,
+            watch_enabled: false,
+            debounce_seconds: default_debounce_seconds(),
+            base_backoff_seconds: default_base_backoff_seconds(),
+            max_backoff_seconds: default_max_backoff_seconds(),
+            skip_file: default_skip_file_patterns(),
+            big_delete_row_threshold: default_big_delete_row_threshold(),
+            big_delete_fraction: default_big_delete_fraction(),
+        }
+    }
+}
+
+/// Transport-agnostic backend for `SyncManager`'s single-file database sync, selected by
+/// `SyncConfig.backend`. [`FolderSyncBackend`] wraps the existing locally-mounted OneDrive
+/// folder approach; [`GraphSyncBackend`] talks to Microsoft Graph directly. `try_onedrive_sync`
+/// and `load_from_onedrive_static` go through this trait only for the `"graph"` backend; the
+/// `"folder"` backend keeps their own direct file-copy code (atomic publish, big-delete guard),
+/// which the `"graph"` path does not have.
+#[async_trait::async_trait]
+pub trait SyncBackend: Send + Sync {
+    /// Uploads the database at `db` as the new remote copy, stamped with `meta`.
+    async fn upload(&self, db: &Path, meta: &SyncMetadata) -> Result<(), String>;
+    /// Downloads the remote copy to `dest`, returning the metadata that shipped with it.
+    async fn download(&self, dest: &Path) -> Result<SyncMetadata, String>;
+    /// The remote copy's metadata without downloading the database itself, or `None` if nothing
+    /// has been synced to this backend yet.
+    async fn remote_metadata(&self) -> Result<Option<SyncMetadata>, String>;
+}
+
+/// The original sync transport: a locally-mounted OneDrive (or any other synced) folder, written
+/// to with plain file copies that the OneDrive client itself then uploads.
+pub struct FolderSyncBackend {
+    sync_dir: PathBuf,
+}
+
+impl FolderSyncBackend {
+    pub fn new(sync_dir: PathBuf) -> Self {
+        Self { sync_dir }
+    }
+
+    fn db_path(&self) -> PathBuf {
+        self.sync_dir.join("walnutbook_sync.db")
+    }
+
+    fn metadata_path(&self) -> PathBuf {
+        self.sync_dir.join("sync_metadata.json")
+    }
+}
+
+#[async_trait::async_trait]
+impl SyncBackend for FolderSyncBackend {
+    async fn upload(&self, db: &Path, meta: &SyncMetadata) -> Result<(), String> {
+        fs::create_dir_all(&self.sync_dir).map_err(|e| format!("Failed to create sync directory: {}", e))?;
+        fs::copy(db, self.db_path()).map_err(|e| format!("Failed to copy database to sync folder: {}", e))?;
+        let metadata_json = serde_json::to_string_pretty(meta).map_err(|e| format!("Failed to serialize metadata: {}", e))?;
+        fs::write(self.metadata_path(), metadata_json).map_err(|e| format!("Failed to write metadata: {}", e))
+    }
+
+    async fn download(&self, dest: &Path) -> Result<SyncMetadata, String> {
+        let meta = self.remote_metadata().await?.ok_or("No sync data found in sync folder")?;
+        fs::copy(self.db_path(), dest).map_err(|e| format!("Failed to copy database from sync folder: {}", e))?;
+        Ok(meta)
+    }
+
+    async fn remote_metadata(&self) -> Result<Option<SyncMetadata>, String> {
+        if !self.metadata_path().exists() {
+            return Ok(None);
+        }
+        let metadata_json = fs::read_to_string(self.metadata_path()).map_err(|e| format!("Failed to read metadata: {}", e))?;
+        serde_json::from_str(&metadata_json).map(Some).map_err(|e| format!("Failed to parse metadata: {}", e))
+    }
+}
+
+/// Refresh token persisted next to `sync_config.json`, so a `GraphSyncBackend` signed in once
+/// via `cloud::cloud_start_device_code` keeps syncing across restarts without the user
+/// re-authorizing in a browser each time.
+#[derive(Debug, Serialize, Deserialize)]
+struct GraphTokenFile {
+    refresh_token: String,
+}
+
+/// Direct-to-Microsoft-Graph sync transport, for machines with no locally-mounted OneDrive
+/// folder (Linux, headless servers). Stores the database and its metadata under the app's
+/// special `approot` folder, Graph's name for the app-scoped storage area `Files.ReadWrite`
+/// already grants access to without needing a picker.
+pub struct GraphSyncBackend {
+    client_id: String,
+    token_path: PathBuf,
+}
+
+impl GraphSyncBackend {
+    pub fn new(client_id: String, token_path: PathBuf) -> Self {
+        Self { client_id, token_path }
+    }
+
+    fn load_refresh_token(&self) -> Result<String, String> {
+        let json = fs::read_to_string(&self.token_path)
+            .map_err(|_| "Graph sign-in required: no refresh token on file".to_string())?;
+        let file: GraphTokenFile = serde_json::from_str(&json).map_err(|e| e.to_string())?;
+        Ok(file.refresh_token)
+    }
+
+    fn save_refresh_token(&self, refresh_token: &str) -> Result<(), String> {
+        if let Some(parent) = self.token_path.parent() {
+            fs::create_dir_all(parent).map_err(|e| e.to_string())?;
+        }
+        let json = serde_json::to_string_pretty(&GraphTokenFile { refresh_token: refresh_token.to_string() })
+            .map_err(|e| e.to_string())?;
+        fs::write(&self.token_path, json).map_err(|e| e.to_string())
+    }
+
+    /// Exchanges the stored refresh token for a fresh access token, persisting Graph's rotated
+    /// refresh token (if it issued a new one) so the next call keeps working.
+    async fn access_token(&self) -> Result<String, String> {
+        let refresh_token = self.load_refresh_token()?;
+        let token = crate::cloud::cloud_refresh_token(self.client_id.clone(), refresh_token).await?;
+        if let Some(new_refresh_token) = &token.refresh_token {
+            self.save_refresh_token(new_refresh_token)?;
+        }
+        Ok(token.access_token)
+    }
+
+    /// Streams `bytes` to `createUploadSession`'s one-time `uploadUrl` in fixed-size chunks with
+    /// `Content-Range`, as Graph requires for anything past [`GRAPH_SIMPLE_UPLOAD_LIMIT`].
+    async fn upload_large(&self, client: &reqwest::Client, access_token: &str, remote_name: &str, bytes: &[u8]) -> Result<(), String> {
+        const CHUNK_SIZE: usize = 5 * 1024 * 1024;
+
+        let session_url = format!("{}/me/drive/special/approot:/{}:/createUploadSession", GRAPH_BASE, remote_name);
+        let resp = client
+            .post(&session_url)
+            .bearer_auth(access_token)
+            .json(&serde_json::json!({ "item": { "@microsoft.graph.conflictBehavior": "replace" } }))
+            .send()
+            .await
+            .map_err(|e| e.to_string())?;
+        if !resp.status().is_success() {
+            return Err(format!("Failed to create Graph upload session: HTTP {}", resp.status()));
+        }
+        let session: serde_json::Value = resp.json().await.map_err(|e| e.to_string())?;
+        let upload_url = session["uploadUrl"]
+            .as_str()
+            .ok_or("Graph upload session response missing uploadUrl")?
+            .to_string();
+
+        let total = bytes.len();
+        let mut offset = 0usize;
+        while offset < total {
+            let end = (offset + CHUNK_SIZE).min(total);
+            let chunk = &bytes[offset..end];
+            let resp = client
+                .put(&upload_url)
+                .header("Content-Length", chunk.len().to_string())
+                .header("Content-Range", format!("bytes {}-{}/{}", offset, end - 1, total))
+                .body(chunk.to_vec())
+                .send()
+                .await
+                .map_err(|e| e.to_string())?;
+            if !resp.status().is_success() && resp.status() != reqwest::StatusCode::ACCEPTED {
+                return Err(format!("Failed to upload chunk {}-{}: HTTP {}", offset, end, resp.status()));
+            }
+            offset = end;
+        }
+        Ok(())
+    }
+}
+
+#[async_trait::async_trait]
+impl SyncBackend for GraphSyncBackend {
+    async fn upload(&self, db: &Path, meta: &SyncMetadata) -> Result<(), String> {
+        let access_token = self.access_token().await?;
+        let bytes = fs::read(db).map_err(|e| format!("Failed to read database: {}", e))?;
+        let client = reqwest::Client::new();
+
+        if (bytes.len() as u64) <= GRAPH_SIMPLE_UPLOAD_LIMIT {
+            let url = format!("{}/me/drive/special/approot:/walnutbook_sync.db:/content", GRAPH_BASE);
+            let resp = client.put(&url).bearer_auth(&access_token).body(bytes).send().await.map_err(|e| e.to_string())?;
+            if !resp.status().is_success() {
+                return Err(format!("Failed to upload database to Graph: HTTP {}", resp.status()));
+            }
+        } else {
+            self.upload_large(&client, &access_token, "walnutbook_sync.db", &bytes).await?;
+        }
+
+        let metadata_json = serde_json::to_vec(meta).map_err(|e| e.to_string())?;
+        let metadata_url = format!("{}/me/drive/special/approot:/sync_metadata.json:/content", GRAPH_BASE);
+        let resp = client.put(&metadata_url).bearer_auth(&access_token).body(metadata_json).send().await.map_err(|e| e.to_string())?;
+        if !resp.status().is_success() {
+            return Err(format!("Failed to upload sync metadata to Graph: HTTP {}", resp.status()));
+        }
+        Ok(())
+    }
+
+    async fn download(&self, dest: &Path) -> Result<SyncMetadata, String> {
+        let meta = self.remote_metadata().await?.ok_or("No sync data found in Graph")?;
+
+        let access_token = self.access_token().await?;
+        let client = reqwest::Client::new();
+        let db_url = format!("{}/me/drive/special/approot:/walnutbook_sync.db:/content", GRAPH_BASE);
+        let resp = client.get(&db_url).bearer_auth(&access_token).send().await.map_err(|e| e.to_string())?;
+        if !resp.status().is_success() {
+            return Err(format!("Failed to download database from Graph: HTTP {}", resp.status()));
+        }
+        let bytes = resp.bytes().await.map_err(|e| e.to_string())?;
+        fs::write(dest, &bytes).map_err(|e| format!("Failed to write downloaded database: {}", e))?;
+        Ok(meta)
+    }
+
+    async fn remote_metadata(&self) -> Result<Option<SyncMetadata>, String> {
+        let access_token = self.access_token().await?;
+        let client = reqwest::Client::new();
+        let metadata_url = format!("{}/me/drive/special/approot:/sync_metadata.json:/content", GRAPH_BASE);
+        let resp = client.get(&metadata_url).bearer_auth(&access_token).send().await.map_err(|e| e.to_string())?;
+        if resp.status() == reqwest::StatusCode::NOT_FOUND {
+            return Ok(None);
+        }
+        if !resp.status().is_success() {
+            return Err(format!("Failed to fetch Graph sync metadata: HTTP {}", resp.status()));
+        }
+        Ok(Some(resp.json().await.map_err(|e| e.to_string())?))
+    }
+}
+
+/// Parses the leading integer out of `SyncMetadata.version` (`"{major}.0"`), defaulting to `1`
+/// for the original hard-coded `"1.0"` string so metadata written before this field carried real
+/// meaning isn't misread as some other major version.
+fn parse_major_version(version: &str) -> u32 {
+    version.split('.').next().and_then(|s| s.parse().ok()).unwrap_or(1)
+}
+
+/// Guards against adopting a remote database this app build can't safely reconcile. A different
+/// [`crate::migrations::SCHEMA_MAJOR_VERSION`] means a migration broke forward compatibility
+/// somewhere between the two builds, so it's rejected outright in either direction rather than
+/// risking a half-understood schema. Within the same major version, a remote `schema_version`
+/// higher than [`crate::migrations::MIGRATIONS`]'s length just means the remote was written by a
+/// newer minor release than this one supports yet; a lower one is fine; forward migrations run
+/// after the remote copy is adopted.
+fn check_schema_compatible(metadata: &SyncMetadata) -> Result<(), String> {
+    let remote_major = parse_major_version(&metadata.version);
+    if remote_major != crate::migrations::SCHEMA_MAJOR_VERSION {
+        return Err(format!(
+            "OneDrive copy is schema major version {} but this app supports major version {} - these are incompatible and won't be synced",
+            remote_major, crate::migrations::SCHEMA_MAJOR_VERSION
+        ));
+    }
+    if metadata.schema_version as usize > crate::migrations::MIGRATIONS.len() {
+        return Err(format!(
+            "OneDrive copy was saved by a newer app (schema v{}, this app supports up to v{}); update the app before syncing",
+            metadata.schema_version,
+            crate::migrations::MIGRATIONS.len()
+        ));
+    }
+    Ok(())
+}
+
+/// Builds the `SyncBackend` `config.backend` selects. `"graph"` needs `graph_client_id` and a
+/// prior device-code sign-in (`GraphSyncBackend::access_token` surfaces a clear error otherwise);
+/// anything else, including the unset default, falls back to the original OneDrive folder.
+fn make_backend(config: &SyncConfig) -> Result<Box<dyn SyncBackend>, String> {
+    match config.backend.as_str() {
+        "graph" => {
+            let client_id = config
+                .graph_client_id
+                .clone()
+                .ok_or_else(|| "Graph sync backend selected but graph_client_id is not configured".to_string())?;
+            let token_path = dirs::data_dir()
+                .ok_or_else(|| "Failed to get data dir".to_string())?
+                .join("WalnutBook")
+                .join("graph_token.json");
+            Ok(Box::new(GraphSyncBackend::new(client_id, token_path)))
+        }
+        _ => {
+            let onedrive_data_dir = get_onedrive_data_dir().map_err(|e| format!("OneDrive not available: {}", e))?;
+            Ok(Box::new(FolderSyncBackend::new(onedrive_data_dir.join("sync"))))
         }
     }
 }
@@ -71,6 +752,14 @@ pub struct SyncManager {
     config: Arc<Mutex<SyncConfig>>,
     status: Arc<Mutex<SyncStatus>>,
     sync_task: Option<tokio::task::JoinHandle<()>>,
+    /// Debounced file-watcher started by `start_file_watcher` when `SyncConfig.watch_enabled` is
+    /// set. Runs alongside `sync_task`, not instead of it - the periodic timer is still needed to
+    /// catch changes made on the remote side.
+    watch_task: Option<tokio::task::JoinHandle<()>>,
+    /// Debounced watcher started by `start_remote_watch`, over the OneDrive `sync/` directory
+    /// itself - the remote-side analogue of `watch_task`, for near-real-time pulls of another
+    /// device's changes instead of waiting on `sync_task`'s periodic timer.
+    remote_watch_task: Option<tokio::task::JoinHandle<()>>,
 }
 
 impl SyncManager {
@@ -87,8 +776,13 @@ impl SyncManager {
                 onedrive_available: false,
                 retry_count: 0,
                 last_error_time: None,
+                next_retry_time: None,
+                attachments_total: 0,
+                attachments_synced: 0,
             })),
             sync_task: None,
+            watch_task: None,
+            remote_watch_task: None,
         }
     }
 
@@ -129,6 +823,14 @@ impl SyncManager {
             self.start_auto_sync().await?;
         }
 
+        // Start the debounced file watcher if enabled, so local edits sync shortly after they
+        // happen instead of waiting for the periodic timer above.
+        if config.watch_enabled && onedrive_available {
+            if let Err(e) = self.start_file_watcher().await {
+                eprintln!("Failed to start database file watcher: {}", e);
+            }
+        }
+
         Ok(())
     }
 
@@ -155,6 +857,13 @@ impl SyncManager {
     }
 
     async fn load_config(&self) -> SyncConfig {
+        Self::load_config_static().await
+    }
+
+    /// Free-standing version of [`Self::load_config`] so call sites without a `SyncManager`
+    /// handy (e.g. the static `try_onedrive_sync`/`load_from_onedrive_static` helpers, which need
+    /// to know `SyncConfig.backend` before picking a [`SyncBackend`]) can read it too.
+    async fn load_config_static() -> SyncConfig {
         // Try to load from OneDrive first
         if let Ok(onedrive_data_dir) = get_onedrive_data_dir() {
             let config_file = onedrive_data_dir.join("sync_config.json");
@@ -166,7 +875,7 @@ impl SyncManager {
         }
 
         // Fallback to local config
-        let local_config_path = self.get_local_config_path();
+        let local_config_path = Self::local_config_path();
         if let Ok(config_data) = fs::read_to_string(&local_config_path) {
             if let Ok(config) = serde_json::from_str::<SyncConfig>(&config_data) {
                 return config;
@@ -190,14 +899,14 @@ impl SyncManager {
         }
 
         // Fallback to local storage
-        let local_config_path = self.get_local_config_path();
+        let local_config_path = Self::local_config_path();
         fs::write(&local_config_path, &config_json)
             .map_err(|e| format!("Failed to save config: {}", e))?;
 
         Ok(())
     }
 
-    fn get_local_config_path(&self) -> PathBuf {
+    fn local_config_path() -> PathBuf {
         let app_data_dir = dirs::data_dir().expect("Failed to get data dir").join("WalnutBook");
         fs::create_dir_all(&app_data_dir).expect("Failed to create app data dir");
         app_data_dir.join("sync_config.json")
@@ -220,19 +929,27 @@ impl SyncManager {
                 
                 let config_guard = config.lock().await;
                 let sync_interval = Duration::from_secs(config_guard.sync_interval_minutes * 60);
+                let base_backoff = config_guard.base_backoff_seconds;
+                let max_backoff = config_guard.max_backoff_seconds;
                 drop(config_guard);
 
-                // Check if we should sync
+                // Check if we should sync: due by the regular interval, and not still cooling
+                // down after a prior failure.
                 let should_sync = {
                     let status_guard = status.lock().await;
-                    let last_sync = status_guard.last_sync.as_ref()
-                        .and_then(|s| SystemTime::UNIX_EPOCH.checked_add(Duration::from_secs(s.parse().unwrap_or(0))));
-                    
                     let now = SystemTime::now();
-                    match last_sync {
-                        Some(last) => now.duration_since(last).unwrap_or_default() >= sync_interval,
-                        None => true, // Never synced
-                    }
+
+                    let interval_due = status_guard.last_sync.as_ref()
+                        .and_then(|s| SystemTime::UNIX_EPOCH.checked_add(Duration::from_secs(s.parse().unwrap_or(0))))
+                        .map_or(true, |last| now.duration_since(last).unwrap_or_default() >= sync_interval);
+
+                    let cooldown_elapsed = status_guard.next_retry_time.as_ref()
+                        .and_then(|s| s.parse::<u64>().ok())
+                        .map_or(true, |retry_secs| {
+                            now.duration_since(UNIX_EPOCH).unwrap().as_secs() >= retry_secs
+                        });
+
+                    interval_due && cooldown_elapsed
                 };
 
                 if should_sync {
@@ -250,21 +967,37 @@ impl SyncManager {
                                 status_guard.error_type = None;
                                 status_guard.retry_count = 0;
                                 status_guard.last_error_time = None;
+                                status_guard.next_retry_time = None;
+                                status_guard.sync_in_progress = false;
+                            }
+                            Err(e) if e.starts_with("CONFLICT:") => {
+                                // Divergence was already merged (arbitrated rows are in
+                                // sync_conflicts) by the sync itself - this needs the user to look at it, not an
+                                // automatic backoff retry.
+                                let mut status_guard = status.lock().await;
+                                status_guard.error_message = Some(e);
+                                status_guard.error_type = Some("conflict".to_string());
+                                status_guard.last_error_time = Some(SystemTime::now().duration_since(UNIX_EPOCH).unwrap().as_secs().to_string());
                                 status_guard.sync_in_progress = false;
                             }
                             Err(e) => {
                                 let mut status_guard = status.lock().await;
-                                status_guard.error_message = Some(e.clone());
-                                status_guard.error_type = Some("sync_failed".to_string());
                                 status_guard.retry_count += 1;
+
+                                // Exponential backoff with jitter: base * 2^(retries-1), capped,
+                                // plus up to 20% random jitter so many clients failing at once
+                                // don't all retry in lockstep.
+                                let exponent = status_guard.retry_count.saturating_sub(1).min(20);
+                                let backoff_secs = base_backoff.saturating_mul(1u64 << exponent).min(max_backoff);
+                                let jitter_secs = rand::thread_rng().gen_range(0..=(backoff_secs / 5).max(1));
+                                let delay_secs = backoff_secs + jitter_secs;
+                                let retry_at = SystemTime::now().duration_since(UNIX_EPOCH).unwrap().as_secs() + delay_secs;
+
+                                status_guard.error_message = Some(format!("{} (retrying in {}s)", e, delay_secs));
+                                status_guard.error_type = Some("sync_failed".to_string());
                                 status_guard.last_error_time = Some(SystemTime::now().duration_since(UNIX_EPOCH).unwrap().as_secs().to_string());
+                                status_guard.next_retry_time = Some(retry_at.to_string());
                                 status_guard.sync_in_progress = false;
-                                
-                                // If retry count is too high, disable auto sync temporarily
-                                if status_guard.retry_count >= 5 {
-                                    status_guard.is_enabled = false;
-                                    status_guard.error_message = Some("Auto sync disabled due to repeated failures".to_string());
-                                }
                             }
                         }
                     }
@@ -282,6 +1015,183 @@ impl SyncManager {
         }
     }
 
+    /// Watches `get_db_path`'s directory for writes to the database file and, after
+    /// `debounce_seconds` of quiescence, uploads it - mirroring the event-driven `monitor` loop
+    /// the OneDrive client itself uses instead of waiting on `start_auto_sync`'s periodic timer.
+    /// That timer keeps running unchanged, as a safety net for changes made on the remote side,
+    /// which this watcher can't see.
+    pub async fn start_file_watcher(&mut self) -> Result<(), String> {
+        if self.watch_task.is_some() {
+            return Ok(()); // Already running
+        }
+
+        let db_path = get_db_path(&self.app);
+        let watch_dir = db_path.parent().ok_or("Database path has no parent directory")?.to_path_buf();
+        let watch_file_name = db_path.file_name().ok_or("Database path has no file name")?.to_os_string();
+
+        let (tx, mut rx) = tokio::sync::mpsc::unbounded_channel::<()>();
+        let mut watcher = notify::recommended_watcher(move |res: notify::Result<notify::Event>| {
+            if let Ok(event) = res {
+                if event.kind.is_modify() && event.paths.iter().any(|p| p.file_name() == Some(watch_file_name.as_os_str())) {
+                    let _ = tx.send(());
+                }
+            }
+        })
+        .map_err(|e| format!("Failed to create database file watcher: {}", e))?;
+        watcher
+            .watch(&watch_dir, notify::RecursiveMode::NonRecursive)
+            .map_err(|e| format!("Failed to watch database directory: {}", e))?;
+
+        let config = self.config.clone();
+        let status = self.status.clone();
+        let app = self.app.clone();
+
+        let task = tokio::spawn(async move {
+            let _watcher = watcher; // keep the watcher alive for as long as this task runs
+            'watch: loop {
+                // Wait for the first write of a new burst.
+                if rx.recv().await.is_none() {
+                    return; // sender dropped, watcher was torn down
+                }
+
+                // Coalesce further writes until the database has sat unmodified for the
+                // configured debounce window.
+                let debounce = Duration::from_secs(config.lock().await.debounce_seconds.max(1));
+                loop {
+                    match tokio::time::timeout(debounce, rx.recv()).await {
+                        Ok(Some(())) => continue, // another write arrived, restart the wait
+                        Ok(None) => return,       // sender dropped
+                        Err(_) => break,          // quiescence reached
+                    }
+                }
+
+                let mut status_guard = status.lock().await;
+                if status_guard.sync_in_progress {
+                    continue 'watch;
+                }
+                status_guard.sync_in_progress = true;
+                drop(status_guard);
+
+                match Self::perform_sync_upload_only(&app).await {
+                    Ok(_) => {
+                        let mut status_guard = status.lock().await;
+                        status_guard.last_sync = Some(SystemTime::now().duration_since(UNIX_EPOCH).unwrap().as_secs().to_string());
+                        status_guard.error_message = None;
+                        status_guard.error_type = None;
+                        status_guard.sync_in_progress = false;
+                    }
+                    Err(e) => {
+                        let mut status_guard = status.lock().await;
+                        status_guard.error_type = Some(if e.starts_with("CONFLICT:") { "conflict".to_string() } else { "sync_failed".to_string() });
+                        status_guard.error_message = Some(e);
+                        status_guard.last_error_time = Some(SystemTime::now().duration_since(UNIX_EPOCH).unwrap().as_secs().to_string());
+                        status_guard.sync_in_progress = false;
+                    }
+                }
+            }
+        });
+
+        self.watch_task = Some(task);
+        Ok(())
+    }
+
+    pub async fn stop_file_watcher(&mut self) {
+        if let Some(task) = self.watch_task.take() {
+            task.abort();
+        }
+    }
+
+    /// Watches the OneDrive `sync/` directory for writes to `walnutbook_sync.db` or
+    /// `sync_metadata.json` and, after the configured debounce window, pulls the change in -
+    /// the local equivalent of the reference client's real-time remote-update syncing, so another
+    /// device's edits show up here without waiting on `sync_task`'s periodic timer. Only applies
+    /// to the `"folder"` backend, since the `"graph"` backend has no local directory to watch.
+    /// Safe against the write this process's own uploads make to that same directory: a
+    /// self-originated change always leaves the sync files matching what was just uploaded, so
+    /// `load_from_onedrive` finds nothing new to pull and `last_synced_metadata`'s hash is
+    /// unchanged, which is what this watcher checks before emitting a refresh event.
+    pub async fn start_remote_watch(&mut self) -> Result<(), String> {
+        if self.remote_watch_task.is_some() {
+            return Ok(()); // Already running
+        }
+
+        let config_snapshot = self.load_config().await;
+        if config_snapshot.backend == "graph" {
+            return Err("Remote watch is only supported for the folder sync backend".to_string());
+        }
+
+        let onedrive_data_dir = get_onedrive_data_dir().map_err(|e| format!("OneDrive not available: {}", e))?;
+        let sync_dir = onedrive_data_dir.join("sync");
+        fs::create_dir_all(&sync_dir).map_err(|e| format!("Failed to create sync directory: {}", e))?;
+
+        let (tx, mut rx) = tokio::sync::mpsc::unbounded_channel::<()>();
+        let mut watcher = notify::recommended_watcher(move |res: notify::Result<notify::Event>| {
+            if let Ok(event) = res {
+                let touches_sync_files = event.paths.iter().any(|p| {
+                    matches!(p.file_name().and_then(|n| n.to_str()), Some("walnutbook_sync.db") | Some("sync_metadata.json"))
+                });
+                if event.kind.is_modify() && touches_sync_files {
+                    let _ = tx.send(());
+                }
+            }
+        })
+        .map_err(|e| format!("Failed to create remote sync watcher: {}", e))?;
+        watcher
+            .watch(&sync_dir, notify::RecursiveMode::NonRecursive)
+            .map_err(|e| format!("Failed to watch OneDrive sync directory: {}", e))?;
+
+        let config = self.config.clone();
+        let status = self.status.clone();
+        let app = self.app.clone();
+
+        let task = tokio::spawn(async move {
+            let _watcher = watcher; // keep the watcher alive for as long as this task runs
+            loop {
+                if rx.recv().await.is_none() {
+                    return; // sender dropped, watcher was torn down
+                }
+
+                let debounce = Duration::from_secs(config.lock().await.debounce_seconds.max(1));
+                loop {
+                    match tokio::time::timeout(debounce, rx.recv()).await {
+                        Ok(Some(())) => continue, // another write arrived, restart the wait
+                        Ok(None) => return,       // sender dropped
+                        Err(_) => break,          // quiescence reached
+                    }
+                }
+
+                if status.lock().await.sync_in_progress {
+                    continue;
+                }
+
+                let db_path = get_db_path(&app);
+                let hash_before = load_last_synced_metadata().and_then(|m| m.hash);
+                if let Err(e) = Self::load_from_onedrive_static(&db_path).await {
+                    // "Nothing new" outcomes (already in sync, local ahead, a refused big
+                    // delete) are expected and not worth logging as failures.
+                    if !e.contains("newer or same age") && !e.contains("No sync data found") && !e.starts_with("BIG_DELETE:") {
+                        eprintln!("Remote watch pull failed: {}", e);
+                    }
+                    continue;
+                }
+                let hash_after = load_last_synced_metadata().and_then(|m| m.hash);
+
+                if hash_after != hash_before {
+                    let _ = app.emit("sync_remote_update", ());
+                }
+            }
+        });
+
+        self.remote_watch_task = Some(task);
+        Ok(())
+    }
+
+    pub async fn stop_remote_watch(&mut self) {
+        if let Some(task) = self.remote_watch_task.take() {
+            task.abort();
+        }
+    }
+
     pub async fn manual_sync(&self) -> Result<(), String> {
         // Set sync in progress
         {
@@ -304,6 +1214,13 @@ impl SyncManager {
                     status.retry_count = 0;
                     status.last_error_time = None;
                 }
+                Err(e) if e.starts_with("CONFLICT:") => {
+                    // Divergence was already merged (arbitrated rows are in sync_conflicts) - this
+                    // needs the user to look at it, not an automatic retry.
+                    status.error_message = Some(e.clone());
+                    status.error_type = Some("conflict".to_string());
+                    status.last_error_time = Some(SystemTime::now().duration_since(UNIX_EPOCH).unwrap().as_secs().to_string());
+                }
                 Err(e) => {
                     status.error_message = Some(e.clone());
                     status.error_type = Some("sync_failed".to_string());
@@ -313,10 +1230,95 @@ impl SyncManager {
             }
             status.sync_in_progress = false;
         }
-        
+
+        // Mirror the attachments folder alongside the database. Best-effort: a failure here
+        // (e.g. OneDrive unavailable) doesn't turn a successful database sync into a failed one.
+        if result.is_ok() {
+            if let Err(e) = self.sync_attachments().await {
+                eprintln!("Failed to sync attachments: {}", e);
+            }
+        }
+
         result
     }
 
+    /// Mirrors `get_attachments_dir`'s local folder into `sync/attachments/` next to the synced
+    /// database, skipping any file name matching `SyncConfig.skip_file`. Compares each file's
+    /// QuickXorHash with its sidecar metadata, so an unchanged attachment is never re-copied;
+    /// modification times are not compared.
+    pub async fn sync_attachments(&self) -> Result<(), String> {
+        let local_dir = get_attachments_dir(&self.app);
+        let onedrive_data_dir = get_onedrive_data_dir().map_err(|e| format!("OneDrive not available: {}", e))?;
+        let remote_dir = onedrive_data_dir.join("sync").join("attachments");
+        let skip_patterns = self.config.lock().await.skip_file.clone();
+        Self::sync_attachments_dir(&local_dir, &remote_dir, &skip_patterns, &self.status).await
+    }
+
+    /// Walks `local_dir` non-recursively, copying every file not matching `skip_patterns` into
+    /// `remote_dir` (created if missing) whose hash differs from the sidecar `<name>.meta.json`
+    /// metadata already there, and reports aggregate progress through `status`.
+    async fn sync_attachments_dir(
+        local_dir: &Path,
+        remote_dir: &Path,
+        skip_patterns: &[String],
+        status: &Arc<Mutex<SyncStatus>>,
+    ) -> Result<(), String> {
+        fs::create_dir_all(remote_dir).map_err(|e| format!("Failed to create attachments sync directory: {}", e))?;
+
+        let entries: Vec<PathBuf> = fs::read_dir(local_dir)
+            .map_err(|e| format!("Failed to read attachments directory: {}", e))?
+            .filter_map(|entry| entry.ok())
+            .map(|entry| entry.path())
+            .filter(|path| path.is_file())
+            .filter(|path| {
+                path.file_name()
+                    .and_then(|n| n.to_str())
+                    .map_or(true, |name| !is_skipped_file(name, skip_patterns))
+            })
+            .collect();
+
+        {
+            let mut status_guard = status.lock().await;
+            status_guard.attachments_total = entries.len() as u32;
+            status_guard.attachments_synced = 0;
+        }
+
+        for local_path in entries {
+            let file_name = local_path.file_name().unwrap().to_os_string();
+            let remote_path = remote_dir.join(&file_name);
+            let meta_path = remote_dir.join(format!("{}.meta.json", file_name.to_string_lossy()));
+
+            let local_hash = quickxorhash_file(&local_path)?;
+            let already_synced = fs::read_to_string(&meta_path)
+                .ok()
+                .and_then(|json| serde_json::from_str::<SyncMetadata>(&json).ok())
+                .map_or(false, |existing| existing.hash.as_deref() == Some(local_hash.as_str()));
+
+            if !already_synced {
+                fs::copy(&local_path, &remote_path)
+                    .map_err(|e| format!("Failed to copy attachment {}: {}", file_name.to_string_lossy(), e))?;
+
+                let metadata = SyncMetadata {
+                    last_modified: SystemTime::now().duration_since(UNIX_EPOCH).unwrap().as_secs(),
+                    file_size: fs::metadata(&local_path).map_err(|e| e.to_string())?.len(),
+                    version: "1.0".to_string(),
+                    hash: Some(local_hash),
+                    schema_version: 0,
+                    // Per-file attachment metadata isn't part of the database's conflict tracking.
+                    generation: 0,
+                    parent_hash: None,
+                };
+                let metadata_json = serde_json::to_string_pretty(&metadata).map_err(|e| e.to_string())?;
+                fs::write(&meta_path, metadata_json).map_err(|e| format!("Failed to write attachment metadata: {}", e))?;
+            }
+
+            let mut status_guard = status.lock().await;
+            status_guard.attachments_synced += 1;
+        }
+
+        Ok(())
+    }
+
     async fn perform_sync_upload_only(app: &AppHandle) -> Result<(), String> {
         // Get database path
         let db_path = get_db_path(app);
@@ -327,6 +1329,9 @@ impl SyncManager {
         // This prevents overwriting user's restored/current work with old OneDrive data
         match Self::try_onedrive_sync(&db_path).await {
             Ok(_) => return Ok(()),
+            // The upload itself succeeded - a diverged remote copy was merged in and some rows
+            // arbitrated, not a sync failure - so don't fall back to the local backup, just surface it.
+            Err(onedrive_error) if onedrive_error.starts_with("CONFLICT:") => return Err(onedrive_error),
             Err(onedrive_error) => {
                 // OneDrive sync failed, try local storage fallback
                 match Self::try_local_sync(&db_path).await {
@@ -406,7 +1411,15 @@ impl SyncManager {
         
         // First, try to load latest data from OneDrive (if it's newer)
         // Only do this for automatic sync, not manual sync
-        let loaded_from_onedrive = match Self::load_from_onedrive_static(&db_path).await {
+        let load_result = Self::load_from_onedrive_static(&db_path).await;
+        if let Err(e) = &load_result {
+            if e.starts_with("CONFLICT:") {
+                // The pull already merged the divergence into the local database (the remote
+                // copy is updated by the next upload) - still surface it so the caller can flag it.
+                return Err(e.clone());
+            }
+        }
+        let loaded_from_onedrive = match load_result {
             Ok(_) => {
                 // Successfully loaded newer data from OneDrive
                 true
@@ -427,6 +1440,7 @@ impl SyncManager {
             // We loaded newer data from OneDrive, now sync it back to maintain consistency
             match Self::try_onedrive_sync(&db_path).await {
                 Ok(_) => return Ok(()),
+                Err(onedrive_error) if onedrive_error.starts_with("CONFLICT:") => return Err(onedrive_error),
                 Err(onedrive_error) => {
                     // OneDrive sync failed, try local storage fallback
                     match Self::try_local_sync(&db_path).await {
@@ -445,10 +1459,11 @@ impl SyncManager {
                 Ok(should) => should,
                 Err(_) => true, // If check fails, proceed with sync
             };
-            
+
             if should_sync_local {
                 match Self::try_onedrive_sync(&db_path).await {
                     Ok(_) => return Ok(()),
+                    Err(onedrive_error) if onedrive_error.starts_with("CONFLICT:") => return Err(onedrive_error),
                     Err(onedrive_error) => {
                         // OneDrive sync failed, try local storage fallback
                         match Self::try_local_sync(&db_path).await {
@@ -468,7 +1483,140 @@ impl SyncManager {
         }
     }
 
+    /// Uploads through whichever [`SyncBackend`] `config.backend` selects, stamping the remote
+    /// copy with the same "current time or local file time, whichever is newer" timestamp
+    /// `try_onedrive_sync` always has. Skipped entirely if the remote's own hash already matches
+    /// the local database, so an unchanged file is never needlessly re-uploaded. If the remote has
+    /// diverged from what this machine last synced (see `has_diverged`), its current content is
+    /// downloaded to a scratch file and merged into the local database by `merge_diverged` before
+    /// the upload, which still proceeds but reports any arbitrated rows via a `"CONFLICT:"`-prefixed error.
+    async fn upload_via_backend(backend: &dyn SyncBackend, db_path: &std::path::Path) -> Result<(), String> {
+        let local_hash = quickxorhash_file(db_path)?;
+        let remote_metadata = backend.remote_metadata().await?;
+        if let Some(remote) = &remote_metadata {
+            if remote.hash.as_deref() == Some(local_hash.as_str()) {
+                save_last_synced_metadata(remote)?;
+                return Ok(());
+            }
+        }
+
+        let mut conflicts = 0;
+        if let Some(remote) = &remote_metadata {
+            if has_diverged(remote, &local_hash) {
+                let scratch_path = last_synced_db_path().with_file_name("remote_merge_scratch.db");
+                backend.download(&scratch_path).await.map_err(|e| format!("Failed to download remote copy for merge: {}", e))?;
+                let result = merge_diverged(db_path, &scratch_path, remote.generation + 1, remote.hash.clone());
+                let _ = fs::remove_file(&scratch_path);
+                conflicts = result?;
+            }
+        }
+
+        // Re-hash: the merge above may have changed what `db_path` contains.
+        let local_hash = quickxorhash_file(db_path)?;
+        let local_metadata = fs::metadata(db_path)
+            .map_err(|e| format!("Failed to get local database metadata: {}", e))?;
+        let local_modified = local_metadata
+            .modified()
+            .map_err(|e| format!("Failed to get local modification time: {}", e))?
+            .duration_since(UNIX_EPOCH)
+            .map_err(|e| format!("Failed to convert local time: {}", e))?
+            .as_secs();
+        let now = SystemTime::now().duration_since(UNIX_EPOCH).unwrap().as_secs();
+
+        let metadata = SyncMetadata {
+            last_modified: std::cmp::max(now, local_modified),
+            file_size: local_metadata.len(),
+            version: "1.0".to_string(),
+            hash: Some(local_hash),
+            schema_version: crate::migrations::MIGRATIONS.len() as i64,
+            generation: remote_metadata.as_ref().map_or(0, |m| m.generation) + 1,
+            parent_hash: remote_metadata.as_ref().and_then(|m| m.hash.clone()),
+        };
+        backend.upload(db_path, &metadata).await?;
+        save_last_synced_metadata(&metadata)?;
+        save_base_snapshot(db_path)?;
+
+        if conflicts > 0 {
+            Err(merge_conflict_message(conflicts))
+        } else {
+            Ok(())
+        }
+    }
+
+    /// Downloads through `backend` only if its remote copy is newer than the local database,
+    /// backing up the local file first the same way `load_from_onedrive_static` does so a failed
+    /// or corrupt download never leaves the user with neither copy intact. The downloaded file's
+    /// hash is checked against the metadata it shipped with, so a silently-truncated or otherwise
+    /// corrupted transfer is caught instead of becoming the new local database. If the local
+    /// database has diverged from what this machine last synced, `merge_diverged` is used instead
+    /// of a wholesale overwrite, and any row it had to arbitrate is reported via a `"CONFLICT:"`
+    /// error.
+    async fn download_via_backend(backend: &dyn SyncBackend, db_path: &std::path::Path) -> Result<(), String> {
+        let remote_metadata = backend.remote_metadata().await?.ok_or("No sync data found")?;
+        check_schema_compatible(&remote_metadata)?;
+
+        let local_modified = fs::metadata(db_path)
+            .and_then(|m| m.modified())
+            .map_err(|e| format!("Failed to get local modification time: {}", e))?
+            .duration_since(UNIX_EPOCH)
+            .map_err(|e| e.to_string())?
+            .as_secs();
+
+        if remote_metadata.last_modified <= local_modified {
+            return Err("Local database is newer or same age".to_string());
+        }
+
+        let local_hash = quickxorhash_file(db_path)?;
+        if has_diverged(&remote_metadata, &local_hash) {
+            let scratch_path = last_synced_db_path().with_file_name("remote_merge_scratch.db");
+            backend.download(&scratch_path).await.map_err(|e| format!("Failed to download remote copy for merge: {}", e))?;
+            let conflicts = merge_diverged(db_path, &scratch_path, remote_metadata.generation + 1, remote_metadata.hash.clone());
+            let _ = fs::remove_file(&scratch_path);
+            return match conflicts? {
+                0 => Ok(()),
+                n => Err(merge_conflict_message(n)),
+            };
+        }
+
+        let backup_path = format!(
+            "{}.backup_{}",
+            db_path.to_string_lossy(),
+            SystemTime::now().duration_since(UNIX_EPOCH).unwrap().as_secs()
+        );
+        fs::copy(db_path, &backup_path).map_err(|e| format!("Failed to backup local database: {}", e))?;
+
+        match backend.download(db_path).await {
+            Ok(_) => {
+                if let Some(expected_hash) = &remote_metadata.hash {
+                    let actual_hash = quickxorhash_file(db_path)?;
+                    if &actual_hash != expected_hash {
+                        let _ = fs::copy(&backup_path, db_path);
+                        return Err("Downloaded database failed hash verification, possibly corrupted in transit".to_string());
+                    }
+                }
+                if let Err(e) = crate::migrations::open_and_migrate(db_path) {
+                    let _ = fs::copy(&backup_path, db_path);
+                    return Err(format!("Failed to migrate downloaded database: {}", e));
+                }
+                fs::remove_file(&backup_path).map_err(|e| format!("Failed to remove backup: {}", e))?;
+                save_last_synced_metadata(&remote_metadata)?;
+                save_base_snapshot(db_path)?;
+                Ok(())
+            }
+            Err(e) => {
+                let _ = fs::copy(&backup_path, db_path);
+                Err(e)
+            }
+        }
+    }
+
     async fn try_onedrive_sync(db_path: &std::path::Path) -> Result<(), String> {
+        let config = Self::load_config_static().await;
+        if config.backend == "graph" {
+            let backend = make_backend(&config)?;
+            return Self::upload_via_backend(backend.as_ref(), db_path).await;
+        }
+
         // Get OneDrive data directory
         let onedrive_data_dir = get_onedrive_data_dir()
             .map_err(|e| format!("OneDrive not available: {}", e))?;
@@ -481,21 +1629,60 @@ impl SyncManager {
         // Get local database modification time to use for metadata
         let local_metadata = fs::metadata(db_path)
             .map_err(|e| format!("Failed to get local database metadata: {}", e))?;
-        
+
         let local_modified = local_metadata.modified()
             .map_err(|e| format!("Failed to get local modification time: {}", e))?
             .duration_since(UNIX_EPOCH)
             .map_err(|e| format!("Failed to convert local time: {}", e))?
             .as_secs();
 
+        // Skip the copy entirely if OneDrive's own hash already matches the local database -
+        // the existing mtime comparisons above decided a sync was due, but the content may well
+        // be identical (e.g. both sides just synced the same change).
+        let local_hash = quickxorhash_file(db_path)?;
+        let metadata_path = sync_dir.join("sync_metadata.json");
+        let existing_metadata: Option<SyncMetadata> = fs::read_to_string(&metadata_path)
+            .ok()
+            .and_then(|json| serde_json::from_str(&json).ok());
+        if let Some(existing) = &existing_metadata {
+            if existing.hash.as_deref() == Some(local_hash.as_str()) {
+                save_last_synced_metadata(existing)?;
+                return Ok(());
+            }
+        }
+
+        // If OneDrive moved on to content we've never seen, at the same time we moved on to
+        // content it's never seen, neither side is simply "behind" - merge at row granularity
+        // instead of letting one whole-file copy clobber the other's edits, then push the
+        // merged result live.
+        let sync_db_path = sync_dir.join("walnutbook_sync.db");
+        let mut conflicts = 0;
+        if let Some(existing) = &existing_metadata {
+            if has_diverged(existing, &local_hash) && sync_db_path.exists() {
+                conflicts = merge_diverged(db_path, &sync_db_path, existing.generation + 1, existing.hash.clone())?;
+            }
+        }
+
+        // The merge above may have changed what `db_path` contains - re-read its modification
+        // time and hash before using them for the upload below.
+        let local_metadata = fs::metadata(db_path)
+            .map_err(|e| format!("Failed to get local database metadata: {}", e))?;
+        let local_modified = local_metadata.modified()
+            .map_err(|e| format!("Failed to get local modification time: {}", e))?
+            .duration_since(UNIX_EPOCH)
+            .map_err(|e| format!("Failed to convert local time: {}", e))?
+            .as_secs();
+        let local_hash = quickxorhash_file(db_path)?;
+
         // Use current time or local file time, whichever is newer
         let now = SystemTime::now().duration_since(UNIX_EPOCH).unwrap().as_secs();
         let timestamp_to_use = std::cmp::max(now, local_modified);
 
-        // Copy database to OneDrive
-        let sync_db_path = sync_dir.join("walnutbook_sync.db");
-        fs::copy(db_path, &sync_db_path)
-            .map_err(|e| format!("Failed to copy database to OneDrive: {}", e))?;
+        // Publish atomically: stage to a temp file in the same directory, verify it, then rename
+        // it over the target - a crash or OneDrive sync-in-progress mid-copy can never leave
+        // `sync_db_path` half-written, and a handful of retries absorb the "file busy" errors
+        // OneDrive often returns while it's mid-sync.
+        publish_to_sync_dir(db_path, &sync_db_path, &local_hash)?;
 
         // Update OneDrive file's modification time to match local or current time
         // This ensures consistent timestamp comparison on Mac and Windows
@@ -525,17 +1712,27 @@ impl SyncManager {
         let metadata = SyncMetadata {
             last_modified: timestamp_to_use,
             file_size: fs::metadata(&sync_db_path).map_err(|e| e.to_string())?.len(),
-            version: "1.0".to_string(),
+            version: format!("{}.0", crate::migrations::SCHEMA_MAJOR_VERSION),
+            hash: Some(local_hash),
+            schema_version: crate::migrations::MIGRATIONS.len() as i64,
+            generation: existing_metadata.as_ref().map_or(0, |m| m.generation) + 1,
+            parent_hash: existing_metadata.as_ref().and_then(|m| m.hash.clone()),
         };
 
         let metadata_json = serde_json::to_string_pretty(&metadata)
             .map_err(|e| format!("Failed to serialize metadata: {}", e))?;
 
-        let metadata_path = sync_dir.join("sync_metadata.json");
         fs::write(&metadata_path, &metadata_json)
             .map_err(|e| format!("Failed to write metadata: {}", e))?;
 
-        Ok(())
+        save_last_synced_metadata(&metadata)?;
+        save_base_snapshot(db_path)?;
+
+        if conflicts > 0 {
+            Err(merge_conflict_message(conflicts))
+        } else {
+            Ok(())
+        }
     }
 
     async fn should_sync_local_to_onedrive(db_path: &std::path::Path) -> Result<bool, String> {
@@ -595,7 +1792,13 @@ impl SyncManager {
         let metadata = SyncMetadata {
             last_modified: SystemTime::now().duration_since(UNIX_EPOCH).unwrap().as_secs(),
             file_size: fs::metadata(&sync_db_path).map_err(|e| e.to_string())?.len(),
-            version: "1.0".to_string(),
+            version: format!("{}.0", crate::migrations::SCHEMA_MAJOR_VERSION),
+            hash: Some(quickxorhash_file(db_path)?),
+            schema_version: crate::migrations::MIGRATIONS.len() as i64,
+            // This is a local-only fallback copy, not part of the real remote - no conflict
+            // tracking applies to it.
+            generation: 0,
+            parent_hash: None,
         };
 
         let metadata_json = serde_json::to_string_pretty(&metadata)
@@ -609,6 +1812,19 @@ impl SyncManager {
     }
 
     async fn load_from_onedrive_static(db_path: &std::path::Path) -> Result<(), String> {
+        Self::load_from_onedrive_static_inner(db_path, false).await
+    }
+
+    /// Core of [`Self::load_from_onedrive_static`]. `force_big_delete` lets
+    /// [`SyncManager::confirm_risky_sync`] re-run the exact same pull after the user has
+    /// explicitly accepted a [`classify_as_big_delete`] warning, bypassing only that one guard.
+    async fn load_from_onedrive_static_inner(db_path: &std::path::Path, force_big_delete: bool) -> Result<(), String> {
+        let config = Self::load_config_static().await;
+        if config.backend == "graph" {
+            let backend = make_backend(&config)?;
+            return Self::download_via_backend(backend.as_ref(), db_path).await;
+        }
+
         // Get OneDrive data directory
         let onedrive_data_dir = get_onedrive_data_dir()
             .map_err(|e| format!("OneDrive not available: {}", e))?;
@@ -628,124 +1844,73 @@ impl SyncManager {
         
         let metadata: SyncMetadata = serde_json::from_str(&metadata_json)
             .map_err(|e| format!("Failed to parse metadata: {}", e))?;
+        check_schema_compatible(&metadata)?;
+
+        // Decide purely from content hashes rather than mtimes - OneDrive file modification
+        // times are unreliable (sync delays, clock skew between machines), but a hash either
+        // matches what's already local or it doesn't.
+        let local_hash = quickxorhash_file(db_path)?;
+        if metadata.hash.as_deref() == Some(local_hash.as_str()) {
+            // The remote already matches what's on disk - nothing to pull.
+            save_last_synced_metadata(&metadata)?;
+            save_base_snapshot(db_path)?;
+            return Ok(());
+        }
 
-        // Get OneDrive file's actual modification time
-        let onedrive_file_metadata = fs::metadata(&sync_db_path)
-            .map_err(|e| format!("Failed to get OneDrive file metadata: {}", e))?;
-        
-        let onedrive_file_modified = onedrive_file_metadata.modified()
-            .map_err(|e| format!("Failed to get OneDrive file modification time: {}", e))?
-            .duration_since(UNIX_EPOCH)
-            .map_err(|e| format!("Failed to convert OneDrive file time: {}", e))?
-            .as_secs();
-
-        // Use the newer of metadata timestamp and actual file modification time
-        // On Windows, OneDrive file modification times can be unreliable due to sync delays,
-        // so we prioritize metadata timestamp which is more reliable
-        #[cfg(target_os = "windows")]
-        let onedrive_modified = {
-            // On Windows, trust metadata more than file modification time
-            // Use metadata if it's significantly different, otherwise use the max
-            if metadata.last_modified > onedrive_file_modified + 2 {
-                // Metadata is significantly newer, use it
-                metadata.last_modified
-            } else if onedrive_file_modified > metadata.last_modified + 2 {
-                // File time is significantly newer, use it
-                onedrive_file_modified
-            } else {
-                // They're close, use the max (likely same update)
-                std::cmp::max(metadata.last_modified, onedrive_file_modified)
-            }
-        };
-        #[cfg(not(target_os = "windows"))]
-        let onedrive_modified = std::cmp::max(metadata.last_modified, onedrive_file_modified);
-
-        // Get local database path and check its modification time
-        let local_metadata = fs::metadata(db_path)
-            .map_err(|e| format!("Failed to get local database metadata: {}", e))?;
-        
-        let local_modified = local_metadata.modified()
-            .map_err(|e| format!("Failed to get local modification time: {}", e))?
-            .duration_since(UNIX_EPOCH)
-            .map_err(|e| format!("Failed to convert local time: {}", e))?
-            .as_secs();
-
-        // Compare timestamps and transaction counts
-        // On Windows, also check if local DB is suspiciously old (might be from restore with wrong timestamp)
-        let should_load_from_onedrive = if onedrive_modified > local_modified {
-            true
-        } else if onedrive_modified < local_modified {
-            // Local is newer, but check if the difference is suspiciously large
-            // If local is much newer than OneDrive metadata, it might be a restored database
-            // In that case, compare by transaction count to be safe
-            #[cfg(target_os = "windows")]
-            {
-                // On Windows, if local is significantly newer (more than 1 day), compare by content
-                let diff = local_modified as i64 - onedrive_modified as i64;
-                if diff > 86400 {
-                    // Local is more than 1 day newer, compare by transaction count
-                    let onedrive_conn = Connection::open(&sync_db_path)
-                        .map_err(|e| format!("Failed to open OneDrive database: {}", e))?;
-                    let local_conn = Connection::open(db_path)
-                        .map_err(|e| format!("Failed to open local database: {}", e))?;
-                    
-                    let onedrive_count: i64 = onedrive_conn
-                        .query_row("SELECT COUNT(*) FROM transactions", [], |row| row.get(0))
-                        .unwrap_or(0);
-                    
-                    let local_count: i64 = local_conn
-                        .query_row("SELECT COUNT(*) FROM transactions", [], |row| row.get(0))
-                        .unwrap_or(0);
-                    
-                    // If OneDrive has more transactions, it might be newer despite timestamp
-                    onedrive_count > local_count
-                } else {
-                    false
-                }
-            }
-            #[cfg(not(target_os = "windows"))]
-            {
-                false
+        // The hashes differ, so *something* changed - but only pull it over if OneDrive is the
+        // side that actually moved. If we have a record of what this machine last synced and
+        // OneDrive's hash still matches that, OneDrive hasn't changed at all; the difference is
+        // unsynced local work, which should be pushed, not overwritten by a pull.
+        if let Some(last_synced) = load_last_synced_metadata() {
+            if last_synced.hash.as_deref() == metadata.hash.as_deref() {
+                return Err("Local database is newer or same age".to_string());
             }
-        } else {
-            // Timestamps are equal, compare by transaction count
-            let onedrive_conn = Connection::open(&sync_db_path)
-                .map_err(|e| format!("Failed to open OneDrive database: {}", e))?;
-            let local_conn = Connection::open(db_path)
-                .map_err(|e| format!("Failed to open local database: {}", e))?;
-            
-            let onedrive_count: i64 = onedrive_conn
-                .query_row("SELECT COUNT(*) FROM transactions", [], |row| row.get(0))
-                .unwrap_or(0);
-            
-            let local_count: i64 = local_conn
-                .query_row("SELECT COUNT(*) FROM transactions", [], |row| row.get(0))
-                .unwrap_or(0);
-            
-            onedrive_count > local_count
-        };
+        }
 
-        // Compare timestamps - only load from OneDrive if it's newer
-        if !should_load_from_onedrive {
-            // Local database is newer or same age, don't overwrite
-            return Err("Local database is newer or same age".to_string());
+        // If the local database moved on to content OneDrive has never seen, at the same time
+        // OneDrive moved on to content we've never seen, neither side is simply "behind" - merge
+        // at row granularity instead of letting OneDrive's copy clobber the local edits, and
+        // report any row that had to be arbitrated instead of silently discarding it. The merge
+        // already guarantees the required tables exist and leaves a hash that intentionally
+        // won't match `metadata.hash`, so skip the raw-overwrite verification below entirely.
+        if has_diverged(&metadata, &local_hash) {
+            let conflicts = merge_diverged(db_path, &sync_db_path, metadata.generation + 1, metadata.hash.clone())?;
+            return if conflicts > 0 {
+                Err(merge_conflict_message(conflicts))
+            } else {
+                Ok(())
+            };
         }
 
         // Create backup of local database
-        let backup_path = format!("{}.backup_{}", 
-            db_path.to_string_lossy(), 
+        let backup_path = format!("{}.backup_{}",
+            db_path.to_string_lossy(),
             SystemTime::now().duration_since(UNIX_EPOCH).unwrap().as_secs()
         );
         fs::copy(db_path, &backup_path)
             .map_err(|e| format!("Failed to backup local database: {}", e))?;
 
+        // Refuse a pull that would remove a suspiciously large share of the local database - one
+        // bad sync from another device shouldn't be able to silently wipe a year of records. The
+        // backup just made above is left in place (instead of being cleaned up like the normal
+        // success path does) so the user can inspect or restore it, and `confirm_risky_sync` can
+        // re-run this same pull with the guard bypassed if the deletion is actually intentional.
+        if !force_big_delete {
+            if let Some(removed) = classify_as_big_delete(&sync_db_path, db_path, config.big_delete_row_threshold, config.big_delete_fraction)? {
+                return Err(format!(
+                    "BIG_DELETE: pulling this OneDrive copy would remove {} row(s) from accounts/budgets/transactions; local database backed up to {} - call confirm_risky_sync to proceed anyway",
+                    removed,
+                    backup_path
+                ));
+            }
+        }
+
         // Copy OneDrive database to local
         fs::copy(&sync_db_path, db_path)
             .map_err(|e| format!("Failed to copy OneDrive database: {}", e))?;
 
-        // Update the local file's modification time to match OneDrive timestamp
-        // This ensures consistent timestamp comparison on Mac and Windows
-        let modified_time = SystemTime::UNIX_EPOCH + Duration::from_secs(onedrive_modified);
+        // Update the local file's modification time to match OneDrive's recorded timestamp.
+        let modified_time = SystemTime::UNIX_EPOCH + Duration::from_secs(metadata.last_modified);
         #[cfg(target_os = "windows")]
         {
             // On Windows, try to open with write access to set modification time
@@ -767,9 +1932,23 @@ impl SyncManager {
             }
         }
 
-        // Verify the copied database
-        let conn = Connection::open(db_path)
-            .map_err(|e| format!("Failed to open copied database: {}", e))?;
+        // Verify the copied database's content hash, catching a silently-truncated or otherwise
+        // corrupted copy before it's trusted as the new local database.
+        if let Some(expected_hash) = &metadata.hash {
+            let actual_hash = quickxorhash_file(db_path)?;
+            if &actual_hash != expected_hash {
+                let _ = fs::copy(&backup_path, db_path);
+                return Err("OneDrive database failed hash verification, possibly corrupted".to_string());
+            }
+        }
+
+        // Bring an older remote copy forward to this app's schema before trusting it - the
+        // version gate above already guarantees `metadata.schema_version` is no newer than
+        // `MIGRATIONS.len()`, so this is a no-op when the remote was already current.
+        let conn = crate::migrations::open_and_migrate(db_path).map_err(|e| {
+            let _ = fs::copy(&backup_path, db_path);
+            format!("Failed to migrate OneDrive database: {}", e)
+        })?;
 
         // Check if required tables exist
         let tables = ["accounts", "transactions", "categories", "budgets"];
@@ -785,6 +1964,9 @@ impl SyncManager {
         fs::remove_file(&backup_path)
             .map_err(|e| format!("Failed to remove backup: {}", e))?;
 
+        save_last_synced_metadata(&metadata)?;
+        save_base_snapshot(db_path)?;
+
         Ok(())
     }
 
@@ -793,6 +1975,14 @@ impl SyncManager {
         Self::load_from_onedrive_static(&db_path).await
     }
 
+    /// Re-runs the pull `load_from_onedrive` just refused with a `"BIG_DELETE:"` error, this time
+    /// bypassing [`classify_as_big_delete`] - for when the user has inspected the warning and
+    /// confirmed the large deletion it reported is actually intentional.
+    pub async fn confirm_risky_sync(&self) -> Result<(), String> {
+        let db_path = get_db_path(&self.app);
+        Self::load_from_onedrive_static_inner(&db_path, true).await
+    }
+
     pub async fn get_status(&mut self) -> SyncStatus {
         // Initialize if not already done (lazy initialization)
         let needs_init = {
@@ -874,11 +2064,36 @@ impl SyncManager {
     }
 }
 
-#[derive(Debug, Serialize, Deserialize)]
-struct SyncMetadata {
-    last_modified: u64,
-    file_size: u64,
-    version: String,
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SyncMetadata {
+    pub last_modified: u64,
+    pub file_size: u64,
+    /// `"{major}.0"` from [`crate::migrations::SCHEMA_MAJOR_VERSION`] at the time this was
+    /// written. Unlike `schema_version` below, this is never expected to advance without an app
+    /// update that also bumps the constant - a mismatch here means the two sides can't
+    /// reconcile by migrating and `load_from_onedrive_static` refuses the pull outright.
+    pub version: String,
+    /// Base64-encoded [`QuickXorHash`] of the database this metadata describes. `None` for
+    /// metadata written before this field existed; treated as "unknown, don't compare" rather
+    /// than a mismatch.
+    #[serde(default)]
+    pub hash: Option<String>,
+    /// `crate::migrations::MIGRATIONS.len()` at the time this was written, i.e. how many
+    /// migrations the database this metadata describes has applied. `0` for metadata written
+    /// before this field existed, which `load_from_onedrive_static` treats as "older than
+    /// anything this app would write" rather than a version it must reject.
+    #[serde(default)]
+    pub schema_version: i64,
+    /// Monotonically increasing counter, one higher than the `generation` of the metadata this
+    /// sync superseded. Used only for bookkeeping/debugging - divergence itself is detected by
+    /// `has_diverged` comparing hashes with `last_synced_metadata`, not by comparing generations.
+    #[serde(default)]
+    pub generation: u64,
+    /// `hash` of the remote state this sync was based on, i.e. what `parent_hash`'s writer last
+    /// saw before producing this metadata. Recorded for bookkeeping only; `has_diverged` does not
+    /// read it.
+    #[serde(default)]
+    pub parent_hash: Option<String>,
 }
 
 // Helper function to trigger sync after data changes
@@ -932,6 +2147,13 @@ pub async fn load_from_onedrive(app: AppHandle) -> Result<(), String> {
     manager.load_from_onedrive().await
 }
 
+#[tauri::command]
+pub async fn confirm_risky_sync(app: AppHandle) -> Result<(), String> {
+    let sync_manager = app.state::<Arc<Mutex<SyncManager>>>();
+    let manager = sync_manager.lock().await;
+    manager.confirm_risky_sync().await
+}
+
 #[tauri::command]
 pub async fn start_auto_sync(app: AppHandle) -> Result<(), String> {
     let sync_manager = app.state::<Arc<Mutex<SyncManager>>>();
@@ -946,3 +2168,18 @@ pub async fn stop_auto_sync(app: AppHandle) -> Result<(), String> {
     manager.stop_auto_sync().await;
     Ok(())
 }
+
+#[tauri::command]
+pub async fn start_remote_watch(app: AppHandle) -> Result<(), String> {
+    let sync_manager = app.state::<Arc<Mutex<SyncManager>>>();
+    let mut manager = sync_manager.lock().await;
+    manager.start_remote_watch().await
+}
+
+#[tauri::command]
+pub async fn stop_remote_watch(app: AppHandle) -> Result<(), String> {
+    let sync_manager = app.state::<Arc<Mutex<SyncManager>>>();
+    let mut manager = sync_manager.lock().await;
+    manager.stop_remote_watch().await;
+    Ok(())
+}
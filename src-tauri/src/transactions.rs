@@ -1,11 +1,13 @@
 use chrono::Utc;
-use rusqlite::{params, Connection};
-use serde_json::Value;
-use std::collections::HashSet;
-use tauri::AppHandle;
+use jsonschema::JSONSchema;
+use rusqlite::params;
+use serde_json::{json, Value};
+use std::collections::{HashMap, HashSet};
+use tauri::{AppHandle, State};
 use serde::Serialize;
 use open;
-use crate::utils::{get_db_path, get_onedrive_attachments_dir};
+use crate::encryption::{open_encrypted, DbKeyState};
+use crate::utils::get_onedrive_attachments_dir;
 
 use crate::models::Transaction;
 
@@ -20,24 +22,311 @@ pub struct ImportResult {
     pub imported: Vec<Transaction>,
     pub imported_count: usize,
     pub duplicate_count: usize,
+    pub errors: Vec<ImportRowError>,
+    /// The `import_sessions` row this batch was recorded under, so the caller can hand it
+    /// straight to [`undo_import`] without a separate lookup.
+    pub session_id: i64,
 }
 
+/// One row of `import_sessions`: everything needed to list past imports and undo one of them.
+#[derive(Serialize)]
+pub struct ImportSession {
+    pub id: i64,
+    pub source: String,
+    pub created_at: String,
+    pub imported_count: i64,
+    pub duplicate_count: i64,
+}
+
+/// One row that failed [`transaction_schema`] validation, keyed by its position in the
+/// original `transactions` batch so the UI can point the user back at that CSV line and
+/// retry just the rows that need fixing instead of the whole file.
+#[derive(Serialize)]
+pub struct ImportRowError {
+    pub row_index: usize,
+    pub field: String,
+    pub message: String,
+}
+
+/// JSON Schema for an importable row, checked in addition to whatever Tauri's own IPC
+/// deserialization into [`Transaction`] already guarantees: a CSV importer can hand us a
+/// syntactically valid `Transaction` whose `date` isn't really a date or whose `amount` is
+/// a typo'd ten-million-dollar expense, and those should come back as a per-row error
+/// instead of an `Err` that aborts every other row in the batch.
+fn transaction_schema() -> Value {
+    json!({
+        "type": "object",
+        "required": ["date", "account_id", "type", "amount", "payee"],
+        "properties": {
+            "date": { "type": "string", "pattern": r"^\d{4}-\d{2}-\d{2}$" },
+            "account_id": { "type": "integer", "minimum": 1 },
+            "type": { "type": "string", "enum": ["Expense", "Income", "Transfer", "Adjust"] },
+            "amount": { "type": "number", "minimum": -1000000000.0, "maximum": 1000000000.0 },
+            "payee": { "type": "string", "minLength": 1 }
+        }
+    })
+}
+
+/// Turns one `jsonschema` validation failure into the `{row_index, field, message}` shape the
+/// UI expects. Required-property failures don't point `instance_path` at the missing field
+/// (it points at the object itself), so the field name is pulled out of the quoted property
+/// name in the error message instead.
+fn describe_validation_error(row_index: usize, err: jsonschema::ValidationError<'_>) -> ImportRowError {
+    let message = err.to_string();
+    let path = err.instance_path.to_string();
+    let field = if !path.is_empty() {
+        path.trim_start_matches('/').to_string()
+    } else if let Some(start) = message.find('"') {
+        message[start + 1..]
+            .find('"')
+            .map(|end| message[start + 1..start + 1 + end].to_string())
+            .unwrap_or_else(|| "row".to_string())
+    } else {
+        "row".to_string()
+    };
+    ImportRowError { row_index, field, message }
+}
+
+/// Tolerance below which a transfer group's base-currency sum is considered balanced.
+/// Guards against floating point noise, not real discrepancies.
+const TRANSFER_EPSILON: f64 = 0.005;
+
+#[derive(Serialize)]
+pub struct TransferValidationIssue {
+    pub transfer_id: i64,
+    pub leg_count: i64,
+    pub base_currency_sum: f64,
+    pub issue: String,
+}
+
+/// Groups transactions by `transfer_id` — the column that already links a transfer's
+/// outgoing and incoming legs — and reports any group that isn't exactly two legs summing
+/// to zero in base currency (converted via `exchange_rates`, same as `get_accounts`). An
+/// edited or partially-imported transfer can otherwise silently unbalance two accounts.
 #[tauri::command]
-pub fn get_transactions(app: AppHandle) -> Result<Vec<Transaction>, String> {
-    let path = get_db_path(&app);
-    let conn = Connection::open(path).map_err(|e| e.to_string())?;
-    
+pub fn validate_transfers(app: AppHandle, key_state: State<'_, DbKeyState>) -> Result<Vec<TransferValidationIssue>, String> {
+    let conn = open_encrypted(&app, &key_state)?;
+
+    let mut stmt = conn.prepare(
+        "SELECT t.transfer_id, COUNT(*) AS leg_count,
+            SUM(t.amount * CASE
+                WHEN a.currency = ?1 THEN 1.0
+                ELSE IFNULL((
+                    SELECT er.rate FROM exchange_rates er
+                    WHERE er.currency = a.currency AND er.date <= t.date
+                    ORDER BY er.date DESC LIMIT 1
+                ), 1.0)
+            END) AS base_sum
+         FROM transactions t
+         LEFT JOIN accounts a ON t.account_id = a.id
+         WHERE t.transfer_id IS NOT NULL
+         GROUP BY t.transfer_id
+         HAVING leg_count != 2 OR ABS(base_sum) > ?2"
+    ).map_err(|e| e.to_string())?;
+
+    let rows = stmt.query_map(params![crate::accounts::BASE_CURRENCY, TRANSFER_EPSILON], |row| {
+        let transfer_id: i64 = row.get(0)?;
+        let leg_count: i64 = row.get(1)?;
+        let base_currency_sum: f64 = row.get(2)?;
+        let issue = if leg_count != 2 {
+            format!("expected 2 legs, found {}", leg_count)
+        } else {
+            format!("legs do not balance: off by {:.2}", base_currency_sum)
+        };
+        Ok(TransferValidationIssue { transfer_id, leg_count, base_currency_sum, issue })
+    }).map_err(|e| e.to_string())?;
+
+    let mut issues = Vec::new();
+    for r in rows {
+        issues.push(r.map_err(|e| e.to_string())?);
+    }
+    Ok(issues)
+}
+
+#[derive(Serialize)]
+pub struct TransactionNetValue {
+    pub id: i64,
+    /// `amount` adjusted by the same per-account-type sign logic as `get_accounts`, minus
+    /// `ABS(fee)` — i.e. this transaction's actual contribution to its account's balance.
+    pub net_value: f64,
+}
+
+/// A `v_transactions`-style read: the signed, fee-adjusted contribution of every
+/// transaction to its account's balance, without re-deriving the sign logic client-side.
+#[tauri::command]
+pub fn get_transactions_net_value(app: AppHandle, key_state: State<'_, DbKeyState>) -> Result<Vec<TransactionNetValue>, String> {
+    let conn = open_encrypted(&app, &key_state)?;
+
+    let mut stmt = conn.prepare(
+        "SELECT t.id,
+            (CASE
+                WHEN a.type = 'Credit' THEN
+                    CASE
+                        WHEN t.type = 'Expense' THEN t.amount
+                        WHEN t.type = 'Income' THEN t.amount
+                        WHEN t.type = 'Adjust' AND c.name = 'Add' THEN ABS(t.amount)
+                        WHEN t.type = 'Adjust' AND c.name = 'Subtract' THEN -ABS(t.amount)
+                        WHEN t.type = 'Transfer' THEN t.amount
+                        ELSE 0
+                    END
+                ELSE
+                    CASE
+                        WHEN t.type = 'Expense' THEN -ABS(t.amount)
+                        WHEN t.type = 'Income' THEN ABS(t.amount)
+                        WHEN t.type = 'Adjust' AND c.name = 'Add' THEN ABS(t.amount)
+                        WHEN t.type = 'Adjust' AND c.name = 'Subtract' THEN -ABS(t.amount)
+                        WHEN t.type = 'Transfer' THEN t.amount
+                        ELSE 0
+                    END
+                END
+            - IFNULL(ABS(t.fee), 0)) AS net_value
+        FROM transactions t
+        LEFT JOIN categories c ON t.category_id = c.id
+        LEFT JOIN accounts a ON t.account_id = a.id
+        WHERE t.status = 'permanent'"
+    ).map_err(|e| e.to_string())?;
+
+    let rows = stmt.query_map([], |row| {
+        Ok(TransactionNetValue { id: row.get(0)?, net_value: row.get(1)? })
+    }).map_err(|e| e.to_string())?;
+
+    let mut values = Vec::new();
+    for v in rows {
+        values.push(v.map_err(|e| e.to_string())?);
+    }
+    Ok(values)
+}
+
+/// One row of `v_transactions` (migration `m0031_v_transactions_collapsed`): a transfer's
+/// departure/arrival pair collapsed into a single logical record, so the frontend no longer
+/// has to reconstruct transfer pairs by matching `transfer_id`s itself.
+#[derive(Serialize)]
+pub struct NetTransaction {
+    pub id: i64,
+    pub date: String,
+    #[serde(rename = "type")]
+    pub transaction_type: String,
+    pub payee: String,
+    pub from_account_id: Option<i64>,
+    pub from_account_name: Option<String>,
+    pub to_account_id: Option<i64>,
+    pub to_account_name: Option<String>,
+    pub net_value: f64,
+    pub is_internal_transfer: bool,
+}
+
+/// Reads `v_transactions`: every transaction, with each transfer's two physical rows already
+/// collapsed into one logical record (`net_value` of 0 for a balanced internal transfer), so
+/// a caller summing `net_value` across the result gets the user's real net worth change
+/// without separately filtering out transfers.
+#[tauri::command]
+pub fn get_transactions_net(app: AppHandle, key_state: State<'_, DbKeyState>) -> Result<Vec<NetTransaction>, String> {
+    let conn = open_encrypted(&app, &key_state)?;
+
+    let mut stmt = conn.prepare(
+        "SELECT id, date, type, payee, from_account_id, from_account_name,
+                to_account_id, to_account_name, net_value, is_internal_transfer
+         FROM v_transactions
+         ORDER BY date DESC"
+    ).map_err(|e| e.to_string())?;
+
+    let rows = stmt.query_map([], |row| {
+        Ok(NetTransaction {
+            id: row.get(0)?,
+            date: row.get(1)?,
+            transaction_type: row.get(2)?,
+            payee: row.get(3)?,
+            from_account_id: row.get(4)?,
+            from_account_name: row.get(5)?,
+            to_account_id: row.get(6)?,
+            to_account_name: row.get(7)?,
+            net_value: row.get(8)?,
+            is_internal_transfer: row.get::<_, i64>(9)? != 0,
+        })
+    }).map_err(|e| e.to_string())?;
+
+    let mut values = Vec::new();
+    for v in rows {
+        values.push(v.map_err(|e| e.to_string())?);
+    }
+    Ok(values)
+}
+
+/// Escapes `query` for use inside an FTS5 double-quoted phrase by doubling embedded `"`
+/// characters, then wraps the whole thing in one phrase so the caller's raw text always matches
+/// literally instead of being parsed as FTS5 query syntax (`AND`/`OR`/column filters, etc.).
+fn escape_fts_query(query: &str) -> String {
+    format!("\"{}\"", query.replace('"', "\"\""))
+}
+
+/// Full-text search across `payee` and `notes` via `transactions_fts`
+/// ([`crate::migrations::m0036_transactions_fts`]), ranked by `bm25()` (most relevant first).
+/// An empty `query` returns no rows rather than matching everything, since FTS5 treats an empty
+/// phrase as a syntax error.
+#[tauri::command]
+pub fn search_transactions(app: AppHandle, key_state: State<'_, DbKeyState>, query: String) -> Result<Vec<Transaction>, String> {
+    if query.trim().is_empty() {
+        return Ok(Vec::new());
+    }
+
+    let conn = open_encrypted(&app, &key_state)?;
+
+    let mut stmt = conn.prepare(
+        "SELECT t.id, t.date, t.account_id, t.type, t.category_id, t.amount, t.payee, t.notes, t.transfer_id, t.to_account_id, t.created_at, t.attachment_path, t.fee, t.import_id, t.cleared_status, t.flag_color
+         FROM transactions_fts f
+         JOIN transactions t ON t.id = f.rowid
+         WHERE transactions_fts MATCH ?1
+         ORDER BY bm25(transactions_fts)"
+    ).map_err(|e| e.to_string())?;
+
+    let rows = stmt
+        .query_map(params![escape_fts_query(&query)], |row| {
+            let date: Option<String> = row.get(1)?;
+            let date = date.unwrap_or_else(|| Utc::now().format("%Y-%m-%d").to_string());
+
+            Ok(Transaction {
+                id: row.get(0)?,
+                date,
+                account_id: row.get(2)?,
+                transaction_type: row.get(3)?,
+                category_id: row.get(4)?,
+                amount: row.get(5)?,
+                payee: row.get(6)?,
+                notes: row.get(7)?,
+                transfer_id: row.get(8)?,
+                to_account_id: row.get(9).ok(),
+                created_at: row.get(10)?,
+                attachment_path: row.get(11).ok(),
+                fee: row.get(12).ok(),
+                import_id: row.get(13).ok(),
+                cleared_status: row.get(14).unwrap_or_else(|_| "uncleared".to_string()),
+                flag_color: row.get(15).ok(),
+            })
+        })
+        .map_err(|e| e.to_string())?;
+
+    let mut transactions = Vec::new();
+    for tr in rows {
+        transactions.push(tr.map_err(|e| e.to_string())?);
+    }
+    Ok(transactions)
+}
+
+#[tauri::command]
+pub fn get_transactions(app: AppHandle, key_state: State<'_, DbKeyState>) -> Result<Vec<Transaction>, String> {
+    let conn = open_encrypted(&app, &key_state)?;
+
     let mut stmt = conn
-        .prepare("SELECT t.id, t.date, t.account_id, t.type, t.category_id, t.amount, t.payee, t.notes, t.transfer_id, t.to_account_id, t.created_at, t.attachment_path FROM transactions t WHERE t.notes NOT LIKE '%[TEMP]%' ORDER BY t.date DESC")
+        .prepare("SELECT t.id, t.date, t.account_id, t.type, t.category_id, t.amount, t.payee, t.notes, t.transfer_id, t.to_account_id, t.created_at, t.attachment_path, t.fee, t.import_id, t.cleared_status, t.flag_color FROM transactions t WHERE t.status = 'permanent' ORDER BY t.date DESC")
         .map_err(|e| e.to_string())?;
-    
+
     let rows = stmt
         .query_map([], |row| {
             let date: Option<String> = row.get(1)?;
             let date = date.unwrap_or_else(|| {
                 Utc::now().format("%Y-%m-%d").to_string()
             });
-            
+
             Ok(Transaction {
                 id: row.get(0)?,
                 date,
@@ -51,10 +340,14 @@ pub fn get_transactions(app: AppHandle) -> Result<Vec<Transaction>, String> {
                 to_account_id: row.get(9).ok(),
                 created_at: row.get(10)?,
                 attachment_path: row.get(11).ok(),
+                fee: row.get(12).ok(),
+                import_id: row.get(13).ok(),
+                cleared_status: row.get(14).unwrap_or_else(|_| "uncleared".to_string()),
+                flag_color: row.get(15).ok(),
             })
         })
         .map_err(|e| e.to_string())?;
-    
+
     let mut transactions = Vec::new();
     for tr in rows {
         transactions.push(tr.map_err(|e| e.to_string())?);
@@ -63,9 +356,8 @@ pub fn get_transactions(app: AppHandle) -> Result<Vec<Transaction>, String> {
 }
 
 #[tauri::command]
-pub fn create_transaction(app: AppHandle, transaction: Transaction) -> Result<Vec<Transaction>, String> {
-    let path = get_db_path(&app);
-    let mut conn = Connection::open(&path).map_err(|e| e.to_string())?;
+pub fn create_transaction(app: AppHandle, key_state: State<'_, DbKeyState>, transaction: Transaction) -> Result<Vec<Transaction>, String> {
+    let mut conn = open_encrypted(&app, &key_state)?;
     
     // Create transaction
     let transaction_type = transaction.transaction_type.to_string();
@@ -103,7 +395,7 @@ pub fn create_transaction(app: AppHandle, transaction: Transaction) -> Result<Ve
             "".to_string()
         };
         tx.execute(
-            "INSERT INTO transactions (account_id, category_id, amount, date, payee, notes, type, transfer_id, to_account_id, attachment_path) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10)",
+            "INSERT INTO transactions (account_id, category_id, amount, date, payee, notes, type, transfer_id, to_account_id, attachment_path, fee) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11)",
             params![
                 transaction.account_id,
                 transaction.category_id,
@@ -114,7 +406,8 @@ pub fn create_transaction(app: AppHandle, transaction: Transaction) -> Result<Ve
                 transaction_type,
                 transfer_id,
                 to_id, // departure에만 저장
-                transaction.attachment_path.clone()
+                transaction.attachment_path.clone(),
+                transaction.fee
             ],
         ).map_err(|e| e.to_string())?;
 
@@ -132,7 +425,7 @@ pub fn create_transaction(app: AppHandle, transaction: Transaction) -> Result<Ve
                 "".to_string()
             };
             tx.execute(
-                "INSERT INTO transactions (account_id, category_id, amount, date, payee, notes, type, transfer_id, to_account_id, attachment_path) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10)",
+                "INSERT INTO transactions (account_id, category_id, amount, date, payee, notes, type, transfer_id, to_account_id, attachment_path, fee) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11)",
                 params![
                     to_id,
                     transaction.category_id,
@@ -143,7 +436,9 @@ pub fn create_transaction(app: AppHandle, transaction: Transaction) -> Result<Ve
                     transaction_type,
                     transfer_id,
                     transaction.account_id, // arrival에 출발 계좌 저장
-                    transaction.attachment_path.clone()
+                    transaction.attachment_path.clone(),
+                    // Fee is charged once on the departure leg only, not duplicated on arrival.
+                    None::<f64>
                 ],
             ).map_err(|e| {
                 e.to_string()
@@ -154,7 +449,7 @@ pub fn create_transaction(app: AppHandle, transaction: Transaction) -> Result<Ve
     } else {
         // Regular transaction
         conn.execute(
-            "INSERT INTO transactions (account_id, category_id, amount, date, payee, notes, type, attachment_path) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8)",
+            "INSERT INTO transactions (account_id, category_id, amount, date, payee, notes, type, attachment_path, fee) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9)",
             params![
                 transaction.account_id,
                 transaction.category_id,
@@ -163,43 +458,51 @@ pub fn create_transaction(app: AppHandle, transaction: Transaction) -> Result<Ve
                 payee,
                 notes,
                 transaction_type,
-                transaction.attachment_path.clone()
+                transaction.attachment_path.clone(),
+                transaction.fee
             ],
         ).map_err(|e| e.to_string())?;
     }
 
-    get_transactions(app)
+    // `accounts.balance` is a derived column (see `v_transactions_net`); keep it in sync
+    // instead of leaving it stale until the next `get_accounts` recomputation.
+    crate::accounts::recompute_balances(&conn)?;
+
+    get_transactions(app, key_state)
 }
 
-#[tauri::command]
-pub fn update_transaction(app: AppHandle, transaction: Transaction) -> Result<Vec<Transaction>, String> {
-    let path = get_db_path(&app);
-    let mut conn = Connection::open(&path).map_err(|e| e.to_string())?;
-    
+/// Applies a single transaction edit within an already-open `rusqlite::Transaction`, so
+/// callers that need to update several rows atomically (see `bulk_update_transactions`) can
+/// run them all inside one commit instead of opening a fresh connection per row. `force`
+/// must be set to edit a row whose `cleared_status` is `"reconciled"` — otherwise the edit
+/// is rejected so a closed statement period can't be silently altered.
+fn apply_update(tx: &rusqlite::Transaction, transaction: &Transaction, force: bool) -> Result<(), String> {
     // 기존 거래 정보 조회
-    let (old_type, old_transfer_id) = {
-        let mut sel = conn.prepare("SELECT type, transfer_id FROM transactions WHERE id = ?1").map_err(|e| e.to_string())?;
+    let (old_type, old_transfer_id, old_cleared_status) = {
+        let mut sel = tx.prepare("SELECT type, transfer_id, cleared_status FROM transactions WHERE id = ?1").map_err(|e| e.to_string())?;
         let mut rows = sel.query_map(params![transaction.id], |row| {
-            Ok((row.get::<_, String>(0)?, row.get::<_, Option<i64>>(1)?))
+            Ok((row.get::<_, String>(0)?, row.get::<_, Option<i64>>(1)?, row.get::<_, String>(2)?))
         }).map_err(|e| e.to_string())?;
         rows.next().ok_or("Transaction not found".to_string())?.map_err(|e| e.to_string())?
     };
-    
+
+    if old_cleared_status == "reconciled" && !force {
+        return Err(format!(
+            "Transaction {} is reconciled and cannot be edited without force",
+            transaction.id
+        ));
+    }
+
     // Transfer 거래의 notes만 수정하는 경우 특별 처리
     if old_type == "Transfer" && transaction.transaction_type == "Transfer" {
-        
         // Transfer 거래의 경우 양쪽 거래 모두 업데이트
         if let Some(transfer_id) = old_transfer_id {
-            let tx = conn.transaction().map_err(|e| e.to_string())?;
-            
             // 같은 transfer_id를 가진 모든 거래의 notes, to_account_id, attachment_path 업데이트
             let to_id = transaction.to_account_id;
             tx.execute(
                 "UPDATE transactions SET notes = ?1, to_account_id = ?2, attachment_path = ?3 WHERE transfer_id = ?4",
                 params![transaction.notes.clone().unwrap_or_default(), to_id, transaction.attachment_path.clone(), transfer_id]
             ).map_err(|e| e.to_string())?;
-            
-            tx.commit().map_err(|e| e.to_string())?;
         } else {
             // transfer_id가 없는 경우 해당 거래만 업데이트
             let clean_notes = if let Some(notes_str) = &transaction.notes {
@@ -211,7 +514,7 @@ pub fn update_transaction(app: AppHandle, transaction: Transaction) -> Result<Ve
             } else {
                 "".to_string()
             };
-            conn.execute(
+            tx.execute(
                 "UPDATE transactions SET notes = ?1, attachment_path = ?2 WHERE id = ?3",
                 params![clean_notes, transaction.attachment_path.clone(), transaction.id]
             ).map_err(|e| e.to_string())?;
@@ -219,18 +522,16 @@ pub fn update_transaction(app: AppHandle, transaction: Transaction) -> Result<Ve
     }
     // Transfer로 변경하는 경우 특별 처리
     else if old_type != "Transfer" && transaction.transaction_type == "Transfer" {
-        let tx = conn.transaction().map_err(|e| e.to_string())?;
-        
         // 기존 거래 삭제
         tx.execute("DELETE FROM transactions WHERE id = ?1", params![transaction.id]).map_err(|e| e.to_string())?;
-        
+
         // Transfer ID 생성
         let transfer_id = tx.query_row(
             "SELECT COALESCE(MAX(transfer_id), 0) + 1 FROM transactions",
             [],
             |r| r.get::<_, i64>(0)
         ).map_err(|e| e.to_string())?;
-        
+
         // 출발 계좌 트랜잭션 (음수)
         let departure_amount = -transaction.amount.abs();
         tx.execute(
@@ -248,17 +549,17 @@ pub fn update_transaction(app: AppHandle, transaction: Transaction) -> Result<Ve
                 transaction.attachment_path.clone()
             ],
         ).map_err(|e| e.to_string())?;
-        
+
         // 도착 계좌 ID 추출 (to_account_id 우선 사용)
         let to_account_id = transaction.to_account_id;
-        
+
         // 도착 계좌 트랜잭션 (양수)
         if let Some(to_id) = to_account_id {
             let arrival_amount = transaction.amount.abs();
-            
+
             // Notes에서 임시 정보 제거하고 사용자 입력만 유지
             let clean_notes = transaction.notes.clone();
-            
+
             tx.execute(
                 "INSERT INTO transactions (date, account_id, type, category_id, amount, payee, notes, transfer_id, to_account_id, attachment_path) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10)",
                 params![
@@ -279,8 +580,6 @@ pub fn update_transaction(app: AppHandle, transaction: Transaction) -> Result<Ve
         } else {
             // No to_account_id found, skipping arrival transaction
         }
-        
-        tx.commit().map_err(|e| e.to_string())?;
     }
     // Transfer 거래는 다른 타입으로 변경할 수 없음
     else if old_type == "Transfer" && transaction.transaction_type != "Transfer" {
@@ -288,8 +587,8 @@ pub fn update_transaction(app: AppHandle, transaction: Transaction) -> Result<Ve
     }
     // 일반 거래 업데이트
     else {
-        conn.execute(
-            "UPDATE transactions SET date = ?1, account_id = ?2, type = ?3, category_id = ?4, amount = ?5, payee = ?6, notes = ?7, attachment_path = ?8 WHERE id = ?9",
+        tx.execute(
+            "UPDATE transactions SET date = ?1, account_id = ?2, type = ?3, category_id = ?4, amount = ?5, payee = ?6, notes = ?7, attachment_path = ?8, fee = ?9 WHERE id = ?10",
             params![
                 transaction.date,
                 transaction.account_id,
@@ -299,19 +598,39 @@ pub fn update_transaction(app: AppHandle, transaction: Transaction) -> Result<Ve
                 transaction.payee,
                 transaction.notes.clone().unwrap_or_default(),
                 transaction.attachment_path.clone(),
+                transaction.fee,
                 transaction.id
             ],
         ).map_err(|e| e.to_string())?;
     }
-    
-    get_transactions(app)
+
+    Ok(())
 }
 
 #[tauri::command]
-pub fn delete_transaction(app: AppHandle, id: i64) -> Result<Vec<Transaction>, String> {
-    let path = get_db_path(&app);
-    let mut conn = Connection::open(&path).map_err(|e| e.to_string())?;
-    
+pub fn update_transaction(app: AppHandle, key_state: State<'_, DbKeyState>, transaction: Transaction, force: Option<bool>) -> Result<Vec<Transaction>, String> {
+    let mut conn = open_encrypted(&app, &key_state)?;
+
+    let tx = conn.transaction().map_err(|e| e.to_string())?;
+    apply_update(&tx, &transaction, force.unwrap_or(false))?;
+    tx.commit().map_err(|e| e.to_string())?;
+
+    crate::accounts::recompute_balances(&conn)?;
+
+    get_transactions(app, key_state)
+}
+
+#[tauri::command]
+pub fn delete_transaction(app: AppHandle, key_state: State<'_, DbKeyState>, id: i64, force: Option<bool>) -> Result<Vec<Transaction>, String> {
+    let mut conn = open_encrypted(&app, &key_state)?;
+
+    let cleared_status: String = conn
+        .query_row("SELECT cleared_status FROM transactions WHERE id = ?1", params![id], |row| row.get(0))
+        .map_err(|e| e.to_string())?;
+    if cleared_status == "reconciled" && !force.unwrap_or(false) {
+        return Err(format!("Transaction {} is reconciled and cannot be deleted without force", id));
+    }
+
     // Retrieve transaction info
     let (old_type, old_amount, acct_id, _old_category_id, _old_date, _old_payee) = {
         let mut sel = conn.prepare("SELECT type, amount, account_id, category_id, date, payee FROM transactions WHERE id = ?1").map_err(|e| e.to_string())?;
@@ -352,6 +671,7 @@ pub fn delete_transaction(app: AppHandle, id: i64) -> Result<Vec<Transaction>, S
             
             // Delete both transactions with the same transfer_id
             let _deleted_count = tx.execute("DELETE FROM transactions WHERE transfer_id = ?1", params![transfer_id]).map_err(|e| e.to_string())?;
+            crate::attachments::delete_orphaned_attachments(&tx)?;
         } else {
             // Legacy transfer handling (for old transfers without transfer_id)
             let other_transaction = if old_amount < 0.0 {
@@ -400,8 +720,9 @@ pub fn delete_transaction(app: AppHandle, id: i64) -> Result<Vec<Transaction>, S
             } else {
                 // Deleted single legacy transfer
             }
+            crate::attachments::delete_orphaned_attachments(&tx)?;
         }
-        
+
         tx.commit().map_err(|e| e.to_string())?;
     } else {
         // For single transaction, delete attachment file first if it exists
@@ -419,57 +740,108 @@ pub fn delete_transaction(app: AppHandle, id: i64) -> Result<Vec<Transaction>, S
         
         // Delete single transaction
         conn.execute("DELETE FROM transactions WHERE id = ?1", params![id]).map_err(|e| e.to_string())?;
+        crate::attachments::delete_orphaned_attachments(&conn)?;
     }
-    
-    get_transactions(app)
+
+    crate::accounts::recompute_balances(&conn)?;
+
+    get_transactions(app, key_state)
 }
 
 #[tauri::command]
-pub fn import_transactions(app: AppHandle, transactions: Vec<Transaction>) -> Result<ImportResult, String> {
-    let path = get_db_path(&app);
-    let mut conn = Connection::open(&path).map_err(|e| e.to_string())?;
-    
-    // Collect existing transactions for duplicate checking
-    let (existing_keys, transfer_keys) = {
+pub fn import_transactions(app: AppHandle, key_state: State<'_, DbKeyState>, mut transactions: Vec<Transaction>, source: Option<String>) -> Result<ImportResult, String> {
+    let mut conn = open_encrypted(&app, &key_state)?;
+    let source = source.unwrap_or_else(|| "manual".to_string());
+
+    // Normalize raw bank payee strings before dedup/validation sees them, so the dedup key and
+    // the stored row both reflect the canonical payee rather than whatever the source happened
+    // to write. Applies to every caller (manual paste, CSV import, YNAB pull) since they all
+    // funnel through this one command.
+    let aliases = crate::payee_aliases::list_payee_aliases(app.clone(), key_state)?;
+    crate::payee_aliases::apply_aliases(&mut transactions, &aliases);
+
+    // Collect existing rows for duplicate checking: import_id is authoritative when present;
+    // the old (date, amount_in_cents, payee) heuristic is only a fallback for legacy rows
+    // imported before this column existed.
+    let (existing_import_ids, existing_keys, transfer_keys) = {
+        let mut existing_import_ids = HashSet::new();
         let mut existing_keys = HashSet::new();
         let mut transfer_keys = HashSet::new();
-        let mut stmt = conn.prepare("SELECT date, amount, payee, type FROM transactions").map_err(|e| e.to_string())?;
+        let mut stmt = conn.prepare("SELECT date, amount, payee, type, import_id FROM transactions").map_err(|e| e.to_string())?;
         let rows = stmt.query_map([], |row| {
             let date: String = row.get(0)?;
             let amount: f64 = row.get(1)?;
             let payee: String = row.get(2)?;
             let ttype: String = row.get(3)?;
+            let import_id: Option<String> = row.get(4)?;
             // 더 정확한 중복 체크를 위해 센트 단위로 반올림
             let cents = (amount * 100.0).round() as i64;
-            // Case-insensitive type check
-            Ok(if ttype.to_lowercase() == "transfer" {
-                TransactionKey::Transfer(date, cents)
+            let key = if import_id.is_some() {
+                None
+            } else if ttype.to_lowercase() == "transfer" {
+                Some(TransactionKey::Transfer(date, cents))
             } else {
-                TransactionKey::Regular(date, cents, payee)
-            })
+                Some(TransactionKey::Regular(date, cents, payee))
+            };
+            Ok((import_id, key))
         }).map_err(|e| e.to_string())?;
-        
+
         for row in rows {
-            match row.map_err(|e| e.to_string())? {
-                TransactionKey::Regular(date, cents, payee) => {
+            let (import_id, key) = row.map_err(|e| e.to_string())?;
+            if let Some(import_id) = import_id {
+                existing_import_ids.insert(import_id);
+                continue;
+            }
+            match key {
+                Some(TransactionKey::Regular(date, cents, payee)) => {
                     existing_keys.insert((date, cents, payee));
                 },
-                TransactionKey::Transfer(date, cents) => {
+                Some(TransactionKey::Transfer(date, cents)) => {
                     transfer_keys.insert((date, cents));
                 }
+                None => {}
             }
         }
-        (existing_keys, transfer_keys)
+        (existing_import_ids, existing_keys, transfer_keys)
     };
-    
+
+    // `amount_cents:date` occurrence counters scoped to this batch, so two identical same-day
+    // charges in one CSV get distinct import_ids (`...:1`, `...:2`) instead of colliding.
+    let mut occurrence_counts: HashMap<(i64, String), i64> = HashMap::new();
+
+    let schema = transaction_schema();
+    let compiled_schema = JSONSchema::compile(&schema).map_err(|e| e.to_string())?;
+
     let tx = conn.transaction().map_err(|e| e.to_string())?;
+    tx.execute(
+        "INSERT INTO import_sessions (source) VALUES (?1)",
+        params![source],
+    ).map_err(|e| e.to_string())?;
+    let session_id = tx.last_insert_rowid();
+
     let mut imported_count = 0;
     let mut duplicate_count = 0;
     let mut imported_ids = Vec::new();
-    
+    let mut errors = Vec::new();
+
     // Import new transactions
-    for t in transactions {
+    for (row_index, t) in transactions.into_iter().enumerate() {
+        let instance = serde_json::to_value(&t).map_err(|e| e.to_string())?;
+        if let Err(validation_errors) = compiled_schema.validate(&instance) {
+            errors.extend(validation_errors.map(|e| describe_validation_error(row_index, e)));
+            continue;
+        }
+
         let cents = (t.amount * 100.0).round() as i64;
+        let occurrence = occurrence_counts.entry((cents, t.date.clone())).or_insert(0);
+        *occurrence += 1;
+        let import_id = format!("{}:{}:{}", cents, t.date, *occurrence);
+
+        if existing_import_ids.contains(&import_id) {
+            duplicate_count += 1;
+            continue;
+        }
+        // Legacy fallback for rows imported before import_id existed.
         let key = (t.date.clone(), cents, t.payee.clone());
         if existing_keys.contains(&key) {
             duplicate_count += 1;
@@ -481,7 +853,7 @@ pub fn import_transactions(app: AppHandle, transactions: Vec<Transaction>) -> Re
             continue;
         }
         tx.execute(
-            "INSERT INTO transactions (date, account_id, type, category_id, amount, payee, notes, transfer_id, attachment_path) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9)",
+            "INSERT INTO transactions (date, account_id, type, category_id, amount, payee, notes, transfer_id, attachment_path, fee, import_id, import_session_id) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12)",
             params![
                 t.date,
                 t.account_id,
@@ -491,21 +863,25 @@ pub fn import_transactions(app: AppHandle, transactions: Vec<Transaction>) -> Re
                 t.payee,
                 t.notes.clone().unwrap_or_default(),
                 None::<i64>,
-                t.attachment_path.clone()
+                t.attachment_path.clone(),
+                t.fee,
+                import_id,
+                session_id
             ],
         ).map_err(|e| e.to_string())?;
         // Don't add to sets to allow duplicates within the same import batch
         imported_count += 1;
         imported_ids.push(tx.last_insert_rowid());
     }
-    
+
     tx.commit().map_err(|e| e.to_string())?;
-    
+    crate::accounts::recompute_balances(&conn)?;
+
     // Get only the imported transactions using the original connection
     let mut result = Vec::new();
     for id in imported_ids {
         let transaction = conn.query_row(
-            "SELECT id, date, account_id, type, category_id, amount, payee, notes, transfer_id, to_account_id, created_at, attachment_path FROM transactions WHERE id = ?1",
+            "SELECT id, date, account_id, type, category_id, amount, payee, notes, transfer_id, to_account_id, created_at, attachment_path, fee, import_id, cleared_status, flag_color FROM transactions WHERE id = ?1",
             params![id],
             |row| Ok(Transaction {
                 id: row.get(0)?, date: row.get(1)?, account_id: row.get(2)?,
@@ -514,31 +890,99 @@ pub fn import_transactions(app: AppHandle, transactions: Vec<Transaction>) -> Re
                 to_account_id: row.get(9).ok(),
                 created_at: row.get(10)?,
                 attachment_path: row.get(11).ok(),
+                fee: row.get(12).ok(),
+                import_id: row.get(13).ok(),
+                cleared_status: row.get(14).unwrap_or_else(|_| "uncleared".to_string()),
+                flag_color: row.get(15).ok(),
             }),
         ).map_err(|e| e.to_string())?;
         result.push(transaction);
     }
-    
-    // Add import statistics to the first transaction (temporary storage)
-    if !result.is_empty() {
-        result[0].notes = Some(format!("IMPORT_STATS: imported={}, duplicates={}", imported_count, duplicate_count));
-    }
-    
+
+    conn.execute(
+        "UPDATE import_sessions SET imported_count = ?1, duplicate_count = ?2 WHERE id = ?3",
+        params![imported_count, duplicate_count, session_id],
+    ).map_err(|e| e.to_string())?;
+
     Ok(ImportResult {
         imported: result,
         imported_count,
         duplicate_count,
+        errors,
+        session_id,
     })
 }
 
 #[tauri::command]
-pub fn bulk_update_transactions(app: AppHandle, updates: Vec<(i64, Value)>) -> Result<Vec<Transaction>, String> {
+pub fn list_import_sessions(app: AppHandle, key_state: State<'_, DbKeyState>) -> Result<Vec<ImportSession>, String> {
+    let conn = open_encrypted(&app, &key_state)?;
+
+    let mut stmt = conn.prepare(
+        "SELECT id, source, created_at, imported_count, duplicate_count FROM import_sessions ORDER BY created_at DESC"
+    ).map_err(|e| e.to_string())?;
+    let rows = stmt.query_map([], |row| {
+        Ok(ImportSession {
+            id: row.get(0)?,
+            source: row.get(1)?,
+            created_at: row.get(2)?,
+            imported_count: row.get(3)?,
+            duplicate_count: row.get(4)?,
+        })
+    }).map_err(|e| e.to_string())?;
+
+    let mut sessions = Vec::new();
+    for s in rows {
+        sessions.push(s.map_err(|e| e.to_string())?);
+    }
+    Ok(sessions)
+}
+
+/// Deletes every transaction recorded under `session_id` (and its `import_sessions` row)
+/// atomically, cleaning up attachment files the same way `delete_transaction` does, so a whole
+/// "oops, wrong file" import batch rolls back in one call instead of deleting rows one at a time.
+#[tauri::command]
+pub fn undo_import(app: AppHandle, key_state: State<'_, DbKeyState>, session_id: i64) -> Result<(), String> {
+    let mut conn = open_encrypted(&app, &key_state)?;
+
+    let attachment_paths: Vec<String> = {
+        let mut stmt = conn.prepare(
+            "SELECT attachment_path FROM transactions WHERE import_session_id = ?1 AND attachment_path IS NOT NULL"
+        ).map_err(|e| e.to_string())?;
+        stmt.query_map(params![session_id], |row| row.get::<_, String>(0))
+            .map_err(|e| e.to_string())?
+            .collect::<Result<_, _>>()
+            .map_err(|e| e.to_string())?
+    };
+    for path in attachment_paths {
+        if !path.is_empty() {
+            let _ = delete_transaction_attachment(app.clone(), path);
+        }
+    }
+
+    let tx = conn.transaction().map_err(|e| e.to_string())?;
+    tx.execute("DELETE FROM transactions WHERE import_session_id = ?1", params![session_id])
+        .map_err(|e| e.to_string())?;
+    tx.execute("DELETE FROM import_sessions WHERE id = ?1", params![session_id])
+        .map_err(|e| e.to_string())?;
+    crate::attachments::delete_orphaned_attachments(&tx)?;
+    tx.commit().map_err(|e| e.to_string())?;
+
+    crate::accounts::recompute_balances(&conn)?;
+    Ok(())
+}
+
+/// Applies every `(id, changes)` pair inside a single connection and a single
+/// `conn.transaction()`, so a failure partway through (a bad id, an illegal Transfer
+/// conversion) rolls back every row in the batch instead of leaving some updated and some not.
+#[tauri::command]
+pub fn bulk_update_transactions(app: AppHandle, key_state: State<'_, DbKeyState>, updates: Vec<(i64, Value)>) -> Result<Vec<Transaction>, String> {
+    let mut conn = open_encrypted(&app, &key_state)?;
+    let tx = conn.transaction().map_err(|e| e.to_string())?;
+
     for (id, changes) in updates {
         // Fetch existing transaction
-        let path = get_db_path(&app);
-        let conn = Connection::open(&path).map_err(|e| e.to_string())?;
-        let existing: Transaction = conn.query_row(
-            "SELECT id, date, account_id, type, category_id, amount, payee, notes, transfer_id, to_account_id, created_at, attachment_path FROM transactions WHERE id = ?1",
+        let existing: Transaction = tx.query_row(
+            "SELECT id, date, account_id, type, category_id, amount, payee, notes, transfer_id, to_account_id, created_at, attachment_path, fee, import_id, cleared_status, flag_color FROM transactions WHERE id = ?1",
             params![id],
             |row| Ok(Transaction {
                 id: row.get(0)?, date: row.get(1)?, account_id: row.get(2)?,
@@ -547,9 +991,13 @@ pub fn bulk_update_transactions(app: AppHandle, updates: Vec<(i64, Value)>) -> R
                 to_account_id: row.get(9).ok(),
                 created_at: row.get(10)?,
                 attachment_path: row.get(11).ok(),
+                fee: row.get(12).ok(),
+                import_id: row.get(13).ok(),
+                cleared_status: row.get(14).unwrap_or_else(|_| "uncleared".to_string()),
+                flag_color: row.get(15).ok(),
             }),
         ).map_err(|e| e.to_string())?;
-        
+
         // Merge changes
         let mut updated = existing.clone();
         if let Some(v) = changes.get("date").and_then(|v| v.as_str()) { updated.date = v.to_string(); }
@@ -559,29 +1007,134 @@ pub fn bulk_update_transactions(app: AppHandle, updates: Vec<(i64, Value)>) -> R
         if let Some(v) = changes.get("amount").and_then(|v| v.as_f64()) { updated.amount = v; }
         if let Some(v) = changes.get("payee").and_then(|v| v.as_str()) { updated.payee = v.to_string(); }
         if let Some(v) = changes.get("notes").and_then(|v| v.as_str()) { updated.notes = Some(v.to_string()); }
-        
-        // Apply update
-        update_transaction(app.clone(), updated)?;
+        if let Some(v) = changes.get("fee").and_then(|v| v.as_f64()) { updated.fee = Some(v); }
+        let force = changes.get("force").and_then(|v| v.as_bool()).unwrap_or(false);
+        let cleared_status = changes.get("cleared_status").and_then(|v| v.as_str()).map(|s| s.to_string());
+
+        // Apply update within the shared transaction
+        apply_update(&tx, &updated, force)?;
+
+        // `apply_update` doesn't touch `cleared_status` (it's toggled by the reconcile flow,
+        // not the regular edit form), so a bulk cleared/uncleared toggle is applied separately.
+        if let Some(cleared_status) = cleared_status {
+            tx.execute(
+                "UPDATE transactions SET cleared_status = ?1 WHERE id = ?2",
+                params![cleared_status, id],
+            ).map_err(|e| e.to_string())?;
+        }
     }
-    get_transactions(app)
-} 
 
+    tx.commit().map_err(|e| e.to_string())?;
+    crate::accounts::recompute_balances(&conn)?;
+
+    get_transactions(app, key_state)
+}
+
+/// Tolerance below which a reconciliation's computed balance is considered to match
+/// `target_balance`. Guards against floating point noise in the running `SUM`, not real
+/// discrepancies (same epsilon as `validate_transfers`/`verify_balance_assertions`).
+const RECONCILE_EPSILON: f64 = 0.005;
+
+/// Marks `ids` as `"reconciled"` against a statement, the way YNAB-style reconcile flows do:
+/// the sum of their net effects (via `v_transactions_net`) plus whatever was already
+/// reconciled on the account must equal `target_balance`, or nothing is written and the
+/// discrepancy is reported back to the caller.
 #[tauri::command]
-pub fn get_transaction_by_id(app: AppHandle, id: i64) -> Result<Option<Transaction>, String> {
-    let path = get_db_path(&app);
-    let conn = Connection::open(path).map_err(|e| e.to_string())?;
-    
+pub fn reconcile_transactions(app: AppHandle, key_state: State<'_, DbKeyState>, ids: Vec<i64>, target_balance: f64) -> Result<Vec<Transaction>, String> {
+    if ids.is_empty() {
+        return Err("No transactions selected to reconcile".to_string());
+    }
+
+    let mut conn = open_encrypted(&app, &key_state)?;
+    let tx = conn.transaction().map_err(|e| e.to_string())?;
+
+    let placeholders = ids.iter().map(|_| "?").collect::<Vec<_>>().join(",");
+
+    let account_ids: Vec<i64> = {
+        let sql = format!("SELECT DISTINCT account_id FROM transactions WHERE id IN ({})", placeholders);
+        let mut stmt = tx.prepare(&sql).map_err(|e| e.to_string())?;
+        let params: Vec<&dyn rusqlite::ToSql> = ids.iter().map(|id| id as &dyn rusqlite::ToSql).collect();
+        stmt.query_map(params.as_slice(), |row| row.get(0))
+            .map_err(|e| e.to_string())?
+            .collect::<Result<_, _>>()
+            .map_err(|e| e.to_string())?
+    };
+    if account_ids.len() != 1 {
+        return Err("All reconciled transactions must belong to the same account".to_string());
+    }
+    let account_id = account_ids[0];
+
+    let prior_reconciled: f64 = tx.query_row(
+        "SELECT IFNULL(SUM(v.net_value), 0) FROM v_transactions_net v
+         JOIN transactions t ON t.id = v.transaction_id
+         WHERE t.account_id = ?1 AND t.cleared_status = 'reconciled'",
+        params![account_id],
+        |row| row.get(0),
+    ).map_err(|e| e.to_string())?;
+
+    let batch_net: f64 = {
+        let sql = format!(
+            "SELECT IFNULL(SUM(net_value), 0) FROM v_transactions_net WHERE transaction_id IN ({})",
+            placeholders
+        );
+        let mut stmt = tx.prepare(&sql).map_err(|e| e.to_string())?;
+        let params: Vec<&dyn rusqlite::ToSql> = ids.iter().map(|id| id as &dyn rusqlite::ToSql).collect();
+        stmt.query_row(params.as_slice(), |row| row.get(0)).map_err(|e| e.to_string())?
+    };
+
+    let computed = prior_reconciled + batch_net;
+    if (computed - target_balance).abs() > RECONCILE_EPSILON {
+        return Err(format!(
+            "Reconciliation mismatch: computed balance {:.2} does not match target balance {:.2} (delta {:.2})",
+            computed, target_balance, computed - target_balance
+        ));
+    }
+
+    let sql = format!("UPDATE transactions SET cleared_status = 'reconciled' WHERE id IN ({})", placeholders);
+    let update_params: Vec<&dyn rusqlite::ToSql> = ids.iter().map(|id| id as &dyn rusqlite::ToSql).collect();
+    tx.execute(&sql, update_params.as_slice()).map_err(|e| e.to_string())?;
+
+    tx.commit().map_err(|e| e.to_string())?;
+    crate::accounts::recompute_balances(&conn)?;
+
+    get_transactions(app, key_state)
+}
+
+/// Sets `flag_color` on the given rows. Unlike `cleared_status`, the flag is a free-form
+/// highlight label the UI can apply regardless of clearing state, so it's allowed on
+/// reconciled rows without `force`.
+#[tauri::command]
+pub fn set_flag(app: AppHandle, key_state: State<'_, DbKeyState>, ids: Vec<i64>, color: Option<String>) -> Result<Vec<Transaction>, String> {
+    if ids.is_empty() {
+        return Err("No transactions selected to flag".to_string());
+    }
+
+    let conn = open_encrypted(&app, &key_state)?;
+
+    let placeholders = ids.iter().map(|_| "?").collect::<Vec<_>>().join(",");
+    let sql = format!("UPDATE transactions SET flag_color = ? WHERE id IN ({})", placeholders);
+    let mut params: Vec<&dyn rusqlite::ToSql> = vec![&color];
+    params.extend(ids.iter().map(|id| id as &dyn rusqlite::ToSql));
+    conn.execute(&sql, params.as_slice()).map_err(|e| e.to_string())?;
+
+    get_transactions(app, key_state)
+}
+
+#[tauri::command]
+pub fn get_transaction_by_id(app: AppHandle, key_state: State<'_, DbKeyState>, id: i64) -> Result<Option<Transaction>, String> {
+    let conn = open_encrypted(&app, &key_state)?;
+
     let mut stmt = conn
-        .prepare("SELECT t.id, t.date, t.account_id, t.type, t.category_id, t.amount, t.payee, t.notes, t.transfer_id, t.to_account_id, t.created_at, t.attachment_path FROM transactions t WHERE t.id = ?1")
+        .prepare("SELECT t.id, t.date, t.account_id, t.type, t.category_id, t.amount, t.payee, t.notes, t.transfer_id, t.to_account_id, t.created_at, t.attachment_path, t.fee, t.import_id, t.cleared_status, t.flag_color FROM transactions t WHERE t.id = ?1")
         .map_err(|e| e.to_string())?;
-    
+
     let result = stmt
         .query_row(params![id], |row| {
             let date: Option<String> = row.get(1)?;
             let date = date.unwrap_or_else(|| {
                 Utc::now().format("%Y-%m-%d").to_string()
             });
-            
+
             Ok(Transaction {
                 id: row.get(0)?,
                 date,
@@ -595,6 +1148,10 @@ pub fn get_transaction_by_id(app: AppHandle, id: i64) -> Result<Option<Transacti
                 to_account_id: row.get(9).ok(),
                 created_at: row.get(10)?,
                 attachment_path: row.get(11).ok(),
+                fee: row.get(12).ok(),
+                import_id: row.get(13).ok(),
+                cleared_status: row.get(14).unwrap_or_else(|_| "uncleared".to_string()),
+                flag_color: row.get(15).ok(),
             })
         });
     
@@ -606,10 +1163,9 @@ pub fn get_transaction_by_id(app: AppHandle, id: i64) -> Result<Option<Transacti
 }
 
 #[tauri::command]
-pub fn get_account_name_by_id(app: AppHandle, account_id: i64) -> Result<String, String> {
-    let path = get_db_path(&app);
-    let conn = Connection::open(path).map_err(|e| e.to_string())?;
-    
+pub fn get_account_name_by_id(app: AppHandle, key_state: State<'_, DbKeyState>, account_id: i64) -> Result<String, String> {
+    let conn = open_encrypted(&app, &key_state)?;
+
     let mut stmt = conn
         .prepare("SELECT name FROM accounts WHERE id = ?1")
         .map_err(|e| e.to_string())?;
@@ -622,7 +1178,7 @@ pub fn get_account_name_by_id(app: AppHandle, account_id: i64) -> Result<String,
 }
 
 #[tauri::command]
-pub fn save_transaction_attachment(app: AppHandle, file_name: String, base64: String, transaction_id: Option<i64>, 
+pub fn save_transaction_attachment(app: AppHandle, key_state: State<'_, DbKeyState>, file_name: String, base64: String, transaction_id: Option<i64>,
                                  transaction_data: Option<serde_json::Value>) -> Result<String, String> {
     use base64::engine::general_purpose::STANDARD;
     use base64::Engine;
@@ -649,7 +1205,7 @@ pub fn save_transaction_attachment(app: AppHandle, file_name: String, base64: St
 
     let new_file_name = if let Some(id) = transaction_id {
         // 기존 트랜잭션: Transaction 정보 가져오기
-        let transaction = get_transaction_by_id(app.clone(), id)?;
+        let transaction = get_transaction_by_id(app.clone(), key_state.clone(), id)?;
         if let Some(txn) = transaction {
             // 날짜를 YYYYMMDD 형식으로 변환
             let date_parts: Vec<&str> = txn.date.split('-').collect();
@@ -697,7 +1253,7 @@ pub fn save_transaction_attachment(app: AppHandle, file_name: String, base64: St
     // Account 서브폴더 생성
     let account_subfolder = if let Some(id) = transaction_id {
         // 기존 트랜잭션: DB에서 정보 가져오기
-        let transaction = get_transaction_by_id(app.clone(), id)?;
+        let transaction = get_transaction_by_id(app.clone(), key_state.clone(), id)?;
         if let Some(txn) = transaction {
             // Transfer 거래인 경우 도착 계좌(to_account_id)를 사용, 그렇지 않으면 출발 계좌(account_id) 사용
             let target_account_id = if txn.transaction_type == "Transfer" {
@@ -706,8 +1262,7 @@ pub fn save_transaction_attachment(app: AppHandle, file_name: String, base64: St
                     to_id
                 } else {
                     // 같은 transfer_id를 가진 다른 트랜잭션에서 도착 계좌 찾기
-                    let path = get_db_path(&app);
-                    let conn = Connection::open(path).map_err(|e| e.to_string())?;
+                    let conn = open_encrypted(&app, &key_state)?;
                     
                     if let Some(transfer_id) = txn.transfer_id {
                         // Transfer 거래에서 양수 금액(도착 거래)을 가진 거래의 account_id를 찾기
@@ -730,7 +1285,7 @@ pub fn save_transaction_attachment(app: AppHandle, file_name: String, base64: St
                 txn.account_id
             };
             
-            let account_name = get_account_name_by_id(app.clone(), target_account_id)?;
+            let account_name = get_account_name_by_id(app.clone(), key_state, target_account_id)?;
             // 특수문자 제거 및 안전한 폴더명 생성
             let safe_account_name = account_name
                 .chars()
@@ -760,7 +1315,7 @@ pub fn save_transaction_attachment(app: AppHandle, file_name: String, base64: St
             account_id
         };
         
-        let account_name = get_account_name_by_id(app.clone(), target_account_id)?;
+        let account_name = get_account_name_by_id(app.clone(), key_state, target_account_id)?;
         // 특수문자 제거 및 안전한 폴더명 생성
         let safe_account_name = account_name
             .chars()
@@ -837,21 +1392,21 @@ pub fn open_transaction_attachment(_app: AppHandle, attachment_path: String) ->
     }
 } 
 
+/// Creates a not-yet-confirmed transaction (or transfer pair), marked `status = 'temp'`
+/// ([`crate::migrations`]'s `m0042_transaction_status`) instead of the old `"[TEMP] "`
+/// notes prefix — `notes` is stored as given, with no metadata smuggled into it.
 #[tauri::command]
-pub fn create_temp_transaction(app: AppHandle, transaction: Transaction) -> Result<i64, String> {
-    let path = get_db_path(&app);
-    let mut conn = Connection::open(&path).map_err(|e| e.to_string())?;
-    
-    // Create temporary transaction with a special flag
+pub fn create_temp_transaction(app: AppHandle, key_state: State<'_, DbKeyState>, transaction: Transaction) -> Result<i64, String> {
+    let mut conn = open_encrypted(&app, &key_state)?;
+
     let transaction_type = transaction.transaction_type.to_string();
     let amount = transaction.amount;
     let payee = transaction.payee.to_string();
-    let notes = transaction.notes.clone();
+    let notes = transaction.notes.clone().unwrap_or_default();
 
     if transaction_type == "Transfer" {
         let tx = conn.transaction().map_err(|e| e.to_string())?;
-        
-        // Generate transfer_id if not provided
+
         let transfer_id = match transaction.transfer_id {
             Some(id) => id,
             None => {
@@ -862,171 +1417,113 @@ pub fn create_temp_transaction(app: AppHandle, transaction: Transaction) -> Resu
             }
         };
 
-        // Use to_account_id directly for arrival transaction
         let to_id = transaction.to_account_id;
-
-        // Create departure transaction
         let departure_amount = -amount.abs();
-        // notes에서 [TO_ACCOUNT_ID:x] 메타데이터 제거
-        let clean_notes = if let Some(notes_str) = &notes {
-            if let Some(end) = notes_str.find(']') {
-                notes_str[end+1..].trim().to_string()
-            } else {
-                notes_str.clone()
-            }
-        } else {
-            "".to_string()
-        };
-        
-        // Add temporary flag to notes
-        let temp_notes = format!("[TEMP] {}", clean_notes);
-        
+
         tx.execute(
-            "INSERT INTO transactions (account_id, category_id, amount, date, payee, notes, type, transfer_id, to_account_id, attachment_path) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10)",
+            "INSERT INTO transactions (account_id, category_id, amount, date, payee, notes, type, transfer_id, to_account_id, attachment_path, status) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, 'temp')",
             params![
                 transaction.account_id,
                 transaction.category_id,
                 departure_amount,
                 transaction.date,
                 payee,
-                &temp_notes,
+                &notes,
                 transaction_type,
                 transfer_id,
-                to_id, // departure에만 저장
+                to_id,
                 transaction.attachment_path.clone()
             ],
         ).map_err(|e| e.to_string())?;
 
-        // Create arrival transaction if target account found
         if let Some(to_id) = to_id {
             let arrival_amount = amount.abs();
-            // notes에서 [TO_ACCOUNT_ID:x] 메타데이터 제거
-            let arrival_clean_notes = if let Some(notes_str) = &notes {
-                if let Some(end) = notes_str.find(']') {
-                    notes_str[end+1..].trim().to_string()
-                } else {
-                    notes_str.clone()
-                }
-            } else {
-                "".to_string()
-            };
-            
-            // Add temporary flag to notes
-            let arrival_temp_notes = format!("[TEMP] {}", arrival_clean_notes);
-            
             tx.execute(
-                "INSERT INTO transactions (account_id, category_id, amount, date, payee, notes, type, transfer_id, to_account_id, attachment_path) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10)",
+                "INSERT INTO transactions (account_id, category_id, amount, date, payee, notes, type, transfer_id, to_account_id, attachment_path, status) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, 'temp')",
                 params![
                     to_id,
                     transaction.category_id,
                     arrival_amount,
                     transaction.date,
                     payee,
-                    &arrival_temp_notes,
+                    &notes,
                     transaction_type,
                     transfer_id,
-                    transaction.account_id, // arrival에 출발 계좌 저장
+                    transaction.account_id,
                     transaction.attachment_path.clone()
                 ],
-            ).map_err(|e| {
-                e.to_string()
-            })?;
+            ).map_err(|e| e.to_string())?;
         }
-        let commit_result = tx.commit();
-        commit_result.map_err(|e| e.to_string())?;
-        
-        // Get the last insert rowid from the connection after commit
+        tx.commit().map_err(|e| e.to_string())?;
+
         Ok(conn.last_insert_rowid())
     } else {
-        // Regular transaction
-        // Add temporary flag to notes
-        let temp_notes = format!("[TEMP] {}", notes.unwrap_or_default());
-        
         conn.execute(
-            "INSERT INTO transactions (account_id, category_id, amount, date, payee, notes, type, attachment_path) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8)",
+            "INSERT INTO transactions (account_id, category_id, amount, date, payee, notes, type, attachment_path, status) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, 'temp')",
             params![
                 transaction.account_id,
                 transaction.category_id,
                 amount,
                 transaction.date,
                 payee,
-                temp_notes,
+                notes,
                 transaction_type,
                 transaction.attachment_path.clone()
             ],
         ).map_err(|e| e.to_string())?;
-        
+
         Ok(conn.last_insert_rowid())
     }
 }
 
 #[tauri::command]
-pub fn delete_temp_transaction(app: AppHandle, id: i64) -> Result<(), String> {
-    let path = get_db_path(&app);
-    let conn = Connection::open(&path).map_err(|e| e.to_string())?;
-    
-    // Check if this is a temporary transaction
+pub fn delete_temp_transaction(app: AppHandle, key_state: State<'_, DbKeyState>, id: i64) -> Result<(), String> {
+    let conn = open_encrypted(&app, &key_state)?;
+
     let (is_temp, transfer_id) = {
-        let mut stmt = conn.prepare("SELECT notes, transfer_id FROM transactions WHERE id = ?1")
+        let mut stmt = conn.prepare("SELECT status, transfer_id FROM transactions WHERE id = ?1")
             .map_err(|e| e.to_string())?;
         let mut rows = stmt.query_map(params![id], |row| {
-            Ok((row.get::<_, Option<String>>(0)?, row.get::<_, Option<i64>>(1)?))
+            Ok((row.get::<_, String>(0)?, row.get::<_, Option<i64>>(1)?))
         }).map_err(|e| e.to_string())?;
         let row = rows.next().ok_or("Transaction not found".to_string())?.map_err(|e| e.to_string())?;
-        (row.0.map(|notes| notes.contains("[TEMP]")).unwrap_or(false), row.1)
+        (row.0 == "temp", row.1)
     };
-    
+
     if !is_temp {
         return Err("Not a temporary transaction".to_string());
     }
-    
-    // Delete temporary transaction
+
     if let Some(transfer_id) = transfer_id {
-        // Delete both sides of the transfer
-        conn.execute("DELETE FROM transactions WHERE transfer_id = ?1 AND notes LIKE '%[TEMP]%'", params![transfer_id])
+        conn.execute("DELETE FROM transactions WHERE transfer_id = ?1 AND status = 'temp'", params![transfer_id])
             .map_err(|e| e.to_string())?;
     } else {
-        // Delete single transaction
         conn.execute("DELETE FROM transactions WHERE id = ?1", params![id])
             .map_err(|e| e.to_string())?;
     }
-    
+
     Ok(())
 }
 
 #[tauri::command]
-pub fn update_temp_transaction_to_permanent(app: AppHandle, id: i64) -> Result<(), String> {
-    let path = get_db_path(&app);
-    let conn = Connection::open(&path).map_err(|e| e.to_string())?;
-    
-    // Remove [TEMP] flag from notes
-    let (notes, transfer_id) = {
-        let mut stmt = conn.prepare("SELECT notes, transfer_id FROM transactions WHERE id = ?1")
-            .map_err(|e| e.to_string())?;
-        let mut rows = stmt.query_map(params![id], |row| {
-            Ok((row.get::<_, Option<String>>(0)?, row.get::<_, Option<i64>>(1)?))
-        }).map_err(|e| e.to_string())?;
-        let row = rows.next().ok_or("Transaction not found".to_string())?.map_err(|e| e.to_string())?;
-        (row.0, row.1)
-    };
-    
+pub fn update_temp_transaction_to_permanent(app: AppHandle, key_state: State<'_, DbKeyState>, id: i64) -> Result<(), String> {
+    let conn = open_encrypted(&app, &key_state)?;
+
+    let transfer_id: Option<i64> = conn
+        .query_row("SELECT transfer_id FROM transactions WHERE id = ?1", params![id], |row| row.get(0))
+        .map_err(|e| e.to_string())?;
+
     if let Some(transfer_id) = transfer_id {
-        // Update both sides of the transfer
-        let clean_notes = notes.map(|n| n.replace("[TEMP] ", "")).unwrap_or_default();
         conn.execute(
-            "UPDATE transactions SET notes = ?1 WHERE transfer_id = ?2 AND notes LIKE '%[TEMP]%'",
-            params![clean_notes, transfer_id]
+            "UPDATE transactions SET status = 'permanent' WHERE transfer_id = ?1 AND status = 'temp'",
+            params![transfer_id]
         ).map_err(|e| e.to_string())?;
     } else {
-        // Update single transaction
-        let clean_notes = notes.map(|n| n.replace("[TEMP] ", "")).unwrap_or_default();
         conn.execute(
-            "UPDATE transactions SET notes = ?1 WHERE id = ?2",
-            params![clean_notes, id]
+            "UPDATE transactions SET status = 'permanent' WHERE id = ?1",
+            params![id]
         ).map_err(|e| e.to_string())?;
     }
-    
-    Ok(())
-} 
 
- 
\ No newline at end of file
+    Ok(())
+}